@@ -5,7 +5,9 @@ import {
   wasmDeriveScrambleForEvent,
   wasmFreeMemoryForAllScrambleFinders,
   wasmRandomScrambleForEvent,
+  WasmTwipsSearch,
   wasmTwips,
+  wasmTwipsSolutions,
 } from "../dist/wasm/index";
 
 test("wasmRandomScrambleForEvent(…)", async () => {
@@ -46,6 +48,34 @@ test("wasmTwips(…)", async () => {
   assert(pattern.applyAlg(solution).isIdentical(kpuzzle.defaultPattern()));
 });
 
+test("wasmTwipsSolutions(…)", async () => {
+  const kpuzzle = await cube2x2x2.kpuzzle();
+  const pattern = kpuzzle.defaultPattern().applyAlg("L' U' L U F U F'");
+  const solutions = await wasmTwipsSolutions(kpuzzle.definition, pattern, {
+    generatorMoves: ["R", "U", "F"],
+    minNumSolutions: 3,
+  });
+  expect(solutions.length).toBe(3);
+  for (const solution of solutions) {
+    assert(pattern.applyAlg(solution).isIdentical(kpuzzle.defaultPattern()));
+  }
+});
+
+test("WasmTwipsSearch", async () => {
+  const kpuzzle = await cube2x2x2.kpuzzle();
+  const pattern = kpuzzle.defaultPattern().applyAlg("L' U' L U F U F'");
+  const search = await WasmTwipsSearch.create(kpuzzle.definition, pattern, {
+    generatorMoves: ["R", "U", "F"],
+    minNumSolutions: 3,
+  });
+  const solutions = [...search];
+  search.free();
+  expect(solutions.length).toBe(3);
+  for (const solution of solutions) {
+    assert(pattern.applyAlg(solution).isIdentical(kpuzzle.defaultPattern()));
+  }
+});
+
 test("wasmDeriveScrambleForEvent(…)", async () => {
   console.log("----------------");
   console.log("Deriving scrambles.");
@@ -2,19 +2,27 @@ use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::generator::generate;
 use clap_complete::{Generator, Shell};
 use cubing::alg::{Alg, Move};
+use cubing::kpuzzle::{KPattern, KPuzzle};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use std::io::stdout;
+use std::num::ParseFloatError;
 use std::path::PathBuf;
 use std::process::exit;
+use std::time::Duration;
 use twips::_internal::canonical_fsm::search_generators::Generators;
 use twips::_internal::notation::metric::TurnMetric;
+use twips::_internal::schreier_sims::reachability::ReachabilityCheck;
 use twips::_internal::search::search_logger::VerbosityLevel;
 use twips::experimental_lib_api::{KPuzzleSource, PatternSource};
 
 use twips::_internal::errors::{ArgumentError, TwipsError};
 use twips::_internal::search::iterative_deepening::continuation_condition::ContinuationCondition;
+use twips::_internal::search::iterative_deepening::search_checkpoint::SearchCheckpoint;
 use twips::_internal::search::iterative_deepening::solution_moves::alg_to_moves;
+use twips::_internal::search::prune_table_persistence::{
+    PruneTablePersistenceOptions, PruneTableWriteCondition,
+};
 use twips::_internal::search::prune_table_trait::Depth;
 use twips::scramble::{DerivationSalt, DerivationSeed, Puzzle};
 
@@ -42,10 +50,9 @@ pub enum CliCommand {
     /// Solve a known puzzle.
     SolveKnownPuzzle(SolveKnownPuzzleCommandArgs),
 
-    // TOOD: Detect identical pieces and warn/error (and give advice on how to run the "same" search with fully-distinguishable pieces).
     /// Run the Schreier-Sims algorithm to calculate the number of reachable patterns.
     ///
-    /// Warning: Does NOT account for identical pieces.
+    /// Warning: Does NOT account for identical pieces (a warning is printed if the definition has any).
     SchreierSims(SchreierSimsArgs),
     /// Enumerate the entire pattern graph and print antipodes.
     GodsAlgorithm(GodsAlgorithmArgs),
@@ -59,6 +66,12 @@ pub enum CliCommand {
     ScrambleFinder(ScrambleFinderArgs),
     /// Derive scrambles
     Derive(DeriveArgs),
+    /// Derive all scrambles for a competition, as JSON.
+    DeriveCompetition(DeriveCompetitionArgs),
+    /// Re-derive published scrambles and check that they match.
+    VerifyScrambles(VerifyScramblesArgs),
+    /// Generate, commit to, and obfuscate competition root seeds.
+    RootSeed(RootSeedArgs),
 
     /// Run an internal benchmark suite.
     Benchmark(BenchmarkArgs),
@@ -70,6 +83,7 @@ pub enum CliCommand {
 #[derive(Args, Debug, Default)]
 pub struct CommonSearchArgs {
     /// Check that a position is valid before attempting to solve it. This may take extra time or memory for large puzzles.
+    /// `auto` checks permutation parity and orientation, and only runs a full group membership test for small puzzles.
     #[clap(long/*, visible_alias = "checkbeforesolve" */)]
     pub check_before_solve: Option<EnableAutoAlwaysNeverValueEnum>,
 
@@ -80,7 +94,8 @@ pub struct CommonSearchArgs {
 
     /// Depth to start the pruning table. This can avoid multiple pruning table
     /// expansions that can already be anticipated by starting with a sufficient
-    /// depth.
+    /// depth. The pruning table keeps its initial size after this, so use
+    /// `--memory-MiB` to choose a larger one.
     #[clap(long/*, visible_alias = "startprunedepth" */, id = "DEPTH")]
     pub start_prune_depth: Option<Depth>,
 
@@ -146,6 +161,35 @@ pub struct SearchCommandOptionalArgs {
     #[clap(long/* , visible_short_alias = 't' */)]
     pub min_num_solutions: Option<usize>,
 
+    /// `jsonl` prints one JSON object per line for each solution, followed by
+    /// a summary object for each search.
+    #[clap(long, default_value = "text")]
+    pub output_format: SearchOutputFormat,
+
+    /// Stop searching (for each scramble) after this many seconds.
+    #[clap(long, value_name = "SECONDS", value_parser = duration_from_seconds)]
+    pub timeout: Option<Duration>,
+
+    /// Stop searching (for each scramble) after visiting this many nodes.
+    #[clap(long)]
+    pub max_nodes: Option<usize>,
+
+    /// Periodically write a checkpoint to this file, which can be passed to
+    /// `--resume` to continue the search if it is interrupted. A final
+    /// checkpoint is written when the search stops.
+    #[clap(long, value_name = "FILE")]
+    pub checkpoint: Option<PathBuf>,
+
+    /// How often to write a checkpoint for `--checkpoint`.
+    #[clap(long, value_name = "SECONDS", value_parser = duration_from_seconds, default_value = "60", requires = "checkpoint")]
+    pub checkpoint_interval: Duration,
+
+    /// Resume a search from a checkpoint file written by `--checkpoint`. The
+    /// other options (including the scramble) must be the same as for the
+    /// original search.
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["continue_search", "min_depth", "random_start"])]
+    pub resume: Option<PathBuf>,
+
     #[command(flatten)]
     pub generator_args: GeneratorArgs,
     #[command(flatten)]
@@ -180,6 +224,11 @@ fn puzzle_from_id(s: &str) -> Result<Puzzle, String> {
     Puzzle::try_from_id(s).map_err(|e| e.description)
 }
 
+fn duration_from_seconds(s: &str) -> Result<Duration, String> {
+    let seconds: f64 = s.parse().map_err(|e: ParseFloatError| e.to_string())?;
+    Duration::try_from_secs_f64(seconds).map_err(|e| e.to_string())
+}
+
 #[derive(Args, Debug, Default)]
 pub struct VerbosityArgs {
     #[clap(long)]
@@ -194,10 +243,14 @@ pub struct GeneratorArgs {
     #[clap(long, value_delimiter = ',')]
     pub generator_moves: Option<Vec<Move>>,
 
-    /// A comma-separated list of algs to use. All multiples of these
-    /// algs are considered. For example, `--algs U,F,R2` only permits
-    /// half-turns on R, and all possible turns on U and F.
-    #[clap(long, value_delimiter = ',')]
+    /// An alg to use as an atomic generator. All multiples of the alg are
+    /// considered. Pass this multiple times to use multiple algs (which may
+    /// contain commas themselves), e.g. `--generator-algs "R U R' U'"
+    /// --generator-algs "[R, U]"`. This can be combined with
+    /// `--generator-moves`.
+    ///
+    /// Solutions show each alg generator expanded back into the alg.
+    #[clap(long)]
     pub generator_algs: Option<Vec<Alg>>,
 }
 
@@ -215,13 +268,41 @@ impl GeneratorArgs {
 
 #[derive(Args, Debug, Default)]
 pub struct SearchPersistenceArgs {
+    /// Write prune tables to the cache dir, so that later searches with the
+    /// same puzzle, generators, and target pattern can skip filling them.
+    /// `auto` only writes tables that take a while to fill.
     #[clap(long, help_heading = "Persistence"/* , visible_alias = "writeprunetables" */)]
     pub write_prune_tables: Option<EnableAutoAlwaysNeverValueEnum>,
 
+    /// Folder for reading and writing prune tables. Defaults to
+    /// `$XDG_CACHE_HOME/twips/prune-tables` (or `~/.cache/twips/prune-tables`).
     #[clap(long, help_heading = "Persistence"/* , visible_alias = "cachedir" */)]
     pub cache_dir: Option<PathBuf>,
 }
 
+impl SearchPersistenceArgs {
+    pub fn prune_table_persistence_options(&self) -> Option<PruneTablePersistenceOptions> {
+        let cache_dir = match &self.cache_dir {
+            Some(cache_dir) => cache_dir.clone(),
+            None => {
+                let user_cache_dir = match std::env::var_os("XDG_CACHE_HOME") {
+                    Some(xdg_cache_home) => PathBuf::from(xdg_cache_home),
+                    None => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+                };
+                user_cache_dir.join("twips").join("prune-tables")
+            }
+        };
+        Some(PruneTablePersistenceOptions {
+            cache_dir,
+            write_condition: match self.write_prune_tables {
+                None | Some(EnableAutoAlwaysNeverValueEnum::Auto) => PruneTableWriteCondition::Auto,
+                Some(EnableAutoAlwaysNeverValueEnum::Never) => PruneTableWriteCondition::Never,
+                Some(EnableAutoAlwaysNeverValueEnum::Always) => PruneTableWriteCondition::Always,
+            },
+        })
+    }
+}
+
 #[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
 pub enum EnableAutoAlwaysNeverValueEnum {
     Auto,
@@ -251,6 +332,24 @@ impl Display for EnableAutoAlwaysNeverValueEnum {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum SearchOutputFormat {
+    #[default]
+    Text,
+    Jsonl,
+}
+
+/// Maps `--check-before-solve` (or the equivalent serve client arg), which defaults to `auto`.
+pub fn reachability_check(
+    check_before_solve: &Option<EnableAutoAlwaysNeverValueEnum>,
+) -> ReachabilityCheck {
+    match check_before_solve {
+        None | Some(EnableAutoAlwaysNeverValueEnum::Auto) => ReachabilityCheck::Auto,
+        Some(EnableAutoAlwaysNeverValueEnum::Never) => ReachabilityCheck::Never,
+        Some(EnableAutoAlwaysNeverValueEnum::Always) => ReachabilityCheck::Always,
+    }
+}
+
 #[derive(Args, Debug, Default)]
 pub struct PerformanceArgs {
     /// Defaults to the number of logical CPU cores available.
@@ -261,6 +360,16 @@ pub struct PerformanceArgs {
     pub memory_args: MemoryArgs,
 }
 
+impl PerformanceArgs {
+    pub fn get_num_threads(&self) -> usize {
+        self.num_threads.unwrap_or_else(|| {
+            std::thread::available_parallelism()
+                .map(|num_threads| num_threads.get())
+                .unwrap_or(1)
+        })
+    }
+}
+
 #[derive(Args, Debug, Default)]
 pub struct MemoryArgs {
     /// Memory to use in MiB. See `README.md` for advice on how to tune memory usage.
@@ -279,12 +388,14 @@ pub struct CompletionsArgs {
     shell: Shell,
 }
 
-// TODO: support moves arg?
 #[derive(Args, Debug)]
 pub struct SchreierSimsArgs {
     #[command(flatten)]
     pub def_args: DefOnlyArgs,
 
+    #[command(flatten)]
+    pub generator_args: GeneratorArgs,
+
     #[command(flatten)]
     pub performance_args: PerformanceArgs,
 }
@@ -325,6 +436,21 @@ pub struct TimingTestArgs {
     #[command(flatten)]
     pub metric_args: MetricArgs,
 
+    /// Number of random-state searches to run.
+    #[clap(long, default_value_t = 10)]
+    pub num_searches: usize,
+
+    /// Seed for the random patterns (64-char hex, with the same format as for
+    /// `twips derive`). Defaults to a fixed seed so that results are comparable
+    /// across runs.
+    #[clap(long)]
+    pub seed: Option<DerivationSeed>,
+
+    /// Stop each solution search at this depth. Random-state searches on large
+    /// puzzles can take a very long time to finish without this.
+    #[clap(long)]
+    pub max_depth: Option<Depth>,
+
     #[command(flatten)]
     pub performance_args: PerformanceArgs,
 }
@@ -340,6 +466,14 @@ pub struct CanonicalAlgsArgs {
     #[command(flatten)]
     pub metric_args: MetricArgs,
 
+    /// Enumerate canonical algs up to this depth (inclusive).
+    #[clap(long, default_value_t = 5)]
+    pub max_depth: usize,
+
+    /// Print every canonical alg (in addition to the counts for each depth).
+    #[clap(long)]
+    pub list_algs: bool,
+
     #[command(flatten)]
     pub performance_args: PerformanceArgs,
 }
@@ -353,11 +487,44 @@ pub struct MetricArgs {
 #[derive(Args, Debug)]
 pub struct ScrambleArgs {
     /// Event ID (WCA or unofficial)
-    pub event_id: String,
+    #[clap(required_unless_present = "def", conflicts_with = "def")]
+    pub event_id: Option<String>,
+
+    /// Generate random-state scrambles for an arbitrary definition instead of
+    /// an event. The solution for each pattern is found using an optimal
+    /// search, so this is only practical for smaller puzzles.
+    #[clap(long)]
+    pub def: Option<PathBuf>,
+
+    /// Moves to use for `--def`. All multiples of these moves are
+    /// considered. Defaults to all moves in the definition.
+    #[clap(long, value_delimiter = ',')]
+    pub generator_moves: Option<Vec<Move>>,
+
+    /// Generate random-move scrambles of this length for `--def` instead of
+    /// random-state scrambles. Moves are chosen so that scrambles have no
+    /// cancellations or redundant orderings of commuting moves.
+    #[clap(long)]
+    pub random_moves: Option<usize>,
+
+    /// Reject random-move scrambles that can be solved in fewer than this many moves.
+    #[clap(long)]
+    pub min_optimal_moves: Option<usize>,
+
+    /// Derivation seed for `--def` scrambles (64-char hex, with the same
+    /// format as for `twips derive`). Scramble #N is derived using the salt
+    /// `scrambleN`. Defaults to a seed from the thread RNG, which is printed
+    /// so that the scrambles can be reproduced.
+    #[clap(long)]
+    pub seed: Option<DerivationSeed>,
 
     /// Amount of scrambles
     #[clap(long, default_value_t = 1)]
     pub amount: usize,
+
+    /// Number of cubes in each scramble set (`333mbf` only).
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub cubes: Option<u32>,
 }
 
 #[derive(Args, Debug)]
@@ -421,7 +588,10 @@ pub struct RequiredDefArgs {
 
 #[derive(Args, Debug, Default)]
 pub struct ScrambleAndTargetPatternOptionalArgs {
-    /// Solve all the scrambles from the given file.
+    /// Solve all the scrambles from the given file. This may be a `.json` file
+    /// with a single pattern, a `.jsonl` file with one pattern per line, or a
+    /// `.txt` file with one scramble alg per line. For `.jsonl` and `.txt`
+    /// files, the same prune table is reused for all the scrambles.
     #[clap(help_heading = "Scramble input", group = "scramble_input")]
     pub scramble_file: Option<PathBuf>,
     /// Solve a single scramble specified directly as an argument.
@@ -429,8 +599,11 @@ pub struct ScrambleAndTargetPatternOptionalArgs {
     pub scramble_alg: Option<Alg>,
     // TODO: also allow an alg for this?
     /// Use the target pattern from the specified file instead of the default start pattern from the defintion.
+    /// The file may contain a single pattern or a JSON list of patterns. This
+    /// can be specified multiple times, in which case solutions are found for
+    /// reaching any of the target patterns.
     #[clap(long, help_heading = "Scramble input")]
-    pub experimental_target_pattern: Option<PathBuf>,
+    pub experimental_target_pattern: Vec<PathBuf>,
 }
 
 impl ScrambleAndTargetPatternOptionalArgs {
@@ -452,13 +625,23 @@ impl ScrambleAndTargetPatternOptionalArgs {
         }
     }
 
-    pub fn target_pattern(&self) -> PatternSource {
-        match &self.experimental_target_pattern {
-            Some(experimental_target_pattern) => {
-                PatternSource::FilePath(experimental_target_pattern.clone())
+    /// Returns `None` if no target pattern files were specified.
+    pub fn target_patterns(
+        &self,
+        kpuzzle: &KPuzzle,
+    ) -> Result<Option<Vec<KPattern>>, ArgumentError> {
+        if self.experimental_target_pattern.is_empty() {
+            return Ok(None);
+        }
+        let mut target_patterns = vec![];
+        for experimental_target_pattern in &self.experimental_target_pattern {
+            if let Some(kpatterns) =
+                PatternSource::FilePath(experimental_target_pattern.clone()).kpatterns(kpuzzle)?
+            {
+                target_patterns.extend(kpatterns);
             }
-            None => PatternSource::DefaultFromDefinition,
         }
+        Ok(Some(target_patterns))
     }
 }
 
@@ -482,6 +665,113 @@ pub struct DeriveArgs {
     /// - Level 7 must be a valid monoscramble event ID.
     #[clap(required = true, value_delimiter = '/')]
     pub derivation_salts: Vec<DerivationSalt>,
+
+    /// Derive a full scramble set with this many cubes (`333mbf` only). The
+    /// derivation salts must end at the attempt level (level 6), e.g.
+    /// `…/scrambles/333mbf/r1/g1/a1`.
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub cubes: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+pub struct DeriveCompetitionArgs {
+    /// Derivation seed (see `twips derive --help`). The second byte must be 0.
+    pub root_derivation_seed: DerivationSeed,
+
+    /// Auditor salt (level 1 of the salt hierarchy).
+    pub auditor_salt: DerivationSalt,
+
+    /// A JSON competition description, or a WCIF file.
+    ///
+    /// Each round can specify `groups`, `attempts`, `extras` (default: 2), and
+    /// `cubes` (`333mbf` only). For WCIF files, the number of groups and
+    /// attempts are taken from `scrambleSetCount` and `format`. Example:
+    ///
+    /// {"events": [{"id": "333", "rounds": [{"groups": 2, "attempts": 5}]}]}
+    pub competition_file: PathBuf,
+
+    /// Number of cubes per attempt for `333mbf` rounds that don't specify `cubes`.
+    #[clap(long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub multi_blind_cubes: Option<u32>,
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyScramblesArgs {
+    /// Root derivation seed (see `twips derive --help`).
+    ///
+    /// This may also be a sub-seed at level N (e.g. the competition seed at
+    /// level 1), in which case the first N salts of each derivation path are
+    /// assumed to match it and are skipped.
+    pub derivation_seed: DerivationSeed,
+
+    /// Published scrambles, either in the format output by `twips
+    /// derive-competition` or as a flat JSON list of objects with
+    /// `subevent`, `derivationPath`, and `scramble` fields.
+    pub scrambles_file: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct RootSeedArgs {
+    #[command(subcommand)]
+    pub command: RootSeedCommand,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RootSeedCommand {
+    /// Generate a new random root seed.
+    Generate(RootSeedGenerateArgs),
+    /// Print the public commitment for a root seed.
+    Commit(RootSeedInputArgs),
+    /// Check a revealed root seed against a previously published commitment.
+    Verify(RootSeedVerifyArgs),
+    /// Expand a root seed into an obfuscated file of 1024 lines.
+    Obfuscate(RootSeedObfuscateArgs),
+    /// Reconstruct a root seed from an obfuscated file.
+    Deobfuscate(RootSeedDeobfuscateArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct RootSeedGenerateArgs {
+    /// Write the root seed to this file in obfuscated form instead of printing
+    /// it. The commitment is printed instead.
+    #[clap(long)]
+    pub obfuscated_output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+#[group(required = true, multiple = false)]
+pub struct RootSeedInputArgs {
+    /// Root derivation seed (see `twips derive --help`). The second byte must be 0.
+    pub root_derivation_seed: Option<DerivationSeed>,
+
+    /// Read the root seed from an obfuscated file instead.
+    #[clap(long)]
+    pub obfuscated_file: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RootSeedVerifyArgs {
+    /// The previously published commitment.
+    #[clap(long)]
+    pub commitment: DerivationSeed,
+
+    #[command(flatten)]
+    pub root_seed_input_args: RootSeedInputArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct RootSeedObfuscateArgs {
+    /// Root derivation seed (see `twips derive --help`). The second byte must be 0.
+    pub root_derivation_seed: DerivationSeed,
+
+    /// Write to this file instead of printing to `stdout`.
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
+pub struct RootSeedDeobfuscateArgs {
+    pub obfuscated_file: PathBuf,
 }
 
 #[derive(Args, Debug, Default)]
@@ -548,15 +838,28 @@ pub struct ServeCommandArgs {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ServeClientArgs {
-    // TODO: moves, min num solutions
+    // TODO: moves
     // TODO: allow the client to set performance args (with bounds checks) and prune table (if enabled by server).
     pub check_before_solve: Option<EnableAutoAlwaysNeverValueEnum>,
     pub random_start: Option<bool>,
     pub min_depth: Option<Depth>,
     pub max_depth: Option<Depth>,
+    /// When streaming solutions, solutions are streamed until the search is
+    /// exhausted or the client disconnects if this is unspecified. Otherwise,
+    /// this defaults to 1.
+    pub min_num_solutions: Option<usize>,
     pub start_prune_depth: Option<Depth>,
+    /// If specified, the search is stopped after this many seconds.
+    pub timeout_seconds: Option<f64>,
+    /// If specified, the search is stopped after visiting this many nodes.
+    pub max_nodes: Option<usize>,
+    /// Resume the search from the `checkpoint` of a previous `finished` event.
+    /// This overrides `minDepth`, and cannot be combined with `randomStart`.
+    pub resume: Option<SearchCheckpoint>,
     pub quantum_metric: Option<bool>, // TODO: enum
     pub generator_moves: Option<Vec<Move>>,
+    /// Each alg is used as a single move during search, and expanded in solutions.
+    pub generator_algs: Option<Vec<Alg>>,
 }
 
 #[cfg(test)]
@@ -570,4 +873,25 @@ mod tests {
 
         TwipsArgs::command().debug_assert();
     }
+
+    #[test]
+    fn resume_conflicts_with_random_start() {
+        use clap::Parser;
+
+        let args = |extra_args: &[&str]| {
+            TwipsArgs::try_parse_from(
+                [
+                    "twips",
+                    "search",
+                    "--resume",
+                    "checkpoint.json",
+                    "samples/2x2x2/2x2x2.kpuzzle.json",
+                ]
+                .iter()
+                .chain(extra_args),
+            )
+        };
+        assert!(args(&[]).is_ok());
+        assert!(args(&["--random-start"]).is_err());
+    }
 }
@@ -18,12 +18,15 @@ pub fn benchmark(benchmark_args: &BenchmarkArgs) -> Result<(), TwipsError> {
         read_to_json(&benchmark_args.def_args.def_file).expect("Invalid definition"); // TODO: automatic error conversion.
     let kpuzzle = KPuzzle::try_new(def).expect("Invalid definition"); // TODO: automatic error conversion.
 
+    let kpuzzle_with_generator_moves = benchmark_args
+        .generator_args
+        .generators()
+        .kpuzzle_with_generator_moves(&kpuzzle)?;
+    let kpuzzle = kpuzzle_with_generator_moves.kpuzzle;
+
     let search_generators = SearchGenerators::try_new(
         &kpuzzle,
-        benchmark_args
-            .generator_args
-            .generators()
-            .enumerate_moves_for_kpuzzle(&kpuzzle),
+        kpuzzle_with_generator_moves.generator_moves,
         SearchGeneratorsConstructorOptions {
             metric: benchmark_args.metric_args.metric,
             random_start: None,
@@ -1,33 +1,47 @@
-use cubing::kpuzzle::{KPuzzle, KPuzzleDefinition};
-use twips::_internal::{
-    canonical_fsm::{
-        canonical_fsm::CanonicalFSM,
-        search_generators::{SearchGenerators, SearchGeneratorsConstructorOptions},
-    },
-    errors::TwipsError,
-    read_to_json::read_to_json,
+use cubing::alg::Alg;
+use twips::{
+    _internal::{errors::TwipsError, search::prune_table_trait::Depth},
+    experimental_lib_api::{CanonicalAlgs, CanonicalAlgsOptions, KPuzzleSource},
 };
 
 use crate::args::CanonicalAlgsArgs;
 
 pub fn canonical_algs(args: &CanonicalAlgsArgs) -> Result<(), TwipsError> {
-    let def: KPuzzleDefinition = read_to_json(&args.def_args.def_file)?;
-    let kpuzzle = KPuzzle::try_new(def).unwrap();
+    let kpuzzle = KPuzzleSource::FilePath(args.def_args.def_file.clone()).kpuzzle()?;
 
-    let search_generators = SearchGenerators::try_new(
+    let canonical_algs = CanonicalAlgs::try_new(
         &kpuzzle,
-        args.generator_args
-            .generators()
-            .enumerate_moves_for_kpuzzle(&kpuzzle),
-        SearchGeneratorsConstructorOptions {
+        CanonicalAlgsOptions {
+            generators: args.generator_args.generators(),
             metric: args.metric_args.metric,
-            random_start: Some(false),
+            ..Default::default()
         },
     )?;
 
-    let canonical_fsm = CanonicalFSM::try_new(kpuzzle, search_generators, Default::default())
-        .expect("Could not construct FSM");
-    dbg!(canonical_fsm);
+    let mut previous_num_canonical_algs: Option<usize> = None;
+    for depth in 0..=args.max_depth {
+        let mut print_alg = |alg: &Alg| println!("{}", alg);
+        let counts = canonical_algs.enumerate_depth(
+            Depth(depth),
+            if args.list_algs {
+                Some(&mut print_alg)
+            } else {
+                None
+            },
+        );
+        let branching_factor = match previous_num_canonical_algs {
+            Some(previous_num_canonical_algs) if previous_num_canonical_algs > 0 => format!(
+                " (branching factor: {:.3})",
+                counts.num_canonical_algs as f64 / previous_num_canonical_algs as f64
+            ),
+            _ => "".to_owned(),
+        };
+        println!(
+            "// Depth {}: {} canonical algs{}, {} distinct patterns",
+            depth, counts.num_canonical_algs, branching_factor, counts.num_distinct_patterns
+        );
+        previous_num_canonical_algs = Some(counts.num_canonical_algs);
+    }
 
     Ok(())
 }
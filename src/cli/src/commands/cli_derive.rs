@@ -1,6 +1,6 @@
 use twips::{
     _internal::errors::{ArgumentError, TwipsError},
-    scramble::{derive_scramble_for_event_seeded, Event},
+    scramble::{derive_multi_blind_scramble_set_seeded, derive_scramble_for_event_seeded, Event},
 };
 
 use crate::args::DeriveArgs;
@@ -14,8 +14,25 @@ pub fn cli_derive(args: &DeriveArgs) -> Result<(), TwipsError> {
     }
 
     // Validate the event arg.
-    let _ = Event::try_from(args.derivation_salts[2].unhashed_salt().as_str())?;
+    let event = Event::try_from(args.derivation_salts[2].unhashed_salt().as_str())?;
 
+    if let Some(num_cubes) = args.cubes {
+        if event != Event::Cube3x3x3MultiBlind {
+            return Err(
+                Into::<ArgumentError>::into("`--cubes` is only supported for `333mbf`.").into(),
+            );
+        }
+        for scramble in derive_multi_blind_scramble_set_seeded(
+            &args.root_derivation_seed,
+            &args.derivation_salts,
+            num_cubes as usize,
+        )
+        .map_err(|description| ArgumentError { description })?
+        {
+            println!("{}", scramble);
+        }
+        return Ok(());
+    }
     let subevent = Event::try_from(args.derivation_salts[6].unhashed_salt().as_str())?;
     println!(
         "{}",
@@ -0,0 +1,323 @@
+use std::{fs::read, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+use twips::{
+    _internal::errors::{ArgumentError, TwipsError},
+    scramble::{derive_scramble_for_event_seeded, DerivationSalt, DerivationSeed, Event},
+};
+
+use crate::args::DeriveCompetitionArgs;
+
+// Matches the TNoodle default.
+const DEFAULT_NUM_EXTRAS: usize = 2;
+
+/// Either our own competition description, or a WCIF file (whose other fields are ignored).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompetitionDescription {
+    events: Vec<EventDescription>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventDescription {
+    id: String,
+    rounds: Vec<RoundDescription>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RoundDescription {
+    groups: Option<usize>,
+    attempts: Option<usize>,
+    extras: Option<usize>,
+    cubes: Option<usize>,
+
+    /// WCIF fallback for `groups`.
+    scramble_set_count: Option<usize>,
+    /// WCIF fallback for `attempts`.
+    format: Option<String>,
+}
+
+/// Also used as the input for `twips verify-scrambles`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DerivedCompetition {
+    pub(crate) events: Vec<DerivedEvent>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DerivedEvent {
+    pub(crate) id: String,
+    pub(crate) rounds: Vec<DerivedRound>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DerivedRound {
+    pub(crate) id: String,
+    pub(crate) groups: Vec<DerivedGroup>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DerivedGroup {
+    pub(crate) id: String,
+    pub(crate) attempts: Vec<DerivedAttempt>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DerivedAttempt {
+    pub(crate) id: String,
+    pub(crate) scrambles: Vec<DerivedScramble>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DerivedScramble {
+    pub(crate) subevent: String,
+    pub(crate) derivation_path: String,
+    pub(crate) scramble: String,
+}
+
+fn salt(salt: &str) -> Result<DerivationSalt, ArgumentError> {
+    DerivationSalt::from_str(salt).map_err(|description| ArgumentError { description })
+}
+
+fn derivation_path(salts: &[DerivationSalt]) -> String {
+    salts
+        .iter()
+        .map(|salt| salt.unhashed_salt().as_str())
+        .collect::<Vec<&str>>()
+        .join("/")
+}
+
+fn num_attempts_for_wcif_format(format: &str) -> Option<usize> {
+    match format {
+        "1" => Some(1),
+        "2" => Some(2),
+        "3" => Some(3),
+        "m" => Some(3),
+        "a" => Some(5),
+        _ => None,
+    }
+}
+
+/// Derives a scramble for each (sub)event scramble salt (level 8) of the hierarchy.
+fn derive_scrambles(
+    root_derivation_seed: &DerivationSeed,
+    attempt_salts: &[DerivationSalt],
+    subevent: Event,
+    num_scrambles: usize,
+) -> Result<Vec<DerivedScramble>, ArgumentError> {
+    (1..=num_scrambles)
+        .map(|i| {
+            let salts = [
+                attempt_salts.to_vec(),
+                vec![salt(subevent.id())?, salt(&format!("sub{}", i))?],
+            ]
+            .concat();
+            let scramble = derive_scramble_for_event_seeded(root_derivation_seed, &salts, subevent)
+                .map_err(|description| ArgumentError { description })?;
+            Ok(DerivedScramble {
+                subevent: subevent.id().to_owned(),
+                derivation_path: derivation_path(&salts),
+                scramble: scramble.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn cli_derive_competition(args: &DeriveCompetitionArgs) -> Result<(), TwipsError> {
+    if args.root_derivation_seed.level() != 0 {
+        return Err(Into::<ArgumentError>::into(
+            "Root derivation seed must be at level 0 (second byte must be `0x00`).",
+        )
+        .into());
+    }
+
+    let competition_file_bytes = read(&args.competition_file).map_err(|e| ArgumentError {
+        description: format!("Could not read competition file: {}", e),
+    })?;
+    let competition_description: CompetitionDescription =
+        serde_json::from_slice(&competition_file_bytes).map_err(|e| ArgumentError {
+            description: format!("Invalid competition file: {}", e),
+        })?;
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&derive_competition(
+            &args.root_derivation_seed,
+            &args.auditor_salt,
+            &competition_description,
+            args.multi_blind_cubes.map(|num_cubes| num_cubes as usize),
+        )?)
+        .expect("Could not serialize derived scrambles.")
+    );
+    Ok(())
+}
+
+fn derive_competition(
+    root_derivation_seed: &DerivationSeed,
+    auditor_salt: &DerivationSalt,
+    competition_description: &CompetitionDescription,
+    default_num_multi_blind_cubes: Option<usize>,
+) -> Result<DerivedCompetition, TwipsError> {
+    let mut derived_events = Vec::<DerivedEvent>::default();
+    for event_description in &competition_description.events {
+        let event = Event::try_from(event_description.id.as_str())?;
+        let mut derived_rounds = Vec::<DerivedRound>::default();
+        for (round_index, round_description) in event_description.rounds.iter().enumerate() {
+            let round_id = format!("r{}", round_index + 1);
+            let num_groups = round_description
+                .groups
+                .or(round_description.scramble_set_count)
+                .unwrap_or(1);
+            let Some(num_attempts) = round_description.attempts.or(round_description
+                .format
+                .as_deref()
+                .and_then(num_attempts_for_wcif_format))
+            else {
+                return Err(ArgumentError {
+                    description: format!(
+                        "Could not determine the number of attempts for `{}` round `{}`.",
+                        event, round_id
+                    ),
+                }
+                .into());
+            };
+            let num_extras = round_description.extras.unwrap_or(DEFAULT_NUM_EXTRAS);
+            let attempt_ids = (1..=num_attempts)
+                .map(|i| format!("a{}", i))
+                .chain((1..=num_extras).map(|i| format!("e{}", i)));
+
+            let mut derived_groups = Vec::<DerivedGroup>::default();
+            for group_index in 0..num_groups {
+                let group_id = format!("g{}", group_index + 1);
+                let mut derived_attempts = Vec::<DerivedAttempt>::default();
+                for attempt_id in attempt_ids.clone() {
+                    let attempt_salts = vec![
+                        auditor_salt.clone(),
+                        salt("scrambles")?,
+                        salt(event.id())?,
+                        salt(&round_id)?,
+                        salt(&group_id)?,
+                        salt(&attempt_id)?,
+                    ];
+                    let scrambles = if event == Event::Cube3x3x3MultiBlind {
+                        // Each cube is a `333bf` subevent.
+                        let Some(num_cubes) =
+                            round_description.cubes.or(default_num_multi_blind_cubes)
+                        else {
+                            return Err(Into::<ArgumentError>::into(
+                                "`333mbf` rounds must specify `cubes` (or pass `--multi-blind-cubes`).",
+                            )
+                            .into());
+                        };
+                        derive_scrambles(
+                            root_derivation_seed,
+                            &attempt_salts,
+                            Event::Cube3x3x3Blindfolded,
+                            num_cubes,
+                        )?
+                    } else {
+                        derive_scrambles(root_derivation_seed, &attempt_salts, event, 1)?
+                    };
+                    eprintln!(
+                        "// Derived: {}/{}/{}/{}",
+                        event, round_id, group_id, attempt_id
+                    );
+                    derived_attempts.push(DerivedAttempt {
+                        id: attempt_id,
+                        scrambles,
+                    });
+                }
+                derived_groups.push(DerivedGroup {
+                    id: group_id,
+                    attempts: derived_attempts,
+                });
+            }
+            derived_rounds.push(DerivedRound {
+                id: round_id,
+                groups: derived_groups,
+            });
+        }
+        derived_events.push(DerivedEvent {
+            id: event.id().to_owned(),
+            rounds: derived_rounds,
+        });
+    }
+
+    Ok(DerivedCompetition {
+        events: derived_events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use twips::{_internal::errors::TwipsError, scramble::DerivationSeed};
+
+    use super::{derive_competition, salt, CompetitionDescription};
+
+    const ROOT_DERIVATION_SEED: &str =
+        "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67";
+    const AUDITOR_SALT: &str = "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF";
+
+    fn competition_description(json: &str) -> CompetitionDescription {
+        serde_json::from_str(json).expect("Invalid competition description.")
+    }
+
+    #[test]
+    fn derived_paths() -> Result<(), TwipsError> {
+        let derived_competition = derive_competition(
+            &DerivationSeed::from_str(ROOT_DERIVATION_SEED).unwrap(),
+            &salt(AUDITOR_SALT)?,
+            &competition_description(
+                r#"{"events": [{"id": "222", "rounds": [{"attempts": 1, "extras": 1}]}]}"#,
+            ),
+            None,
+        )?;
+        let scrambles: Vec<(String, String)> = derived_competition
+            .events
+            .into_iter()
+            .flat_map(|event| event.rounds)
+            .flat_map(|round| round.groups)
+            .flat_map(|group| group.attempts)
+            .flat_map(|attempt| attempt.scrambles)
+            .map(|scramble| (scramble.derivation_path, scramble.scramble))
+            .collect();
+        // The scrambles match `twips derive` for the same paths.
+        assert_eq!(
+            scrambles,
+            vec![
+                (
+                    format!("{}/scrambles/222/r1/g1/a1/222/sub1", AUDITOR_SALT),
+                    "F L' U F L2 F R' F U' R' U'".to_owned()
+                ),
+                (
+                    format!("{}/scrambles/222/r1/g1/e1/222/sub1", AUDITOR_SALT),
+                    "F U2 F R U L F' L2 U' L' U'".to_owned()
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn multi_blind_requires_cubes() -> Result<(), TwipsError> {
+        assert!(derive_competition(
+            &DerivationSeed::from_str(ROOT_DERIVATION_SEED).unwrap(),
+            &salt(AUDITOR_SALT)?,
+            &competition_description(
+                r#"{"events": [{"id": "333mbf", "rounds": [{"attempts": 1}]}]}"#,
+            ),
+            None,
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use twips::{
+    _internal::errors::{ArgumentError, TwipsError},
+    scramble::{
+        competition_seed_commitment, deobfuscate_root_derivation_seed,
+        obfuscate_root_derivation_seed, verify_competition_seed_commitment, DerivationSeed,
+    },
+};
+
+use crate::args::{RootSeedArgs, RootSeedCommand, RootSeedInputArgs};
+
+fn read_obfuscated_file(path: &Path) -> Result<DerivationSeed, ArgumentError> {
+    let obfuscated = read_to_string(path).map_err(|e| ArgumentError {
+        description: format!("Could not read obfuscated file: {}", e),
+    })?;
+    deobfuscate_root_derivation_seed(&obfuscated)
+}
+
+fn write_obfuscated_file(path: &Path, obfuscated: &str) -> Result<(), ArgumentError> {
+    write(path, obfuscated).map_err(|e| ArgumentError {
+        description: format!("Could not write obfuscated file: {}", e),
+    })
+}
+
+fn root_derivation_seed(args: &RootSeedInputArgs) -> Result<DerivationSeed, ArgumentError> {
+    match (&args.root_derivation_seed, &args.obfuscated_file) {
+        (Some(root_derivation_seed), None) => Ok(*root_derivation_seed),
+        (None, Some(obfuscated_file)) => read_obfuscated_file(obfuscated_file),
+        // `clap` enforces that exactly one is present.
+        _ => Err("Specify either a root seed or `--obfuscated-file`.".into()),
+    }
+}
+
+pub fn cli_root_seed(args: &RootSeedArgs) -> Result<(), TwipsError> {
+    match &args.command {
+        RootSeedCommand::Generate(generate_args) => {
+            let root_derivation_seed = DerivationSeed::generate_root();
+            match &generate_args.obfuscated_output {
+                Some(path) => {
+                    write_obfuscated_file(
+                        path,
+                        &obfuscate_root_derivation_seed(&root_derivation_seed, rand::rng())?,
+                    )?;
+                    eprintln!("// Wrote obfuscated root seed to: {}", path.display());
+                    eprintln!("// Commitment:");
+                    println!("{}", competition_seed_commitment(&root_derivation_seed)?);
+                }
+                None => println!("{}", root_derivation_seed),
+            }
+        }
+        RootSeedCommand::Commit(root_seed_input_args) => {
+            let root_derivation_seed = root_derivation_seed(root_seed_input_args)?;
+            println!("{}", competition_seed_commitment(&root_derivation_seed)?);
+        }
+        RootSeedCommand::Verify(verify_args) => {
+            let root_derivation_seed = root_derivation_seed(&verify_args.root_seed_input_args)?;
+            if !verify_competition_seed_commitment(&root_derivation_seed, &verify_args.commitment)?
+            {
+                return Err(Into::<ArgumentError>::into(
+                    "Root seed does NOT match the commitment.",
+                )
+                .into());
+            }
+            println!("Root seed matches the commitment.");
+        }
+        RootSeedCommand::Obfuscate(obfuscate_args) => {
+            let obfuscated =
+                obfuscate_root_derivation_seed(&obfuscate_args.root_derivation_seed, rand::rng())?;
+            match &obfuscate_args.output {
+                Some(path) => write_obfuscated_file(path, &obfuscated)?,
+                None => println!("{}", obfuscated),
+            }
+        }
+        RootSeedCommand::Deobfuscate(deobfuscate_args) => {
+            println!(
+                "{}",
+                read_obfuscated_file(&deobfuscate_args.obfuscated_file)?
+            );
+        }
+    }
+    Ok(())
+}
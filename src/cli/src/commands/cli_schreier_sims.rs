@@ -0,0 +1,50 @@
+use twips::{
+    _internal::errors::TwipsError,
+    experimental_lib_api::{schreier_sims, KPuzzleSource, SchreierSimsOptions},
+};
+
+use crate::args::SchreierSimsArgs;
+
+pub fn cli_schreier_sims(schreier_sims_args: SchreierSimsArgs) -> Result<(), TwipsError> {
+    let kpuzzle_source: KPuzzleSource = schreier_sims_args.def_args.into();
+    let kpuzzle = kpuzzle_source.kpuzzle()?;
+    let result = schreier_sims(
+        &kpuzzle,
+        SchreierSimsOptions {
+            generators: schreier_sims_args.generator_args.generators(),
+            ..Default::default()
+        },
+    )?;
+
+    if !result.orbits_with_identical_pieces.is_empty() {
+        eprintln!("WARNING: The default pattern does not distinguish all pieces:");
+        for orbit in &result.orbits_with_identical_pieces {
+            eprintln!(
+                "- {}: {} distinguishable piece{} out of {}{}",
+                orbit.orbit_name,
+                orbit.num_distinguishable_pieces,
+                if orbit.num_distinguishable_pieces == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                orbit.num_pieces,
+                if orbit.has_ignored_orientations {
+                    " (some orientations are ignored)"
+                } else {
+                    ""
+                }
+            );
+        }
+        eprintln!("The group order below treats all pieces as distinguishable, so it is only an upper bound on the number of reachable patterns.");
+        eprintln!("To get an exact count, run this on a definition whose default pattern uses a distinct value for every piece and no `orientationMod`.");
+        eprintln!();
+    }
+
+    println!(
+        "Group order: {} ({})",
+        result.group_order,
+        result.group_order.factorization()
+    );
+    Ok(())
+}
@@ -1,13 +1,22 @@
-use std::time::Instant;
+use std::{str::FromStr, time::Instant};
 
 use cubing::alg::experimental_twizzle_link::{
     experimental_twizzle_link, ExperimentalTwizzleLinkParameters,
 };
 use twips::{
-    _internal::errors::TwipsError,
+    _internal::{
+        canonical_fsm::search_generators::Generators,
+        errors::{ArgumentError, TwipsError},
+        search::move_count::MoveCount,
+    },
+    experimental_lib_api::{
+        derive_random_move_scramble, derive_random_state_scramble, KPuzzleSource,
+        RandomMoveScrambleOptions, RandomStateScrambleOptions,
+    },
     scramble::{
-        experimental_scramble_finder_filter_and_or_search, random_scramble_for_event,
-        solve_known_puzzle, Event, ExperimentalFilterAndOrSearchOptions,
+        experimental_scramble_finder_filter_and_or_search, random_multi_blind_scramble_set,
+        random_scramble_for_event, solve_known_puzzle, DerivationSalt, DerivationSeed, Event,
+        ExperimentalFilterAndOrSearchOptions,
     },
 };
 
@@ -16,19 +25,91 @@ use crate::args::{
 };
 
 pub fn cli_scramble(args: &ScrambleArgs) -> Result<(), TwipsError> {
-    let event_id = args.event_id.as_str();
-    let event = Event::try_from(event_id)?;
+    let kpuzzle = match &args.def {
+        Some(def_file) => Some(KPuzzleSource::FilePath(def_file.clone()).kpuzzle()?),
+        None => None,
+    };
+    let event = match &args.event_id {
+        Some(event_id) => Some(Event::try_from(event_id.as_str())?),
+        None => None,
+    };
+    if kpuzzle.is_none()
+        && (args.generator_moves.is_some() || args.random_moves.is_some() || args.seed.is_some())
+    {
+        return Err(Into::<ArgumentError>::into(
+            "`--generator-moves`, `--random-moves`, and `--seed` are only supported with `--def`.",
+        )
+        .into());
+    }
+    if args.random_moves.is_none() && args.min_optimal_moves.is_some() {
+        return Err(Into::<ArgumentError>::into(
+            "`--min-optimal-moves` is only supported with `--random-moves`.",
+        )
+        .into());
+    }
+    if args.cubes.is_some() && event != Some(Event::Cube3x3x3MultiBlind) {
+        return Err(
+            Into::<ArgumentError>::into("`--cubes` is only supported for `333mbf`.").into(),
+        );
+    }
+
+    let generators = || match &args.generator_moves {
+        Some(generator_moves) => Generators::from(generator_moves.clone()),
+        None => Generators::Default,
+    };
+
+    // Only `--def` scrambles are derived from a seed.
+    let derivation_seed = kpuzzle.is_some().then(|| {
+        args.seed.unwrap_or_else(|| {
+            let seed = DerivationSeed::from_thread_rng();
+            eprintln!("Derivation seed from thread RNG: {}", seed);
+            seed
+        })
+    });
 
     let total_start_time = Instant::now();
     for i in 1..=args.amount {
         let current_scramble_start_time = Instant::now();
-        let scramble = random_scramble_for_event(event)?;
+        let scrambles = match (&kpuzzle, event, args.cubes) {
+            (Some(kpuzzle), _, _) => {
+                let salt = DerivationSalt::from_str(&format!("scramble{}", i))
+                    .map_err(|description| ArgumentError { description })?;
+                // `derivation_seed` is always set for `--def`.
+                let derivation_seed = derivation_seed.unwrap().derive(&salt);
+                vec![match args.random_moves {
+                    Some(num_random_moves) => derive_random_move_scramble(
+                        kpuzzle,
+                        RandomMoveScrambleOptions {
+                            generators: generators(),
+                            num_random_moves,
+                            min_optimal_solution_move_count: args.min_optimal_moves.map(MoveCount),
+                            ..Default::default()
+                        },
+                        derivation_seed,
+                    )?,
+                    None => derive_random_state_scramble(
+                        kpuzzle,
+                        RandomStateScrambleOptions {
+                            generators: generators(),
+                            ..Default::default()
+                        },
+                        derivation_seed,
+                    )?,
+                }]
+            }
+            (None, _, Some(num_cubes)) => random_multi_blind_scramble_set(num_cubes as usize)?,
+            (None, Some(event), None) => vec![random_scramble_for_event(event)?],
+            // `clap` enforces that either an event or a definition is present.
+            (None, None, None) => unreachable!(),
+        };
         eprintln!(
             "// Scramble #{} found in: {:?}",
             i,
             Instant::now() - current_scramble_start_time
         );
-        println!("{}", scramble);
+        for scramble in scrambles {
+            println!("{}", scramble);
+        }
         let elapsed_duration = Instant::now() - total_start_time;
         eprintln!(
             "Found {} scramble{} in {:?} so far (average: {:?} per scramble)",
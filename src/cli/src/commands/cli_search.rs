@@ -1,63 +1,347 @@
+use std::{
+    fs::{rename, write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use cubing::{alg::Alg, kpuzzle::KPuzzle};
+use instant::Instant;
+use serde::Serialize;
 use twips::{
     _internal::{
-        errors::TwipsError, search::iterative_deepening::individual_search::IndividualSearchOptions,
+        canonical_fsm::search_generators::Generators,
+        errors::{ArgumentError, TwipsError},
+        notation::metric::TurnMetric,
+        read_to_json::read_to_json,
+        search::{
+            iterative_deepening::{
+                individual_search::{IndividualSearchOptions, SearchTerminationReason},
+                search_checkpoint::{SearchCheckpoint, SearchCheckpointer},
+            },
+            prune_table_trait::{Depth, PruneTable},
+            recursive_work_tracker::RecursiveWorkDepthStats,
+        },
     },
-    experimental_lib_api::{search, KPuzzleSource, PatternSource, SearchOptions},
+    experimental_lib_api::{search, BatchSearch, KPuzzleSource, PatternSource, SearchOptions},
+};
+
+use crate::{
+    args::{reachability_check, SearchCommandArgs, SearchOutputFormat},
+    commands::cli_timing_test::DepthTiming,
 };
 
-use crate::args::SearchCommandArgs;
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SolutionOutput {
+    /// Only present for scramble files with multiple scrambles.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scramble_index: Option<usize>,
+    alg: String,
+    move_count: usize,
+    metric: String,
+    depth: Depth,
+    elapsed_seconds: f64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PruneTableOutput {
+    approximate_num_bytes: usize,
+    depths: Vec<DepthTiming>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SummaryOutput {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scramble_index: Option<usize>,
+    num_solutions: usize,
+    num_recursive_calls: usize,
+    depths: Vec<DepthTiming>,
+    prune_table: PruneTableOutput,
+    termination_reason: Option<SearchTerminationReason>,
+    elapsed_seconds: f64,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum SearchOutputLine {
+    Solution(SolutionOutput),
+    Summary(SummaryOutput),
+}
+
+/// Prints the solutions (and, for `jsonl`, a summary) for a single search pattern.
+struct SearchOutput {
+    output_format: SearchOutputFormat,
+    metric: TurnMetric,
+    scramble_index: Option<usize>,
+    start_time: Instant,
+    num_solutions: usize,
+}
+
+impl SearchOutput {
+    fn new(
+        output_format: SearchOutputFormat,
+        metric: TurnMetric,
+        scramble_index: Option<usize>,
+    ) -> Self {
+        Self {
+            output_format,
+            metric,
+            scramble_index,
+            start_time: Instant::now(),
+            num_solutions: 0,
+        }
+    }
+
+    fn print_solution(&mut self, solution: &Alg, depth: Depth) {
+        self.num_solutions += 1;
+        match self.output_format {
+            SearchOutputFormat::Text => println!(
+                "{} // solution #{} ({} nodes)",
+                solution,
+                self.num_solutions,
+                solution.nodes.len()
+            ),
+            SearchOutputFormat::Jsonl => {
+                print_json_line(&SearchOutputLine::Solution(SolutionOutput {
+                    scramble_index: self.scramble_index,
+                    alg: solution.to_string(),
+                    move_count: self.metric.move_count(solution).0,
+                    metric: self.metric.to_string(),
+                    depth,
+                    elapsed_seconds: (Instant::now() - self.start_time).as_secs_f64(),
+                }))
+            }
+        }
+    }
+
+    fn print_summary(
+        &self,
+        recursive_work_depth_stats: &[RecursiveWorkDepthStats],
+        prune_table: &dyn PruneTable<KPuzzle>,
+        termination_reason: Option<SearchTerminationReason>,
+    ) {
+        let elapsed = Instant::now() - self.start_time;
+        match self.output_format {
+            SearchOutputFormat::Text => {
+                if let Some(termination_reason) =
+                    termination_reason.filter(|reason| reason.is_interrupted())
+                {
+                    println!("// search stopped: {}", termination_reason);
+                }
+                if let Some(scramble_index) = self.scramble_index {
+                    println!(
+                        "// scramble #{} search duration: {:?}",
+                        scramble_index + 1,
+                        elapsed
+                    );
+                }
+            }
+            SearchOutputFormat::Jsonl => {
+                let depths: Vec<DepthTiming> = recursive_work_depth_stats
+                    .iter()
+                    .map(DepthTiming::from)
+                    .collect();
+                print_json_line(&SearchOutputLine::Summary(SummaryOutput {
+                    scramble_index: self.scramble_index,
+                    num_solutions: self.num_solutions,
+                    num_recursive_calls: depths.iter().map(|depth| depth.num_recursive_calls).sum(),
+                    depths,
+                    prune_table: PruneTableOutput {
+                        approximate_num_bytes: prune_table.approximate_num_bytes(),
+                        depths: prune_table
+                            .recursive_work_depth_stats()
+                            .iter()
+                            .map(DepthTiming::from)
+                            .collect(),
+                    },
+                    termination_reason,
+                    elapsed_seconds: elapsed.as_secs_f64(),
+                }))
+            }
+        }
+    }
+}
+
+fn print_json_line(search_output_line: &SearchOutputLine) {
+    println!(
+        "{}",
+        serde_json::to_string(search_output_line).expect("Could not serialize search output.")
+    );
+}
+
+fn write_checkpoint_file(
+    checkpoint_file: &Path,
+    checkpoint: &SearchCheckpoint,
+) -> Result<(), ArgumentError> {
+    let write_error = |e: std::io::Error| ArgumentError {
+        description: format!("Could not write checkpoint file: {}", e),
+    };
+    let checkpoint_json =
+        serde_json::to_string(checkpoint).expect("Could not serialize checkpoint.");
+    // Write to a temporary file first so that an interrupted write never leaves a truncated checkpoint.
+    let mut temp_file_path = checkpoint_file.as_os_str().to_owned();
+    temp_file_path.push(format!(".partial-{}", std::process::id()));
+    write(&temp_file_path, checkpoint_json).map_err(write_error)?;
+    rename(&temp_file_path, checkpoint_file).map_err(write_error)
+}
+
+fn search_checkpointer(
+    checkpoint_file: PathBuf,
+    search_command_args: &SearchCommandArgs,
+) -> SearchCheckpointer {
+    SearchCheckpointer {
+        interval: search_command_args.optional.checkpoint_interval,
+        checkpoint_fn: Arc::new(move |checkpoint| {
+            // A failed checkpoint shouldn't abort a long search.
+            if let Err(e) = write_checkpoint_file(&checkpoint_file, checkpoint) {
+                eprintln!("{}", e.description);
+            }
+        }),
+    }
+}
 
 pub fn cli_search(search_command_args: SearchCommandArgs) -> Result<(), TwipsError> {
-    let search_start_time: std::time::Instant = instant::Instant::now();
-    let kpuzzle: KPuzzleSource = search_command_args.def_args.def_args.into();
+    let search_start_time: std::time::Instant = Instant::now();
+    let kpuzzle = KPuzzleSource::FilePath(search_command_args.def_args.def_args.def_file.clone());
     let kpuzzle = kpuzzle.kpuzzle()?;
 
     let search_pattern: PatternSource = search_command_args
         .optional
         .scramble_and_target_pattern_optional_args
         .search_pattern();
-    let solutions = search(
-        &kpuzzle,
-        &search_pattern
-            .kpattern(&kpuzzle)?
-            .unwrap_or_else(|| kpuzzle.default_pattern()),
-        SearchOptions {
-            // TODO: allow mapping to `None`
-            target_pattern: search_command_args
-                .optional
-                .scramble_and_target_pattern_optional_args
-                .target_pattern()
-                .kpattern(&kpuzzle)?,
-            generators: search_command_args.optional.generator_args.generators(),
-            metric: search_command_args.optional.metric_args.metric,
-            random_start: Some(search_command_args.optional.search_args.random_start),
-            verbosity: search_command_args.optional.verbosity_args.verbosity,
-            individual_search_options: IndividualSearchOptions {
-                min_num_solutions: search_command_args.optional.min_num_solutions,
-                min_depth_inclusive: search_command_args.optional.search_args.min_depth,
-                max_depth_exclusive: search_command_args.optional.search_args.max_depth,
-                canonical_fsm_pre_moves: None,
-                canonical_fsm_post_moves: None,
-                root_continuation_condition: search_command_args
-                    .optional
-                    .search_args
-                    .continuation_condition()?,
-            },
-        },
-    )?;
-    let mut solution_index = 0;
-    for solution in solutions {
-        solution_index += 1;
-        println!(
-            "{} // solution #{} ({} nodes)",
-            solution,
-            solution_index,
-            solution.nodes.len()
-        )
+    let mut search_options = search_options(&search_command_args, &kpuzzle)?;
+    let output_format = search_command_args.optional.output_format;
+    let metric = search_options.metric.unwrap_or_default();
+    if search_pattern.is_pattern_list_file() {
+        if search_command_args.optional.checkpoint.is_some()
+            || search_command_args.optional.resume.is_some()
+        {
+            return Err(ArgumentError::from(
+                "`--checkpoint` and `--resume` are not supported for scramble files with multiple scrambles.",
+            )
+            .into());
+        }
+        // Otherwise, we would never move on from the first scramble.
+        search_options
+            .individual_search_options
+            .min_num_solutions
+            .get_or_insert(1);
+        let search_patterns = search_pattern.kpatterns(&kpuzzle)?.unwrap_or_default();
+        // Reuse the same search (and its prune table) for every scramble.
+        let mut batch_search = BatchSearch::try_new(&kpuzzle, search_options)?;
+        for (scramble_index, search_pattern) in search_patterns.iter().enumerate() {
+            if let SearchOutputFormat::Text = output_format {
+                println!("// scramble #{}", scramble_index + 1);
+            }
+            let mut search_output = SearchOutput::new(output_format, metric, Some(scramble_index));
+            let mut solutions = batch_search.search(search_pattern)?;
+            while let Some(solution) = solutions.next() {
+                // The checkpoint is at the depth of the latest solution.
+                search_output.print_solution(&solution, solutions.checkpoint().depth);
+            }
+            search_output.print_summary(
+                solutions.recursive_work_depth_stats(),
+                solutions.prune_table(),
+                solutions.termination_reason(),
+            );
+        }
+    } else {
+        let mut search_output = SearchOutput::new(output_format, metric, None);
+        let mut solutions = search(
+            &kpuzzle,
+            &search_pattern
+                .kpattern(&kpuzzle)?
+                .unwrap_or_else(|| kpuzzle.default_pattern()),
+            search_options,
+        )?;
+        while let Some(solution) = solutions.next() {
+            // The checkpoint is at the depth of the latest solution.
+            search_output.print_solution(&solution, solutions.checkpoint().depth);
+        }
+        search_output.print_summary(
+            solutions.recursive_work_depth_stats(),
+            solutions.prune_table(),
+            solutions.termination_reason(),
+        );
+        if let Some(checkpoint_file) = &search_command_args.optional.checkpoint {
+            write_checkpoint_file(checkpoint_file, solutions.checkpoint())?;
+        }
     }
     eprintln!(
         "// Entire search duration: {:?}",
-        instant::Instant::now() - search_start_time
+        Instant::now() - search_start_time
     );
     Ok(())
 }
+
+fn search_options(
+    search_command_args: &SearchCommandArgs,
+    kpuzzle: &KPuzzle,
+) -> Result<SearchOptions, TwipsError> {
+    let mut individual_search_options = IndividualSearchOptions {
+        min_num_solutions: search_command_args.optional.min_num_solutions,
+        min_depth_inclusive: search_command_args.optional.search_args.min_depth,
+        max_depth_exclusive: search_command_args.optional.search_args.max_depth,
+        canonical_fsm_pre_moves: None,
+        canonical_fsm_post_moves: None,
+        num_threads: Some(
+            search_command_args
+                .optional
+                .search_args
+                .performance_args
+                .get_num_threads(),
+        ),
+        root_continuation_condition: search_command_args
+            .optional
+            .search_args
+            .continuation_condition()?,
+        timeout: search_command_args.optional.timeout,
+        max_num_recursive_calls: search_command_args.optional.max_nodes,
+        cancellation_token: None,
+        checkpointer: search_command_args
+            .optional
+            .checkpoint
+            .clone()
+            .map(|checkpoint_file| search_checkpointer(checkpoint_file, search_command_args)),
+    };
+    if let Some(resume_file) = &search_command_args.optional.resume {
+        let checkpoint: SearchCheckpoint = read_to_json(resume_file)?;
+        checkpoint.resume(&mut individual_search_options);
+    }
+    Ok(SearchOptions {
+        target_patterns: search_command_args
+            .optional
+            .scramble_and_target_pattern_optional_args
+            .target_patterns(kpuzzle)?,
+        generators: match search_command_args.optional.generator_args.generators() {
+            // Checkpoints are only valid for the same search order.
+            Generators::Default
+                if search_command_args.optional.checkpoint.is_some()
+                    || search_command_args.optional.resume.is_some() =>
+            {
+                Generators::default_in_stable_order(kpuzzle)
+            }
+            generators => generators,
+        },
+        metric: search_command_args.optional.metric_args.metric,
+        random_start: Some(search_command_args.optional.search_args.random_start),
+        check_before_solve: reachability_check(
+            &search_command_args.optional.search_args.check_before_solve,
+        ),
+        verbosity: search_command_args.optional.verbosity_args.verbosity,
+        individual_search_options,
+        prune_table_persistence: search_command_args
+            .optional
+            .search_persistence_args
+            .prune_table_persistence_options(),
+        memory_mebibytes: search_command_args
+            .optional
+            .search_args
+            .performance_args
+            .memory_args
+            .memory_mebibytes,
+        start_prune_depth: search_command_args.optional.search_args.start_prune_depth,
+    })
+}
@@ -0,0 +1,231 @@
+use std::{str::FromStr, sync::Arc, time::Duration};
+
+use cubing::kpuzzle::KPuzzle;
+use instant::Instant;
+use serde::Serialize;
+use twips::{
+    _internal::{
+        canonical_fsm::search_generators::{
+            Generators, SearchGenerators, SearchGeneratorsConstructorOptions,
+        },
+        errors::{ArgumentError, TwipsError},
+        schreier_sims::schreier_sims::KPuzzleStabilizerChain,
+        search::{
+            iterative_deepening::{
+                individual_search::IndividualSearchOptions,
+                iterative_deepening_search::{
+                    ImmutableSearchData, ImmutableSearchDataConstructionOptions,
+                    IterativeDeepeningSearch,
+                },
+            },
+            prune_table_trait::Depth,
+            recursive_work_tracker::RecursiveWorkDepthStats,
+        },
+    },
+    experimental_lib_api::KPuzzleSource,
+    scramble::{DerivationSalt, DerivationSeed, DerivationSeedRng, DERIVATION_SEED_BYTE_LENGTH},
+};
+
+use crate::args::TimingTestArgs;
+
+// An arbitrary fixed seed, so that runs are comparable by default.
+fn default_timing_test_seed() -> DerivationSeed {
+    let mut bytes = [0; DERIVATION_SEED_BYTE_LENGTH];
+    bytes[0] = 0x67;
+    DerivationSeed::try_new(bytes).unwrap()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DepthTiming {
+    depth: Depth,
+    pub(crate) num_recursive_calls: usize,
+    duration_seconds: f64,
+}
+
+impl From<&RecursiveWorkDepthStats> for DepthTiming {
+    fn from(stats: &RecursiveWorkDepthStats) -> Self {
+        Self {
+            depth: stats.depth,
+            num_recursive_calls: stats.num_recursive_calls,
+            duration_seconds: stats.duration.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PruneTableTiming {
+    /// Includes the initial construction as well as all extensions during searches.
+    build_time_seconds: f64,
+    depths: Vec<DepthTiming>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchTiming {
+    /// `None` if no solution was found within the max depth.
+    solution: Option<String>,
+    num_recursive_calls: usize,
+    /// Excludes time spent extending the prune table.
+    duration_seconds: f64,
+    /// `None` if the search was too fast to measure.
+    nodes_per_second: Option<f64>,
+    depths: Vec<DepthTiming>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimingTestResult {
+    puzzle_name: String,
+    seed: String,
+    max_depth: Option<Depth>,
+    prune_table: PruneTableTiming,
+    searches: Vec<SearchTiming>,
+    total_num_recursive_calls: usize,
+    total_search_duration_seconds: f64,
+    /// `None` if the searches were too fast to measure.
+    nodes_per_second: Option<f64>,
+    total_time_seconds: f64,
+}
+
+// Dividing by a zero duration would produce a value that can't be represented in JSON.
+fn nodes_per_second(num_recursive_calls: usize, duration: Duration) -> Option<f64> {
+    if duration.is_zero() {
+        return None;
+    }
+    Some(num_recursive_calls as f64 / duration.as_secs_f64())
+}
+
+pub fn cli_timing_test(timing_test_args: TimingTestArgs) -> Result<(), TwipsError> {
+    let start_time = Instant::now();
+
+    let kpuzzle_source: KPuzzleSource = timing_test_args.def_args.into();
+    let kpuzzle = kpuzzle_source.kpuzzle()?;
+    let generator_moves = Generators::Default.enumerate_moves_for_kpuzzle(&kpuzzle);
+    let seed = timing_test_args
+        .seed
+        .unwrap_or_else(default_timing_test_seed);
+
+    // The patterns are fixed by the seed, so we generate them up front to exclude this from all timings.
+    let stabilizer_chain = KPuzzleStabilizerChain::try_new(&kpuzzle, &generator_moves)?;
+    let search_patterns: Vec<_> = (0..timing_test_args.num_searches)
+        .map(|i| {
+            let salt = DerivationSalt::from_str(&format!("search{}", i))
+                .map_err(|description| ArgumentError { description })?;
+            let rng = DerivationSeedRng::new(seed.derive(&salt));
+            Ok(kpuzzle
+                .default_pattern()
+                .apply_transformation(&stabilizer_chain.random_transformation(rng)))
+        })
+        .collect::<Result<_, ArgumentError>>()?;
+
+    let prune_table_construction_start_time = Instant::now();
+    let search_generators = SearchGenerators::try_new(
+        &kpuzzle,
+        generator_moves,
+        SearchGeneratorsConstructorOptions {
+            metric: timing_test_args.metric_args.metric,
+            random_start: Some(false),
+        },
+    )?;
+    let mut iterative_deepening_search =
+        <IterativeDeepeningSearch<KPuzzle>>::new_with_hash_prune_table(
+            ImmutableSearchData::try_from_common_options(
+                kpuzzle.clone(),
+                search_generators,
+                vec![kpuzzle.default_pattern()],
+                ImmutableSearchDataConstructionOptions {
+                    search_logger: Arc::new(Default::default()),
+                    ..Default::default()
+                },
+            )?,
+            Default::default(),
+            Default::default(),
+        );
+    let prune_table_construction_duration = Instant::now() - prune_table_construction_start_time;
+
+    let mut searches = vec![];
+    for (i, search_pattern) in search_patterns.iter().enumerate() {
+        let mut search = iterative_deepening_search.search(
+            search_pattern,
+            IndividualSearchOptions {
+                min_num_solutions: Some(1),
+                max_depth_exclusive: timing_test_args.max_depth,
+                num_threads: Some(timing_test_args.performance_args.get_num_threads()),
+                ..Default::default()
+            },
+            Default::default(),
+        );
+        let solution = search.next();
+        let depths: Vec<DepthTiming> = search
+            .recursive_work_depth_stats()
+            .iter()
+            .map(DepthTiming::from)
+            .collect();
+        let num_recursive_calls = depths.iter().map(|depth| depth.num_recursive_calls).sum();
+        let duration: Duration = search
+            .recursive_work_depth_stats()
+            .iter()
+            .map(|stats| stats.duration)
+            .sum();
+        eprintln!(
+            "[Timing test] Search #{}: {} ({:?})",
+            i + 1,
+            match &solution {
+                Some(solution) => solution.to_string(),
+                None => "(no solution within max depth)".to_owned(),
+            },
+            duration
+        );
+        searches.push(SearchTiming {
+            solution: solution.map(|solution| solution.to_string()),
+            num_recursive_calls,
+            duration_seconds: duration.as_secs_f64(),
+            nodes_per_second: nodes_per_second(num_recursive_calls, duration),
+            depths,
+        });
+    }
+
+    let prune_table_depth_stats = iterative_deepening_search
+        .prune_table
+        .recursive_work_depth_stats();
+    let prune_table_build_duration = prune_table_construction_duration
+        + prune_table_depth_stats
+            .iter()
+            .map(|stats| stats.duration)
+            .sum();
+
+    let total_num_recursive_calls = searches
+        .iter()
+        .map(|search| search.num_recursive_calls)
+        .sum();
+    let total_search_duration_seconds: f64 =
+        searches.iter().map(|search| search.duration_seconds).sum();
+
+    let result = TimingTestResult {
+        puzzle_name: kpuzzle.definition().name.clone(),
+        seed: seed.to_string(),
+        max_depth: timing_test_args.max_depth,
+        prune_table: PruneTableTiming {
+            build_time_seconds: prune_table_build_duration.as_secs_f64(),
+            depths: prune_table_depth_stats
+                .iter()
+                .map(DepthTiming::from)
+                .collect(),
+        },
+        searches,
+        total_num_recursive_calls,
+        total_search_duration_seconds,
+        nodes_per_second: nodes_per_second(
+            total_num_recursive_calls,
+            Duration::from_secs_f64(total_search_duration_seconds),
+        ),
+        total_time_seconds: (Instant::now() - start_time).as_secs_f64(),
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&result).expect("Could not serialize timing test result.")
+    );
+    Ok(())
+}
@@ -0,0 +1,171 @@
+use std::{fs::read, str::FromStr};
+
+use cubing::alg::Alg;
+use serde::Deserialize;
+use twips::{
+    _internal::errors::{ArgumentError, TwipsError},
+    scramble::{
+        derive_scramble_for_event_seeded, experimental_scramble_finder_filter_and_or_search,
+        DerivationSalt, Event, ExperimentalFilterAndOrSearchOptions,
+    },
+};
+
+use crate::{
+    args::VerifyScramblesArgs,
+    commands::cli_derive_competition::{DerivedCompetition, DerivedScramble},
+};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PublishedScrambles {
+    Competition(DerivedCompetition),
+    List(Vec<DerivedScramble>),
+}
+
+impl PublishedScrambles {
+    fn into_scrambles(self) -> Vec<DerivedScramble> {
+        match self {
+            PublishedScrambles::Competition(derived_competition) => derived_competition
+                .events
+                .into_iter()
+                .flat_map(|event| event.rounds)
+                .flat_map(|round| round.groups)
+                .flat_map(|group| group.attempts)
+                .flat_map(|attempt| attempt.scrambles)
+                .collect(),
+            PublishedScrambles::List(scrambles) => scrambles,
+        }
+    }
+}
+
+/// Returns a description of the problem, if there is one.
+fn verify_scramble(
+    args: &VerifyScramblesArgs,
+    published_scramble: &DerivedScramble,
+) -> Result<Option<String>, TwipsError> {
+    let subevent = Event::try_from(published_scramble.subevent.as_str())?;
+    let salts = published_scramble
+        .derivation_path
+        .split('/')
+        .map(DerivationSalt::from_str)
+        .collect::<Result<Vec<DerivationSalt>, String>>()
+        .map_err(|description| ArgumentError { description })?;
+    let Some(salts) = salts.get((args.derivation_seed.level() as usize)..) else {
+        return Err(Into::<ArgumentError>::into(
+            "Derivation path is shorter than the level of the derivation seed.",
+        )
+        .into());
+    };
+
+    let Ok(published_alg) = Alg::from_str(&published_scramble.scramble) else {
+        return Ok(Some("Invalid scramble alg.".to_owned()));
+    };
+    let derived_scramble =
+        derive_scramble_for_event_seeded(&args.derivation_seed, &salts.to_vec(), subevent)
+            .map_err(|description| ArgumentError { description })?;
+    // Compare first, so that only algs that are valid for the event are filtered.
+    if published_alg != derived_scramble {
+        return Ok(Some(format!(
+            "Mismatched scramble. Expected: {}",
+            derived_scramble
+        )));
+    }
+
+    if let Err(e) = experimental_scramble_finder_filter_and_or_search(
+        subevent,
+        &ExperimentalFilterAndOrSearchOptions {
+            scramble_setup_alg: &published_alg,
+            apply_filtering: true,
+            perform_search: false,
+        },
+    ) {
+        return Ok(Some(format!(
+            "Scramble did not pass filtering: {}",
+            e.description()
+        )));
+    }
+
+    Ok(None)
+}
+
+pub fn cli_verify_scrambles(args: &VerifyScramblesArgs) -> Result<(), TwipsError> {
+    let scrambles_file_bytes = read(&args.scrambles_file).map_err(|e| ArgumentError {
+        description: format!("Could not read scrambles file: {}", e),
+    })?;
+    let published_scrambles: PublishedScrambles = serde_json::from_slice(&scrambles_file_bytes)
+        .map_err(|e| ArgumentError {
+            description: format!("Invalid scrambles file: {}", e),
+        })?;
+
+    let mut num_verified = 0;
+    let mut num_failed = 0;
+    for published_scramble in published_scrambles.into_scrambles() {
+        match verify_scramble(args, &published_scramble)? {
+            None => {
+                num_verified += 1;
+                eprintln!("// Verified: {}", published_scramble.derivation_path);
+            }
+            Some(problem) => {
+                num_failed += 1;
+                println!(
+                    "❌ {} ({}): {}",
+                    published_scramble.derivation_path, published_scramble.subevent, problem
+                );
+            }
+        }
+    }
+
+    println!(
+        "{} scramble(s) verified, {} failed.",
+        num_verified, num_failed
+    );
+    if num_failed > 0 {
+        return Err(ArgumentError {
+            description: format!("{} scramble(s) failed verification.", num_failed),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, str::FromStr};
+
+    use twips::{_internal::errors::TwipsError, scramble::DerivationSeed};
+
+    use super::verify_scramble;
+    use crate::{args::VerifyScramblesArgs, commands::cli_derive_competition::DerivedScramble};
+
+    const ROOT_DERIVATION_SEED: &str =
+        "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67";
+    const DERIVATION_PATH: &str =
+        "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF/scrambles/222/r1/g1/a1/222/sub1";
+
+    fn verify(scramble: &str) -> Result<Option<String>, TwipsError> {
+        verify_scramble(
+            &VerifyScramblesArgs {
+                derivation_seed: DerivationSeed::from_str(ROOT_DERIVATION_SEED).unwrap(),
+                scrambles_file: PathBuf::default(),
+            },
+            &DerivedScramble {
+                subevent: "222".to_owned(),
+                derivation_path: DERIVATION_PATH.to_owned(),
+                scramble: scramble.to_owned(),
+            },
+        )
+    }
+
+    #[test]
+    fn verifies_derived_scramble() -> Result<(), TwipsError> {
+        assert_eq!(verify("F L' U F L2 F R' F U' R' U'")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn reports_invalid_move_as_mismatch() -> Result<(), TwipsError> {
+        assert!(verify("F L' U F L2 F R' F U' R' Q'")?
+            .is_some_and(|problem| problem.starts_with("Mismatched scramble.")));
+        Ok(())
+    }
+}
@@ -1,6 +1,11 @@
 pub mod cli_benchmark;
 pub mod cli_canonical_algs;
 pub mod cli_derive;
+pub mod cli_derive_competition;
+pub mod cli_root_seed;
+pub mod cli_schreier_sims;
 pub mod cli_scramble;
 pub mod cli_search;
+pub mod cli_timing_test;
+pub mod cli_verify_scrambles;
 pub mod gods_algorithm;
@@ -5,15 +5,20 @@ mod serve;
 use commands::{
     cli_benchmark::benchmark,
     cli_canonical_algs::canonical_algs,
+    cli_schreier_sims::cli_schreier_sims,
     cli_scramble::{cli_scramble, cli_scramble_finder, cli_solve_known_puzzle},
     cli_search::cli_search,
+    cli_timing_test::cli_timing_test,
     gods_algorithm::cli_gods_algorithm,
 };
 use twips::_internal::errors::TwipsError;
 
 use crate::{
     args::{get_args, CliCommand},
-    commands::cli_derive::cli_derive,
+    commands::{
+        cli_derive::cli_derive, cli_derive_competition::cli_derive_competition,
+        cli_root_seed::cli_root_seed, cli_verify_scrambles::cli_verify_scrambles,
+    },
 };
 
 #[tokio::main]
@@ -30,15 +35,22 @@ async fn main() -> Result<(), TwipsError> {
         }
         CliCommand::Serve(serve_command_args) => serve::serve::serve(serve_command_args).await,
         // TODO: consolidate def-only arg implementations.
-        CliCommand::SchreierSims(_schreier_sims_command_args) => todo!(),
+        CliCommand::SchreierSims(schreier_sims_args) => cli_schreier_sims(schreier_sims_args),
         CliCommand::GodsAlgorithm(gods_algorithm_args) => cli_gods_algorithm(gods_algorithm_args),
-        CliCommand::TimingTest(_args) => todo!(),
+        CliCommand::TimingTest(timing_test_args) => cli_timing_test(timing_test_args),
         CliCommand::CanonicalAlgs(args) => canonical_algs(&args),
         CliCommand::Scramble(scramble_args) => cli_scramble(&scramble_args),
         CliCommand::ScrambleFinder(scramble_finder_solve_args) => {
             cli_scramble_finder(&scramble_finder_solve_args)
         }
         CliCommand::Derive(derive_args) => cli_derive(&derive_args),
+        CliCommand::DeriveCompetition(derive_competition_args) => {
+            cli_derive_competition(&derive_competition_args)
+        }
+        CliCommand::VerifyScrambles(verify_scrambles_args) => {
+            cli_verify_scrambles(&verify_scrambles_args)
+        }
+        CliCommand::RootSeed(root_seed_args) => cli_root_seed(&root_seed_args),
         CliCommand::Benchmark(benchmark_args) => benchmark(&benchmark_args),
     }
 }
@@ -1,2 +1,4 @@
 #[allow(clippy::module_inception)]
 pub mod serve;
+
+mod search_cache;
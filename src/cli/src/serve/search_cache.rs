@@ -0,0 +1,100 @@
+use cubing::{
+    alg::{Alg, Move},
+    kpuzzle::{KPatternData, KPuzzle, KPuzzleDefinition},
+};
+use serde::Serialize;
+use twips::_internal::{
+    canonical_fsm::search_generators::KPuzzleWithGeneratorMoves, notation::metric::TurnMetric,
+    search::iterative_deepening::iterative_deepening_search::IterativeDeepeningSearch,
+};
+
+const BYTES_PER_MEBIBYTE: usize = 1 << 20;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchCacheKeyData<'a> {
+    definition: &'a KPuzzleDefinition,
+    generator_moves: &'a Option<Vec<Move>>,
+    generator_algs: &'a Option<Vec<Alg>>,
+    metric: Option<TurnMetric>,
+    target_patterns: &'a Option<Vec<KPatternData>>,
+}
+
+/// Identifies all the request fields that affect the construction of an `IterativeDeepeningSearch`.
+#[derive(PartialEq, Eq)]
+pub struct SearchCacheKey(String);
+
+impl SearchCacheKey {
+    pub fn new(
+        definition: &KPuzzleDefinition,
+        generator_moves: &Option<Vec<Move>>,
+        generator_algs: &Option<Vec<Alg>>,
+        metric: Option<TurnMetric>,
+        target_patterns: &Option<Vec<KPatternData>>,
+    ) -> Self {
+        // The definition contains `HashMap`s, so we round-trip through `serde_json::Value` (which sorts object keys) to get a canonical serialization.
+        let value = serde_json::to_value(SearchCacheKeyData {
+            definition,
+            generator_moves,
+            generator_algs,
+            metric,
+            target_patterns,
+        })
+        .expect("Could not serialize search cache key.");
+        Self(value.to_string())
+    }
+}
+
+pub struct CachedSearch {
+    pub search: IterativeDeepeningSearch<KPuzzle>,
+    /// Needed to expand alg generators in solutions.
+    pub kpuzzle_with_generator_moves: KPuzzleWithGeneratorMoves,
+}
+
+/// A least-recently-used cache of searches, so that repeated requests for the
+/// same puzzle can reuse a prune table that has already been filled.
+///
+/// Searches are taken out of the cache while they are in use (so that the
+/// cache does not need to be locked during a search) and returned afterwards.
+pub struct SearchCache {
+    max_num_bytes: usize,
+    /// Ordered from least recently used to most recently used.
+    entries: Vec<(SearchCacheKey, CachedSearch)>,
+}
+
+impl SearchCache {
+    pub fn new(max_mebibytes: usize) -> Self {
+        Self {
+            max_num_bytes: max_mebibytes.saturating_mul(BYTES_PER_MEBIBYTE),
+            entries: vec![],
+        }
+    }
+
+    /// Removes the search for the given key from the cache (if present).
+    pub fn take(&mut self, key: &SearchCacheKey) -> Option<CachedSearch> {
+        let index = self
+            .entries
+            .iter()
+            .position(|(entry_key, _)| entry_key == key)?;
+        Some(self.entries.remove(index).1)
+    }
+
+    /// Inserts the search as the most recently used entry, evicting the least
+    /// recently used entries until the cache fits in its memory limit. If the
+    /// search by itself does not fit, it is not cached.
+    pub fn insert(&mut self, key: SearchCacheKey, search: CachedSearch) {
+        // Another request for the same key may have returned its search in the meantime.
+        self.take(&key);
+        self.entries.push((key, search));
+        while self.num_bytes() > self.max_num_bytes {
+            self.entries.remove(0);
+        }
+    }
+
+    fn num_bytes(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|(_, cached_search)| cached_search.search.prune_table.approximate_num_bytes())
+            .sum()
+    }
+}
@@ -1,214 +1,560 @@
 use axum::{
     http::StatusCode,
-    response::{IntoResponse, Response},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     routing::{get, post},
     Json, Router,
 };
-use cubing::kpuzzle::{KPattern, KPatternData, KPuzzle, KPuzzleDefinition};
+use cubing::{
+    alg::{Alg, Move},
+    kpuzzle::{KPattern, KPatternData, KPuzzle, KPuzzleDefinition},
+};
 
-use crate::args::{ServeArgsForIndividualSearch, ServeClientArgs, ServeCommandArgs};
+use crate::{
+    args::{reachability_check, ServeArgsForIndividualSearch, ServeClientArgs, ServeCommandArgs},
+    serve::search_cache::{CachedSearch, SearchCache, SearchCacheKey},
+};
 use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 use tower_http::cors::CorsLayer;
 use twips::_internal::{
     canonical_fsm::search_generators::{
-        Generators, SearchGenerators, SearchGeneratorsConstructorOptions,
+        Generators, KPuzzleWithGeneratorMoves, SearchGenerators, SearchGeneratorsConstructorOptions,
     },
     errors::TwipsError,
     notation::metric::TurnMetric,
+    schreier_sims::reachability::check_reachability,
     search::{
         iterative_deepening::{
-            individual_search::IndividualSearchOptions,
+            individual_search::{IndividualSearchOptions, SearchTerminationReason},
             iterative_deepening_search::{
                 ImmutableSearchData, ImmutableSearchDataConstructionOptions,
                 IterativeDeepeningSearch,
             },
             search_adaptations::StoredSearchAdaptations,
+            search_checkpoint::SearchCheckpoint,
+            search_limits::SearchCancellationToken,
         },
         search_logger::SearchLogger,
     },
 };
 
+use std::convert::Infallible;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
+
+/// Used when `--memory-MiB` is not specified.
+const DEFAULT_SEARCH_CACHE_MEBIBYTES: usize = 1024;
+
+// Solutions are usually consumed much faster than they are found, so this only needs to be large enough to avoid blocking the search on a slow client.
+const SOLUTION_STREAM_BUFFER_SIZE: usize = 16;
 
 #[derive(Serialize)]
 struct ResponseAlg {
     alg: String, // TODO: support automatic alg serialization somehome
 }
 
+#[derive(Serialize)]
+struct ResponseAlgs {
+    /// The first solution.
+    alg: String, // TODO: support automatic alg serialization somehome
+    /// Up to `minNumSolutions` solutions (default: 1), in the order they were found.
+    algs: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct KPatternSolve {
     definition: KPuzzleDefinition,
     pattern: KPatternData,
     start_pattern: Option<KPatternData>,
+    /// Solutions are found for reaching any of these patterns. Cannot be combined with `startPattern`.
+    target_patterns: Option<Vec<KPatternData>>,
     search_args: Option<ServeClientArgs>,
 }
 
-async fn solve_pattern(
-    Json(kpattern_solve): Json<KPatternSolve>,
-    serve_command_args: Arc<ServeCommandArgs>,
-    request_counter: usize,
-) -> Response {
-    println!("[Search request #{}] Starting search…", request_counter);
-    let start_time = instant::Instant::now();
+fn bad_request(description: String) -> Response {
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(description.into())
+        .unwrap()
+}
+
+struct PreparedSearch {
+    search: IterativeDeepeningSearch<KPuzzle>,
+    kpuzzle_with_generator_moves: KPuzzleWithGeneratorMoves,
+    search_pattern: KPattern,
+    individual_search_options: IndividualSearchOptions,
+    /// If present, the search should be returned to the cache under this key after use.
+    search_cache_key: Option<SearchCacheKey>,
+}
+
+/// Returns a description of the issue if the request is invalid.
+fn prepare_search(
+    kpattern_solve: KPatternSolve,
+    serve_command_args: &ServeCommandArgs,
+    search_cache: &Mutex<SearchCache>,
+) -> Result<PreparedSearch, String> {
     // TODO: use the client args
     let args_for_individual_search = ServeArgsForIndividualSearch {
-        commandline_args: &serve_command_args,
+        commandline_args: serve_command_args,
         client_args: &kpattern_solve.search_args,
     };
-    let kpuzzle = match KPuzzle::try_new(kpattern_solve.definition) {
-        Ok(kpuzzle) => kpuzzle.clone(),
-        Err(e) => {
-            return Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(e.description.into())
-                .unwrap();
+    let client_args = args_for_individual_search.client_args.as_ref();
+    let move_subset = client_args.and_then(|client_args| client_args.generator_moves.clone());
+    let generator_algs = client_args.and_then(|client_args| client_args.generator_algs.clone());
+    let metric = client_args.map(|client_args| {
+        if client_args.quantum_metric.unwrap_or_default() {
+            TurnMetric::Quantum
+        } else {
+            TurnMetric::Hand
+        }
+    });
+    let random_start = client_args.map(|client_args| client_args.random_start == Some(true));
+    // A checkpoint is only valid for the move order of the search it came from.
+    if random_start == Some(true)
+        && client_args.is_some_and(|client_args| client_args.resume.is_some())
+    {
+        return Err("`resume` cannot be combined with `randomStart`".to_owned());
+    }
+    let target_patterns_data = match (kpattern_solve.start_pattern, kpattern_solve.target_patterns)
+    {
+        (None, None) => None,
+        (Some(kpattern_data), None) => Some(vec![kpattern_data]),
+        (None, Some(target_patterns_data)) if !target_patterns_data.is_empty() => {
+            Some(target_patterns_data)
+        }
+        (None, Some(_)) => return Err("`targetPatterns` must not be empty".to_owned()),
+        (Some(_), Some(_)) => {
+            return Err("`startPattern` and `targetPatterns` cannot both be specified".to_owned())
         }
     };
-    let target_pattern = match kpattern_solve.start_pattern {
-        Some(kpattern_data) => match KPattern::try_from_data(&kpuzzle, &kpattern_data) {
-            Ok(target_pattern) => target_pattern,
-            Err(e) => {
-                return Response::builder()
-                    .status(StatusCode::BAD_REQUEST)
-                    .body(e.to_string().into())
-                    .unwrap()
+    // A cached search would always start with the same random move order, so we don't cache these.
+    let search_cache_key = (random_start != Some(true)).then(|| {
+        SearchCacheKey::new(
+            &kpattern_solve.definition,
+            &move_subset,
+            &generator_algs,
+            metric,
+            &target_patterns_data,
+        )
+    });
+    let cached_search = search_cache_key.as_ref().and_then(|search_cache_key| {
+        search_cache
+            .lock()
+            .expect("Internal error: could not access search cache")
+            .take(search_cache_key)
+    });
+    let CachedSearch {
+        search,
+        kpuzzle_with_generator_moves,
+    } = match cached_search {
+        Some(cached_search) => cached_search,
+        None => {
+            let kpuzzle = KPuzzle::try_new(kpattern_solve.definition).map_err(|e| e.description)?;
+            let generators = match (move_subset, generator_algs) {
+                // Every search returns a checkpoint, which can be passed as `resume` in a later request.
+                (None, None) => Generators::default_in_stable_order(&kpuzzle),
+                (moves, algs) => Generators::Custom {
+                    moves: moves.unwrap_or_default(),
+                    algs: algs.unwrap_or_default(),
+                },
+            };
+            let kpuzzle_with_generator_moves = generators
+                .kpuzzle_with_generator_moves(&kpuzzle)
+                .map_err(|e| e.description)?;
+            let kpuzzle = kpuzzle_with_generator_moves.kpuzzle.clone();
+            let target_patterns = match target_patterns_data {
+                Some(target_patterns_data) => target_patterns_data
+                    .iter()
+                    .map(|kpattern_data| KPattern::try_from_data(&kpuzzle, kpattern_data))
+                    .collect::<Result<Vec<KPattern>, _>>()
+                    .map_err(|e| e.to_string())?,
+                None => vec![kpuzzle.default_pattern()],
+            };
+            let search_logger = Arc::new(SearchLogger {
+                verbosity: args_for_individual_search
+                    .commandline_args
+                    .verbosity_args
+                    .verbosity
+                    .unwrap_or_default(),
+            });
+            let search_generators = SearchGenerators::try_new(
+                &kpuzzle,
+                kpuzzle_with_generator_moves.generator_moves.clone(),
+                SearchGeneratorsConstructorOptions {
+                    metric,
+                    random_start,
+                },
+            )
+            .map_err(|e| e.description)?;
+            let immutable_search_data = ImmutableSearchData::try_from_common_options(
+                kpuzzle,
+                search_generators,
+                target_patterns,
+                ImmutableSearchDataConstructionOptions {
+                    search_logger,
+                    ..Default::default()
+                },
+            )
+            .map_err(|e| e.description)?;
+            CachedSearch {
+                search: <IterativeDeepeningSearch<KPuzzle>>::new_with_hash_prune_table(
+                    immutable_search_data,
+                    StoredSearchAdaptations::default(),
+                    Default::default(),
+                ),
+                kpuzzle_with_generator_moves,
             }
-        },
-        None => kpuzzle.default_pattern(),
+        }
     };
-    let search_logger = Arc::new(SearchLogger {
-        verbosity: args_for_individual_search
-            .commandline_args
-            .verbosity_args
-            .verbosity
-            .unwrap_or_default(),
-    });
-    let move_subset = match args_for_individual_search.client_args {
-        Some(client_args) => client_args.generator_moves.as_ref().cloned(),
+    let search_pattern = KPattern::try_from_data(
+        &search.immutable_search_data.tpuzzle,
+        &kpattern_solve.pattern,
+    )
+    .map_err(|e| e.to_string())?;
+    let generator_moves: Vec<Move> = search
+        .immutable_search_data
+        .search_generators
+        .flat
+        .iter()
+        .map(|(_, move_transformation_info)| move_transformation_info.r#move.clone())
+        .collect();
+    if let Err(e) = check_reachability(
+        &search.immutable_search_data.tpuzzle,
+        &generator_moves,
+        &search_pattern,
+        &search.immutable_search_data.target_patterns,
+        reachability_check(
+            &client_args.and_then(|client_args| client_args.check_before_solve.clone()),
+        ),
+    ) {
+        return_search_to_cache(
+            search_cache,
+            search_cache_key,
+            CachedSearch {
+                search,
+                kpuzzle_with_generator_moves,
+            },
+        );
+        return Err(e.description);
+    }
+    let timeout = match client_args.and_then(|client_args| client_args.timeout_seconds) {
+        Some(timeout_seconds) => Some(
+            Duration::try_from_secs_f64(timeout_seconds)
+                .map_err(|e| format!("Invalid `timeoutSeconds`: {}", e))?,
+        ),
         None => None,
     };
-    let move_list =
-        move_subset.unwrap_or_else(|| kpuzzle.definition().moves.keys().cloned().collect());
-    let search_pattern = match KPattern::try_from_data(&kpuzzle, &kpattern_solve.pattern) {
-        Ok(search_pattern) => search_pattern,
-        Err(e) => {
-            return Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(e.to_string().into())
-                .unwrap()
-        }
+    let mut individual_search_options = IndividualSearchOptions {
+        min_num_solutions: client_args.and_then(|client_args| client_args.min_num_solutions),
+        min_depth_inclusive: client_args.and_then(|client_args| client_args.min_depth),
+        max_depth_exclusive: client_args.and_then(|client_args| client_args.max_depth),
+        num_threads: Some(
+            args_for_individual_search
+                .commandline_args
+                .performance_args
+                .get_num_threads(),
+        ),
+        timeout,
+        max_num_recursive_calls: client_args.and_then(|client_args| client_args.max_nodes),
+        // TODO: support canonical FSM pre-moves and post-moves.
+        ..Default::default()
     };
-    let generator_moves = Generators::Custom {
-        moves: move_list.clone(),
-        algs: vec![],
+    if let Some(checkpoint) = client_args.and_then(|client_args| client_args.resume.as_ref()) {
+        checkpoint.resume(&mut individual_search_options);
     }
-    .enumerate_moves_for_kpuzzle(&kpuzzle);
-    let search_generators = match SearchGenerators::try_new(
-        &kpuzzle,
-        generator_moves,
-        SearchGeneratorsConstructorOptions {
-            metric: match args_for_individual_search.client_args {
-                Some(client_args) => {
-                    if client_args.quantum_metric.unwrap_or_default() {
-                        Some(TurnMetric::Quantum)
-                    } else {
-                        Some(TurnMetric::Hand)
-                    }
-                }
-                None => None,
-            },
-            random_start: args_for_individual_search
-                .client_args
-                .as_ref()
-                .map(|client_args| client_args.random_start == Some(true)),
-        },
-    ) {
-        Ok(search_generators) => search_generators,
-        Err(e) => {
-            return Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(e.description.into())
-                .unwrap()
-        }
-    };
-    let immutable_search_data = match ImmutableSearchData::try_from_common_options(
-        kpuzzle.clone(),
-        search_generators,
-        vec![target_pattern], // TODO: modify api to support multiple target patterns
-        ImmutableSearchDataConstructionOptions {
-            search_logger,
-            ..Default::default()
-        },
-    ) {
-        Ok(immutable_search_data) => immutable_search_data,
-        Err(e) => {
-            return Response::builder()
-                .status(StatusCode::BAD_REQUEST)
-                .body(e.description.into())
-                .unwrap()
-        }
+    Ok(PreparedSearch {
+        search,
+        kpuzzle_with_generator_moves,
+        search_pattern,
+        individual_search_options,
+        search_cache_key,
+    })
+}
+
+/// Runs [`prepare_search`] on a blocking thread, since the reachability check can take a while.
+async fn prepare_search_blocking(
+    kpattern_solve: KPatternSolve,
+    serve_command_args: Arc<ServeCommandArgs>,
+    search_cache: Arc<Mutex<SearchCache>>,
+) -> Result<PreparedSearch, String> {
+    tokio::task::spawn_blocking(move || {
+        prepare_search(kpattern_solve, &serve_command_args, &search_cache)
+    })
+    .await
+    .expect("Internal error: could not prepare search")
+}
+
+fn return_search_to_cache(
+    search_cache: &Mutex<SearchCache>,
+    search_cache_key: Option<SearchCacheKey>,
+    search: CachedSearch,
+) {
+    if let Some(search_cache_key) = search_cache_key {
+        search_cache
+            .lock()
+            .expect("Internal error: could not access search cache")
+            .insert(search_cache_key, search);
+    }
+}
+
+async fn solve_pattern(
+    Json(kpattern_solve): Json<KPatternSolve>,
+    serve_command_args: Arc<ServeCommandArgs>,
+    search_cache: Arc<Mutex<SearchCache>>,
+    request_counter: usize,
+) -> Response {
+    println!("[Search request #{}] Starting search…", request_counter);
+    let start_time = instant::Instant::now();
+    let PreparedSearch {
+        mut search,
+        kpuzzle_with_generator_moves,
+        search_pattern,
+        individual_search_options,
+        search_cache_key,
+    } = match prepare_search_blocking(kpattern_solve, serve_command_args, search_cache.clone())
+        .await
+    {
+        Ok(prepared_search) => prepared_search,
+        Err(description) => return bad_request(description),
     };
-    let mut search = <IterativeDeepeningSearch<KPuzzle>>::new_with_hash_prune_table(
-        immutable_search_data,
-        StoredSearchAdaptations::default(),
-        Default::default(),
-        // IterativeDeepeningSearchConstructionOptions {
-        //     random_start: match args_for_individual_search.client_args {
-        //         Some(client_args) => client_args.random_start == Some(true),
-        //         None => false,
-        //     },
-        //     ..Default::default()
-        // },
-        // None,
-    );
-    if let Some(solution) = search
-        .search(
+    // Filling the prune table and searching can take a while, so we do this on a blocking thread.
+    let (solutions, termination_reason) = tokio::task::spawn_blocking(move || {
+        let min_num_solutions = individual_search_options.min_num_solutions.unwrap_or(1);
+        let mut solutions = search.search(
             &search_pattern,
             IndividualSearchOptions {
-                min_num_solutions: None,
-                min_depth_inclusive: args_for_individual_search
-                    .client_args
-                    .as_ref()
-                    .and_then(|client_args| client_args.min_depth),
-                max_depth_exclusive: args_for_individual_search
-                    .client_args
-                    .as_ref()
-                    .and_then(|client_args| client_args.max_depth),
-                // TODO: support canonical FSM pre-moves and post-moves.
-                ..Default::default()
+                min_num_solutions: Some(min_num_solutions),
+                ..individual_search_options
             },
             Default::default(),
-        )
-        .next()
-    {
+        );
+        let solutions_found: Vec<Alg> = solutions
+            .by_ref()
+            .take(min_num_solutions)
+            .map(|solution| kpuzzle_with_generator_moves.expand_alg_generators(&solution))
+            .collect();
+        let termination_reason = solutions.termination_reason();
+        return_search_to_cache(
+            &search_cache,
+            search_cache_key,
+            CachedSearch {
+                search,
+                kpuzzle_with_generator_moves,
+            },
+        );
+        (solutions_found, termination_reason)
+    })
+    .await
+    .expect("Internal error: could not run search");
+    if let Some(solution) = solutions.first() {
         println!(
-            "[Search request #{}] Solution found (in {:?}): {}",
+            "[Search request #{}] {} solution(s) found (in {:?}), starting with: {}",
             request_counter,
+            solutions.len(),
             instant::Instant::now() - start_time,
             solution
         );
-        // TODO: send multiple solutions via socket
-        return Json(ResponseAlg {
+        return Json(ResponseAlgs {
             alg: solution.to_string(),
+            algs: solutions
+                .iter()
+                .map(|solution| solution.to_string())
+                .collect(),
         })
         .into_response();
-
-        //  Response::json(&ResponseAlg {
-        //     alg: solution.to_string(),
-        // });
     }
-    println!("[Search request #{}] No solution found.", request_counter);
+    let description = match termination_reason.filter(|reason| reason.is_interrupted()) {
+        Some(termination_reason) => format!("No solution found ({})", termination_reason),
+        None => "No solution found".to_owned(),
+    };
+    println!("[Search request #{}] {}.", request_counter, description);
     Response::builder()
         .status(StatusCode::NOT_FOUND)
-        .body("No solution found".to_owned().into())
+        .body(description.into())
         .unwrap()
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseSearchFinished {
+    num_solutions: usize,
+    termination_reason: Option<SearchTerminationReason>,
+    /// Can be passed as `resume` to continue the search.
+    checkpoint: SearchCheckpoint,
+}
+
+/// Streams solutions as [server-sent events](https://developer.mozilla.org/en-US/docs/Web/API/Server-sent_events):
+///
+/// - A `solution` event (with the same `alg` field as the response from `/v0/solve/pattern`) for each solution.
+/// - A single `finished` event once the search is finished.
+///
+/// The search is cancelled if the client disconnects (or a client-specified limit is reached).
+async fn solve_pattern_stream(
+    Json(kpattern_solve): Json<KPatternSolve>,
+    serve_command_args: Arc<ServeCommandArgs>,
+    search_cache: Arc<Mutex<SearchCache>>,
+    request_counter: usize,
+) -> Response {
+    println!(
+        "[Search request #{}] Starting streaming search…",
+        request_counter
+    );
+    let start_time = instant::Instant::now();
+    let PreparedSearch {
+        mut search,
+        kpuzzle_with_generator_moves,
+        search_pattern,
+        individual_search_options,
+        search_cache_key,
+    } = match prepare_search_blocking(kpattern_solve, serve_command_args, search_cache.clone())
+        .await
+    {
+        Ok(prepared_search) => prepared_search,
+        Err(description) => return bad_request(description),
+    };
+    let (sender, receiver) = mpsc::channel::<Event>(SOLUTION_STREAM_BUFFER_SIZE);
+    let cancellation_token = SearchCancellationToken::new();
+    // Dropped when the search is finished, so that we stop waiting for a disconnect.
+    let (search_finished_sender, search_finished_receiver) = oneshot::channel::<()>();
+    {
+        let sender = sender.clone();
+        let cancellation_token = cancellation_token.clone();
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = sender.closed() => cancellation_token.cancel(),
+                _ = search_finished_receiver => {}
+            }
+        });
+    }
+    tokio::task::spawn_blocking(move || {
+        stream_solutions(
+            &mut search,
+            &kpuzzle_with_generator_moves,
+            &search_pattern,
+            IndividualSearchOptions {
+                cancellation_token: Some(cancellation_token),
+                ..individual_search_options
+            },
+            &sender,
+            request_counter,
+            start_time,
+        );
+        return_search_to_cache(
+            &search_cache,
+            search_cache_key,
+            CachedSearch {
+                search,
+                kpuzzle_with_generator_moves,
+            },
+        );
+        drop(search_finished_sender);
+    });
+    Sse::new(ReceiverStream::new(receiver).map(Ok::<Event, Infallible>))
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn stream_solutions(
+    search: &mut IterativeDeepeningSearch<KPuzzle>,
+    kpuzzle_with_generator_moves: &KPuzzleWithGeneratorMoves,
+    search_pattern: &KPattern,
+    individual_search_options: IndividualSearchOptions,
+    sender: &mpsc::Sender<Event>,
+    request_counter: usize,
+    start_time: instant::Instant,
+) {
+    let mut num_solutions = 0;
+    let mut solutions = search.search(
+        search_pattern,
+        individual_search_options,
+        Default::default(),
+    );
+    loop {
+        if sender.is_closed() {
+            println!(
+                "[Search request #{}] Client disconnected, cancelling search.",
+                request_counter
+            );
+            return;
+        }
+        let Some(solution) = solutions.next() else {
+            break;
+        };
+        let solution = kpuzzle_with_generator_moves.expand_alg_generators(&solution);
+        num_solutions += 1;
+        println!(
+            "[Search request #{}] Solution #{} found (in {:?}): {}",
+            request_counter,
+            num_solutions,
+            instant::Instant::now() - start_time,
+            solution
+        );
+        let event = Event::default()
+            .event("solution")
+            .json_data(ResponseAlg {
+                alg: solution.to_string(),
+            })
+            .expect("Could not serialize solution.");
+        if sender.blocking_send(event).is_err() {
+            println!(
+                "[Search request #{}] Client disconnected, cancelling search.",
+                request_counter
+            );
+            return;
+        }
+    }
+    let termination_reason = solutions.termination_reason();
+    if termination_reason == Some(SearchTerminationReason::Cancelled) {
+        println!(
+            "[Search request #{}] Client disconnected, cancelled search.",
+            request_counter
+        );
+        return;
+    }
+    println!(
+        "[Search request #{}] Search finished with {} solution(s) ({}).",
+        request_counter,
+        num_solutions,
+        termination_reason
+            .map(|termination_reason| termination_reason.to_string())
+            .unwrap_or_default()
+    );
+    let event = Event::default()
+        .event("finished")
+        .json_data(ResponseSearchFinished {
+            num_solutions,
+            termination_reason,
+            checkpoint: solutions.checkpoint().clone(),
+        })
+        .expect("Could not serialize search result.");
+    // If the client has disconnected, there is no one left to notify.
+    let _ = sender.blocking_send(event);
+}
+
+fn next_request_number(search_request_counter: &Mutex<usize>) -> usize {
+    let mut counter = search_request_counter
+        .lock()
+        .expect("Internal error: could not access request counter");
+    *counter += 1;
+    *counter
+}
+
 pub async fn serve(serve_command_args: ServeCommandArgs) -> Result<(), TwipsError> {
     let serve_command_args = Arc::new(serve_command_args);
     let search_request_counter = Arc::new(Mutex::<usize>::new(0));
+    let search_cache = Arc::new(Mutex::new(SearchCache::new(
+        serve_command_args
+            .performance_args
+            .memory_args
+            .memory_mebibytes
+            .unwrap_or(DEFAULT_SEARCH_CACHE_MEBIBYTES),
+    )));
     println!(
         "Starting `twips serve` on port 2023.
 Use with one of the following:
@@ -225,15 +571,30 @@ Use with one of the following:
         )
         .route(
             "/v0/solve/pattern",
+            post({
+                let search_request_counter = search_request_counter.clone();
+                let serve_command_args = serve_command_args.clone();
+                let search_cache = search_cache.clone();
+                move |body| {
+                    solve_pattern(
+                        body,
+                        serve_command_args.clone(),
+                        search_cache.clone(),
+                        next_request_number(&search_request_counter),
+                    )
+                }
+            }),
+        )
+        .route(
+            "/v0/solve/pattern/stream",
             post({
                 move |body| {
-                    let mut counter = search_request_counter
-                        .lock()
-                        .expect("Internal error: could not access request counter");
-                    *counter += 1;
-                    let local_counter = *counter;
-                    drop(counter);
-                    solve_pattern(body, serve_command_args.clone(), local_counter)
+                    solve_pattern_stream(
+                        body,
+                        serve_command_args.clone(),
+                        search_cache.clone(),
+                        next_request_number(&search_request_counter),
+                    )
                 }
             }),
         )
@@ -7,7 +7,8 @@ use std::{
 };
 
 use twips::scramble::{
-    derive_scramble_for_event_seeded, random_scramble_for_event,
+    derive_multi_blind_scramble_set_seeded, derive_scramble_for_event_seeded,
+    random_multi_blind_scramble_set, random_scramble_for_event,
     scramble_finder::free_memory_for_all_scramble_finders, DerivationSalt, DerivationSeed, Event,
 };
 
@@ -83,15 +84,7 @@ fn ffi_derive_scramble_for_event_internal(
     let subevent_str = war_cstr_to_rust_str_ref(subevent_raw_cstr)?;
 
     let derivation_seed = DerivationSeed::from_str(hex_derivation_seed_str).map_err(|_| ())?;
-    let hierarchy = if derivation_salt_hierarchy_str.is_empty() {
-        vec![]
-    } else {
-        derivation_salt_hierarchy_str
-            .split("/")
-            .map(DerivationSalt::from_str)
-            .collect::<Result<Vec<DerivationSalt>, String>>()
-            .map_err(|_| ())?
-    };
+    let hierarchy = parse_derivation_salt_hierarchy(derivation_salt_hierarchy_str)?;
     let subevent = Event::try_from(subevent_str)
         .map_err(|e| e.description)
         .map_err(|_| ())?;
@@ -101,6 +94,85 @@ fn ffi_derive_scramble_for_event_internal(
     }
 }
 
+fn parse_derivation_salt_hierarchy(
+    // Blank string or a slash-separated hierarchy
+    derivation_salt_hierarchy_str: &str,
+) -> Result<Vec<DerivationSalt>, ()> {
+    if derivation_salt_hierarchy_str.is_empty() {
+        return Ok(vec![]);
+    }
+    derivation_salt_hierarchy_str
+        .split("/")
+        .map(DerivationSalt::from_str)
+        .collect::<Result<Vec<DerivationSalt>, String>>()
+        .map_err(|_| ())
+}
+
+/// # Safety
+///
+/// This function can panic. If you are working in pure Rust, use [`twips::scramble::random_multi_blind_scramble_set`] instead.
+///
+/// Returns:
+/// - A null pointer for *any* error.
+/// - A valid set of scrambles (in the form of a C string with one scramble per line) otherwise.
+#[no_mangle]
+pub extern "C" fn ffi_random_multi_blind_scramble_set(num_cubes: u32) -> *const c_char {
+    unwrap_cstr_result_or_null_ptr(ffi_random_multi_blind_scramble_set_internal(num_cubes))
+}
+
+fn ffi_random_multi_blind_scramble_set_internal(num_cubes: u32) -> Result<*const c_char, ()> {
+    let scrambles = random_multi_blind_scramble_set(num_cubes as usize).map_err(|_| ())?;
+    Ok(rust_str_to_raw_cstr(&join_scramble_lines(&scrambles)))
+}
+
+/// # Safety
+///
+/// This function can panic. If you are working in pure Rust, use [`twips::scramble::derive_multi_blind_scramble_set_seeded`] instead.
+///
+/// Returns:
+/// - A null pointer for *any* error.
+/// - A valid set of derived scrambles (in the form of a C string with one scramble per line) otherwise.
+#[no_mangle]
+pub extern "C" fn ffi_derive_multi_blind_scramble_set(
+    hex_derivation_seed_cstr: *const c_char,
+    // A slash-separated hierarchy that ends at the attempt level.
+    derivation_salt_hierarchy_str: *const c_char,
+    num_cubes: u32,
+) -> *const c_char {
+    unwrap_cstr_result_or_null_ptr(ffi_derive_multi_blind_scramble_set_internal(
+        hex_derivation_seed_cstr,
+        derivation_salt_hierarchy_str,
+        num_cubes,
+    ))
+}
+
+fn ffi_derive_multi_blind_scramble_set_internal(
+    hex_derivation_seed_raw_cstr: *const c_char,
+    // Blank string or a slash-separated hierarchy
+    derivation_salt_hierarchy_raw_cstr: *const c_char,
+    num_cubes: u32,
+) -> Result<*const c_char, ()> {
+    let hex_derivation_seed_str = war_cstr_to_rust_str_ref(hex_derivation_seed_raw_cstr)?;
+    let derivation_salt_hierarchy_str =
+        war_cstr_to_rust_str_ref(derivation_salt_hierarchy_raw_cstr)?;
+
+    let derivation_seed = DerivationSeed::from_str(hex_derivation_seed_str).map_err(|_| ())?;
+    let hierarchy = parse_derivation_salt_hierarchy(derivation_salt_hierarchy_str)?;
+    let scramble_set =
+        derive_multi_blind_scramble_set_seeded(&derivation_seed, &hierarchy, num_cubes as usize)
+            .map_err(|_| ())?;
+    Ok(rust_str_to_raw_cstr(&join_scramble_lines(&scramble_set)))
+}
+
+// 3x3x3 scrambles never contain newlines, so we can use them as a separator.
+fn join_scramble_lines<T: ToString>(scrambles: &[T]) -> String {
+    scrambles
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
 #[no_mangle]
 pub extern "C" fn ffi_free_memory_for_all_scramble_finders() -> u32 {
     // We cast to `u32` for the public API so that it's more stable across environments (including WASM).
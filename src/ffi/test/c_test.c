@@ -14,6 +14,9 @@ int main() {
   puts("Derived scramble: ");
   puts(ffi_derive_scramble_for_event("67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67", "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF/scrambles/333/r1/g1/a1/333/sub1", CUBING_EVENT_333));
 
+  puts("Derived multi-blind scramble set: ");
+  puts(ffi_derive_multi_blind_scramble_set("67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67", "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF/scrambles/333mbf/r1/g1/a1", 3));
+
   printf("Freed %u scramble finder(s).\n", ffi_free_memory_for_all_scramble_finders()); 
 
   generate_scramble(CUBING_EVENT_222);
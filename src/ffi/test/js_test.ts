@@ -10,6 +10,7 @@ const {
     ffi_random_scramble_for_event,
     ffi_free_memory_for_all_scramble_finders,
     ffi_derive_scramble_for_event,
+    ffi_derive_multi_blind_scramble_set,
   },
 } = dlopen(
   import.meta.resolve(`../../../target/release/libtwips_ffi.${suffix}`),
@@ -26,6 +27,10 @@ const {
       args: [FFIType.cstring, FFIType.cstring, FFIType.cstring],
       returns: FFIType.cstring,
     },
+    ffi_derive_multi_blind_scramble_set: {
+      args: [FFIType.cstring, FFIType.cstring, FFIType.u32],
+      returns: FFIType.cstring,
+    },
   },
 );
 
@@ -59,6 +64,26 @@ assert(
 );
 console.log("✅ Derived scramble is okay.");
 
+{
+  const deriveScrambleSet = (numCubes: number) =>
+    ffi_derive_multi_blind_scramble_set(
+      new TextEncoder().encode(
+        "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+      ),
+      new TextEncoder().encode(
+        "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF/scrambles/333mbf/r1/g1/a1",
+      ),
+      numCubes,
+    )
+      .toString()
+      .split("\n");
+  const scrambleSet = deriveScrambleSet(3);
+  assert.equal(scrambleSet.length, 3);
+  assert.equal(new Set(scrambleSet).size, 3);
+  assert.deepEqual(deriveScrambleSet(2), scrambleSet.slice(0, 2));
+  console.log("✅ Derived multi-blind scramble set is okay.");
+}
+
 {
   const numScrambleFindersFreed = ffi_free_memory_for_all_scramble_finders();
   assert.equal(numScrambleFindersFreed, 1);
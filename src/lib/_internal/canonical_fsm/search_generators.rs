@@ -1,8 +1,8 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use cubing::{
-    alg::{Alg, Move, QuantumMove},
-    kpuzzle::{InvalidAlgError, KPuzzle},
+    alg::{Alg, AlgNode, Grouping, Move, QuantumMove},
+    kpuzzle::{InvalidAlgError, KPattern, KPuzzle, KPuzzleDefinition},
 };
 
 use crate::{
@@ -59,18 +59,142 @@ impl From<Vec<Move>> for Generators {
 }
 
 impl Generators {
+    /// Like [`Generators::Default`], but with the moves sorted by name. The
+    /// definition stores moves in a `HashMap`, so the default order can differ
+    /// between runs. Resuming a search from a checkpoint requires the same
+    /// search order as the original search.
+    pub fn default_in_stable_order(kpuzzle: &KPuzzle) -> Self {
+        let mut moves = kpuzzle.puzzle_definition_all_moves();
+        moves.sort_by_cached_key(|r#move| r#move.to_string());
+        Self::Custom {
+            moves,
+            algs: vec![],
+        }
+    }
+
+    /// Alg generators are ignored by this function. Use
+    /// [`Generators::kpuzzle_with_generator_moves`] to support them.
     pub fn enumerate_moves_for_kpuzzle(&self, kpuzzle: &KPuzzle) -> Vec<Move> {
         match self {
             Generators::Default => kpuzzle.puzzle_definition_all_moves(),
             Generators::Custom { moves, algs } => {
                 if !algs.is_empty() {
-                    eprintln!("WARNING: Alg generators are not implemented yet. Ignoring.");
+                    eprintln!("WARNING: Alg generators are not supported here. Ignoring.");
                 };
                 // TODO: avoid a clone?
                 moves.clone()
             }
         }
     }
+
+    /// Returns a puzzle that has a derived move for each alg generator (if
+    /// there are any), together with the moves to use for search.
+    pub fn kpuzzle_with_generator_moves(
+        &self,
+        kpuzzle: &KPuzzle,
+    ) -> Result<KPuzzleWithGeneratorMoves, SearchError> {
+        let (moves, algs) = match self {
+            Generators::Custom { moves, algs } if !algs.is_empty() => (moves, algs),
+            _ => {
+                return Ok(KPuzzleWithGeneratorMoves {
+                    kpuzzle: kpuzzle.clone(),
+                    generator_moves: self.enumerate_moves_for_kpuzzle(kpuzzle),
+                    alg_generators: HashMap::default(),
+                })
+            }
+        };
+
+        // `KPuzzleDefinition` doesn't implement `Clone`, so we round-trip it through JSON instead.
+        let mut definition: KPuzzleDefinition = serde_json::to_value(kpuzzle.definition())
+            .and_then(serde_json::from_value)
+            .map_err(|e| SearchError {
+                description: e.to_string(),
+            })?;
+        let mut derived_moves = definition.derived_moves.take().unwrap_or_default();
+        let mut generator_moves = moves.clone();
+        let mut alg_generators = HashMap::<QuantumMove, Alg>::default();
+        for (i, alg) in algs.iter().enumerate() {
+            let alg_generator_move = Move {
+                quantum: QuantumMove::new(format!("AlgGenerator{}", i + 1), None).into(),
+                amount: 1,
+            };
+            if definition.moves.contains_key(&alg_generator_move)
+                || derived_moves.contains_key(&alg_generator_move)
+            {
+                return Err(SearchError {
+                    description: format!(
+                        "Puzzle definition already has a move named {}, which is needed for alg generators.",
+                        alg_generator_move
+                    ),
+                });
+            }
+            // Check the alg up front to get a more helpful error than for the derived move.
+            if let Err(e) = kpuzzle.transformation_from_alg(alg) {
+                return Err(SearchError {
+                    description: format!("Invalid alg generator ({}): {}", alg, e),
+                });
+            }
+            derived_moves.insert(alg_generator_move.clone(), alg.clone());
+            alg_generators.insert(alg_generator_move.quantum.as_ref().clone(), alg.clone());
+            generator_moves.push(alg_generator_move);
+        }
+        definition.derived_moves = Some(derived_moves);
+        let kpuzzle = KPuzzle::try_new(definition).map_err(|e| SearchError {
+            description: e.to_string(),
+        })?;
+        Ok(KPuzzleWithGeneratorMoves {
+            kpuzzle,
+            generator_moves,
+            alg_generators,
+        })
+    }
+}
+
+/// See [`Generators::kpuzzle_with_generator_moves`].
+#[derive(Clone, Debug)]
+pub struct KPuzzleWithGeneratorMoves {
+    /// Patterns for the original puzzle must be converted using
+    /// [`KPuzzleWithGeneratorMoves::convert_pattern`] to use them with this.
+    pub kpuzzle: KPuzzle,
+    pub generator_moves: Vec<Move>,
+    alg_generators: HashMap<QuantumMove, Alg>,
+}
+
+impl KPuzzleWithGeneratorMoves {
+    pub fn convert_pattern(&self, pattern: &KPattern) -> Result<KPattern, SearchError> {
+        if self.alg_generators.is_empty() {
+            return Ok(pattern.clone());
+        }
+        KPattern::try_from_data(&self.kpuzzle, &pattern.to_data()).map_err(|e| SearchError {
+            description: e.to_string(),
+        })
+    }
+
+    /// Replaces every alg generator move in the given alg with the
+    /// corresponding alg. An alg generator move with an amount other than 1
+    /// is expanded into a grouping (e.g. `(R U R' U')2`).
+    pub fn expand_alg_generators(&self, alg: &Alg) -> Alg {
+        let mut nodes = vec![];
+        for node in &alg.nodes {
+            let AlgNode::MoveNode(r#move) = node else {
+                nodes.push(node.clone());
+                continue;
+            };
+            let Some(alg_generator) = self.alg_generators.get(&r#move.quantum) else {
+                nodes.push(node.clone());
+                continue;
+            };
+            if r#move.amount == 1 {
+                nodes.extend(alg_generator.nodes.iter().cloned());
+            } else {
+                nodes.push(AlgNode::GroupingNode(Grouping {
+                    alg: Arc::new(alg_generator.clone()),
+                    amount: r#move.amount,
+                }));
+            }
+        }
+        Alg { nodes }
+    }
 }
 
 #[derive(Clone, Debug)]
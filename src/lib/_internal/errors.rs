@@ -8,6 +8,17 @@ pub enum TwipsError {
     EventError(EventError),
 }
 
+impl TwipsError {
+    pub fn description(&self) -> &str {
+        match self {
+            TwipsError::ArgumentError(e) => &e.description,
+            TwipsError::SearchError(e) => &e.description,
+            TwipsError::PuzzleError(e) => &e.description,
+            TwipsError::EventError(e) => &e.description,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ArgumentError {
     pub description: String,
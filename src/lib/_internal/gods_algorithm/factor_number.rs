@@ -33,6 +33,9 @@ pub struct Factorization {
 
 impl Display for Factorization {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.prime_powers.is_empty() {
+            return write!(f, "1");
+        }
         let primer_powers_list: Vec<String> =
             self.prime_powers.iter().map(|p| p.to_string()).collect();
         write!(f, "{}", primer_powers_list.join(" × "))
@@ -62,3 +65,21 @@ fn factor_number_from(n: u64, from: u64) -> Factorization {
 pub fn factor_number(n: u64) -> Factorization {
     factor_number_from(n, 2)
 }
+
+/// Factors a product of numbers without computing the product itself (which
+/// may not fit in a `u64`).
+pub fn factor_product(factors: impl IntoIterator<Item = u64>) -> Factorization {
+    let mut prime_powers: Vec<PrimePower> = vec![];
+    for factor in factors {
+        if factor <= 1 {
+            continue;
+        }
+        for prime_power in factor_number(factor).prime_powers {
+            match prime_powers.binary_search_by_key(&prime_power.prime, |existing| existing.prime) {
+                Ok(idx) => prime_powers[idx].power += prime_power.power,
+                Err(idx) => prime_powers.insert(idx, prime_power),
+            }
+        }
+    }
+    Factorization { prime_powers }
+}
@@ -77,9 +77,15 @@ impl GodsAlgorithmSearch {
         metric: TurnMetric,
     ) -> Result<Self, SearchError> {
         let depth_to_patterns = vec![];
+        // Patterns compare by value, so the table can be used with patterns for the original puzzle.
+        let kpuzzle_with_generator_moves = generators.kpuzzle_with_generator_moves(&kpuzzle)?;
+        let start_pattern = start_pattern
+            .map(|start_pattern| kpuzzle_with_generator_moves.convert_pattern(&start_pattern))
+            .transpose()?;
+        let kpuzzle = kpuzzle_with_generator_moves.kpuzzle;
         let search_generators = SearchGenerators::try_new(
             &kpuzzle,
-            generators.enumerate_moves_for_kpuzzle(&kpuzzle),
+            kpuzzle_with_generator_moves.generator_moves,
             SearchGeneratorsConstructorOptions {
                 metric: Some(metric),
                 ..Default::default()
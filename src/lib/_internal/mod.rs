@@ -4,4 +4,5 @@ pub mod gods_algorithm;
 pub mod notation;
 pub mod puzzle_traits;
 pub mod read_to_json;
+pub mod schreier_sims;
 pub mod search;
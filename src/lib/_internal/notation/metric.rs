@@ -1,7 +1,10 @@
 use std::{fmt::Display, str::FromStr};
 
+use cubing::alg::{Alg, AlgNode};
 use serde::{Deserialize, Serialize};
 
+use crate::_internal::search::move_count::MoveCount;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
 pub enum TurnMetric {
     #[default]
@@ -30,3 +33,35 @@ impl FromStr for TurnMetric {
         })
     }
 }
+
+impl TurnMetric {
+    /// Counts the moves in `alg` in this metric. Groupings, commutators and
+    /// conjugates are counted as if they were expanded.
+    pub fn move_count(&self, alg: &Alg) -> MoveCount {
+        MoveCount(
+            alg.nodes
+                .iter()
+                .map(|node| self.node_move_count(node))
+                .sum(),
+        )
+    }
+
+    fn node_move_count(&self, node: &AlgNode) -> usize {
+        match node {
+            AlgNode::MoveNode(r#move) => match self {
+                TurnMetric::Hand => 1,
+                TurnMetric::Quantum => r#move.amount.unsigned_abs() as usize,
+            },
+            AlgNode::GroupingNode(grouping) => {
+                self.move_count(&grouping.alg).0 * grouping.amount.unsigned_abs() as usize
+            }
+            AlgNode::CommutatorNode(commutator) => {
+                2 * (self.move_count(&commutator.a).0 + self.move_count(&commutator.b).0)
+            }
+            AlgNode::ConjugateNode(conjugate) => {
+                2 * self.move_count(&conjugate.a).0 + self.move_count(&conjugate.b).0
+            }
+            AlgNode::PauseNode(_) | AlgNode::NewlineNode(_) | AlgNode::LineCommentNode(_) => 0,
+        }
+    }
+}
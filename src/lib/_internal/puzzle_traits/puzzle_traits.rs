@@ -6,15 +6,17 @@ use crate::_internal::search::move_count::MoveCount;
 
 // TODO: split this into 3 related traits.
 /// The `Clone` implementation must be cheap for both the main struct as well as the `Pattern` and `Transformation` types (e.g. implemented using data shared with an `Arc` under the hood whenever any non-trivial amount of data is associated).
-pub trait SemiGroupActionPuzzle: Debug + Clone {
-    type Pattern: Eq + Clone + Debug;
+///
+/// The puzzle, its patterns and its transformations are shared between threads in a multi-threaded search or prune table fill, so they must be `Send + Sync`.
+pub trait SemiGroupActionPuzzle: Debug + Clone + Send + Sync {
+    type Pattern: Eq + Clone + Debug + Send + Sync;
     /// This is a proper "transformation" (such as a permutation) in the general
     /// case, but for `GenericPuzzleCore` it can be anything that is applied to a
     /// pattern, such as:
     ///
     /// - A [`Move`]
     /// - An index or reference into an array that encodes how to apply it
-    type Transformation: Eq + Clone + Debug;
+    type Transformation: Eq + Clone + Debug + Send + Sync;
 
     // /********* Functions "defined on the puzzle". ********/
     // fn puzzle_default_pattern(&self) -> Self::Pattern;
@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+use crate::_internal::gods_algorithm::factor_number::{factor_product, Factorization};
+
+const DECIMAL_LIMB_BASE: u64 = 1_000_000_000;
+
+/// The order of a permutation group, stored as the product of the orbit
+/// lengths of its stabilizer chain. This can be much larger than a `u128` for
+/// bigger puzzles, so we only multiply it out for display.
+#[derive(Clone, Debug, Default)]
+pub struct GroupOrder {
+    orbit_lengths: Vec<u64>,
+}
+
+impl GroupOrder {
+    pub(crate) fn from_orbit_lengths(orbit_lengths: Vec<u64>) -> Self {
+        Self { orbit_lengths }
+    }
+
+    pub fn orbit_lengths(&self) -> &[u64] {
+        &self.orbit_lengths
+    }
+
+    /// Returns `None` if the order does not fit in a `u128`.
+    pub fn to_u128(&self) -> Option<u128> {
+        self.orbit_lengths
+            .iter()
+            .try_fold(1u128, |product, orbit_length| {
+                product.checked_mul(*orbit_length as u128)
+            })
+    }
+
+    pub fn factorization(&self) -> Factorization {
+        factor_product(self.orbit_lengths.iter().copied())
+    }
+}
+
+impl Display for GroupOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Little-endian limbs in base 10⁹.
+        let mut limbs: Vec<u64> = vec![1];
+        for orbit_length in &self.orbit_lengths {
+            let mut carry: u128 = 0;
+            for limb in limbs.iter_mut() {
+                let value = (*limb as u128) * (*orbit_length as u128) + carry;
+                *limb = (value % DECIMAL_LIMB_BASE as u128) as u64;
+                carry = value / DECIMAL_LIMB_BASE as u128;
+            }
+            while carry > 0 {
+                limbs.push((carry % DECIMAL_LIMB_BASE as u128) as u64);
+                carry /= DECIMAL_LIMB_BASE as u128;
+            }
+        }
+        let mut limbs_iter = limbs.iter().rev();
+        if let Some(most_significant_limb) = limbs_iter.next() {
+            write!(f, "{}", most_significant_limb)?;
+        }
+        for limb in limbs_iter {
+            write!(f, "{:09}", limb)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod group_order;
+pub(crate) mod permutation;
+pub mod reachability;
+#[allow(clippy::module_inception)] // TODO
+pub mod schreier_sims;
@@ -0,0 +1,51 @@
+/// A permutation of the points `0..n`, stored as the image of each point.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Permutation {
+    images: Vec<u32>,
+}
+
+impl Permutation {
+    pub fn identity(num_points: usize) -> Self {
+        Self {
+            images: (0..num_points as u32).collect(),
+        }
+    }
+
+    pub fn from_images(images: Vec<u32>) -> Self {
+        Self { images }
+    }
+
+    pub fn num_points(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn image(&self, point: usize) -> usize {
+        self.images[point] as usize
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.images
+            .iter()
+            .enumerate()
+            .all(|(point, image)| point == *image as usize)
+    }
+
+    /// Returns the permutation that applies `self` and then `other`.
+    pub fn then(&self, other: &Permutation) -> Permutation {
+        Permutation {
+            images: self
+                .images
+                .iter()
+                .map(|image| other.images[*image as usize])
+                .collect(),
+        }
+    }
+
+    pub fn invert(&self) -> Permutation {
+        let mut images = vec![0; self.images.len()];
+        for (point, image) in self.images.iter().enumerate() {
+            images[*image as usize] = point as u32;
+        }
+        Permutation { images }
+    }
+}
@@ -0,0 +1,397 @@
+use cubing::{
+    alg::Move,
+    kpuzzle::{KPattern, KPuzzle, KPuzzleOrbitInfo, KTransformation},
+};
+
+use crate::_internal::errors::SearchError;
+
+use super::schreier_sims::{KPuzzlePointIndexer, StabilizerChain};
+
+/// With [`ReachabilityCheck::Auto`], the full membership test is only run if
+/// the checked orbits have at most this many (piece, orientation) points.
+const AUTO_MEMBERSHIP_TEST_MAX_NUM_POINTS: usize = 256;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReachabilityCheck {
+    /// Check permutation parity and orientation invariants, and only run the
+    /// full membership test for small puzzles.
+    #[default]
+    Auto,
+    Never,
+    /// Check invariants and always run the full membership test.
+    Always,
+}
+
+/// Checks whether the search pattern can reach at least one of the target
+/// patterns using the generator moves.
+///
+/// This is a necessary condition rather than a sufficient one: orbits with
+/// identical pieces (or ignored orientations) can only be checked partially.
+pub fn check_reachability(
+    kpuzzle: &KPuzzle,
+    generator_moves: &[Move],
+    search_pattern: &KPattern,
+    target_patterns: &[KPattern],
+    reachability_check: ReachabilityCheck,
+) -> Result<(), SearchError> {
+    if reachability_check == ReachabilityCheck::Never {
+        return Ok(());
+    }
+    let generator_transformations = generator_moves
+        .iter()
+        .map(|generator_move| {
+            kpuzzle
+                .transformation_from_move(generator_move)
+                .map_err(|e| SearchError {
+                    description: e.to_string(),
+                })
+        })
+        .collect::<Result<Vec<KTransformation>, SearchError>>()?;
+
+    let mut first_unreachable_reason = None;
+    for target_pattern in target_patterns {
+        match check_reachability_of_target_pattern(
+            kpuzzle,
+            &generator_transformations,
+            search_pattern,
+            target_pattern,
+            reachability_check,
+        ) {
+            Ok(()) => return Ok(()),
+            Err(reason) => {
+                first_unreachable_reason.get_or_insert(reason);
+            }
+        }
+    }
+    match first_unreachable_reason {
+        None => Ok(()),
+        Some(reason) => Err(SearchError {
+            description: format!(
+                "The search pattern cannot reach {} using the generator moves: {}",
+                if target_patterns.len() == 1 {
+                    "the target pattern"
+                } else {
+                    "any of the target patterns"
+                },
+                reason
+            ),
+        }),
+    }
+}
+
+struct OrbitDistinguishability {
+    has_identical_pieces: bool,
+    has_ignored_orientations: bool,
+}
+
+impl OrbitDistinguishability {
+    fn new(pattern: &KPattern, orbit_info: &KPuzzleOrbitInfo) -> Self {
+        let mut seen_pieces = vec![false; orbit_info.num_pieces as usize];
+        let mut has_identical_pieces = false;
+        let mut has_ignored_orientations = false;
+        for i in 0..orbit_info.num_pieces {
+            let piece = pattern.get_piece(orbit_info, i) as usize;
+            has_identical_pieces |= seen_pieces[piece];
+            seen_pieces[piece] = true;
+            let orientation_mod = pattern
+                .get_orientation_with_mod(orbit_info, i)
+                .orientation_mod;
+            has_ignored_orientations |=
+                orientation_mod != 0 && orientation_mod != orbit_info.num_orientations;
+        }
+        Self {
+            has_identical_pieces,
+            has_ignored_orientations,
+        }
+    }
+}
+
+fn check_reachability_of_target_pattern(
+    kpuzzle: &KPuzzle,
+    generator_transformations: &[KTransformation],
+    search_pattern: &KPattern,
+    target_pattern: &KPattern,
+    reachability_check: ReachabilityCheck,
+) -> Result<(), String> {
+    let orbit_infos: Vec<&KPuzzleOrbitInfo> = kpuzzle.orbit_info_iter().collect();
+    let mut has_distinct_pieces = vec![];
+    let mut has_exact_orientations = vec![];
+    for orbit_info in &orbit_infos {
+        let search_orbit = OrbitDistinguishability::new(search_pattern, orbit_info);
+        let target_orbit = OrbitDistinguishability::new(target_pattern, orbit_info);
+        has_distinct_pieces
+            .push(!search_orbit.has_identical_pieces && !target_orbit.has_identical_pieces);
+        has_exact_orientations
+            .push(!search_orbit.has_ignored_orientations && !target_orbit.has_ignored_orientations);
+    }
+
+    // Orientation sums: every generator changes the orientation sum of an
+    // orbit by a fixed amount, so the difference between the patterns must be
+    // a combination of these amounts.
+    for (orbit_info, has_exact_orientations) in orbit_infos.iter().zip(&has_exact_orientations) {
+        if !has_exact_orientations {
+            continue;
+        }
+        let num_orientations = orbit_info.num_orientations as u32;
+        let reachable_orientation_sum_modulus =
+            generator_transformations
+                .iter()
+                .fold(num_orientations, |modulus, transformation| {
+                    gcd(
+                        modulus,
+                        orientation_delta_sum(transformation, orbit_info) % num_orientations,
+                    )
+                });
+        let search_sum = orientation_sum(search_pattern, orbit_info);
+        let target_sum = orientation_sum(target_pattern, orbit_info);
+        if !(search_sum + num_orientations - target_sum)
+            .is_multiple_of(reachable_orientation_sum_modulus)
+        {
+            return Err(format!(
+                "the total orientation of the `{}` orbit is impossible.",
+                orbit_info.name
+            ));
+        }
+    }
+
+    // Permutation parity: the parities of all orbits with distinct pieces form
+    // a vector over GF(2), and each generator adds its own parity vector.
+    let parity_orbit_infos: Vec<&KPuzzleOrbitInfo> = orbit_infos
+        .iter()
+        .zip(&has_distinct_pieces)
+        .filter(|(_, has_distinct_pieces)| **has_distinct_pieces)
+        .map(|(orbit_info, _)| *orbit_info)
+        .collect();
+    let mut parity_basis = ParityBasis::default();
+    for transformation in generator_transformations {
+        parity_basis.insert(
+            parity_orbit_infos
+                .iter()
+                .map(|orbit_info| {
+                    permutation_parity(
+                        (0..orbit_info.num_pieces)
+                            .map(|i| transformation.get_permutation_idx(orbit_info, i)),
+                    )
+                })
+                .collect(),
+        );
+    }
+    let relative_parity: Vec<bool> = parity_orbit_infos
+        .iter()
+        .map(|orbit_info| {
+            pattern_parity(search_pattern, orbit_info) != pattern_parity(target_pattern, orbit_info)
+        })
+        .collect();
+    if !parity_basis.spans(relative_parity.clone()) {
+        let orbit_names: Vec<String> = parity_orbit_infos
+            .iter()
+            .zip(relative_parity)
+            .filter(|(_, is_odd)| *is_odd)
+            .map(|(orbit_info, _)| format!("`{}`", orbit_info.name))
+            .collect();
+        return Err(format!(
+            "the permutation parity of the {} orbit(s) is impossible.",
+            orbit_names.join(", ")
+        ));
+    }
+
+    // Full membership test, restricted to the orbits in which every piece is
+    // fully distinguishable in both patterns.
+    let membership_orbits: Vec<bool> = has_distinct_pieces
+        .iter()
+        .zip(&has_exact_orientations)
+        .map(|(has_distinct_pieces, has_exact_orientations)| {
+            *has_distinct_pieces && *has_exact_orientations
+        })
+        .collect();
+    let point_indexer = KPuzzlePointIndexer::new_for_orbits(kpuzzle, &membership_orbits);
+    let run_membership_test = match reachability_check {
+        ReachabilityCheck::Auto => point_indexer.num_points <= AUTO_MEMBERSHIP_TEST_MAX_NUM_POINTS,
+        ReachabilityCheck::Never => false,
+        ReachabilityCheck::Always => true,
+    };
+    if !run_membership_test || point_indexer.num_points == 0 {
+        return Ok(());
+    }
+    let mut stabilizer_chain = StabilizerChain::new(point_indexer.num_points);
+    for transformation in generator_transformations {
+        stabilizer_chain.add_generator(point_indexer.permutation(transformation));
+    }
+    let relative_transformation = relative_transformation(
+        kpuzzle,
+        search_pattern,
+        target_pattern,
+        &orbit_infos,
+        &membership_orbits,
+    );
+    if !stabilizer_chain.contains(point_indexer.permutation(&relative_transformation)) {
+        return Err("the pattern is not in the group generated by the generator moves.".to_owned());
+    }
+    Ok(())
+}
+
+/// Returns a transformation `t` such that applying `t` to the target pattern
+/// produces the search pattern (for the included orbits only).
+fn relative_transformation(
+    kpuzzle: &KPuzzle,
+    search_pattern: &KPattern,
+    target_pattern: &KPattern,
+    orbit_infos: &[&KPuzzleOrbitInfo],
+    included_orbits: &[bool],
+) -> KTransformation {
+    let mut transformation = kpuzzle.identity_transformation();
+    for (orbit_info, is_included) in orbit_infos.iter().zip(included_orbits) {
+        if !is_included {
+            continue;
+        }
+        let num_orientations = orbit_info.num_orientations;
+        let mut target_positions = vec![0; orbit_info.num_pieces as usize];
+        for j in 0..orbit_info.num_pieces {
+            target_positions[target_pattern.get_piece(orbit_info, j) as usize] = j;
+        }
+        for i in 0..orbit_info.num_pieces {
+            let j = target_positions[search_pattern.get_piece(orbit_info, i) as usize];
+            let search_orientation = search_pattern
+                .get_orientation_with_mod(orbit_info, i)
+                .orientation;
+            let target_orientation = target_pattern
+                .get_orientation_with_mod(orbit_info, j)
+                .orientation;
+            transformation.set_permutation_idx(orbit_info, i, j);
+            transformation.set_orientation_delta(
+                orbit_info,
+                i,
+                (search_orientation + num_orientations - target_orientation) % num_orientations,
+            );
+        }
+    }
+    transformation
+}
+
+fn orientation_sum(pattern: &KPattern, orbit_info: &KPuzzleOrbitInfo) -> u32 {
+    (0..orbit_info.num_pieces)
+        .map(|i| pattern.get_orientation_with_mod(orbit_info, i).orientation as u32)
+        .sum::<u32>()
+        % orbit_info.num_orientations as u32
+}
+
+fn orientation_delta_sum(transformation: &KTransformation, orbit_info: &KPuzzleOrbitInfo) -> u32 {
+    (0..orbit_info.num_pieces)
+        .map(|i| transformation.get_orientation_delta(orbit_info, i) as u32)
+        .sum()
+}
+
+fn pattern_parity(pattern: &KPattern, orbit_info: &KPuzzleOrbitInfo) -> bool {
+    permutation_parity((0..orbit_info.num_pieces).map(|i| pattern.get_piece(orbit_info, i)))
+}
+
+/// Returns `true` for odd permutations of `0..n`.
+fn permutation_parity(images: impl Iterator<Item = u8>) -> bool {
+    let images: Vec<usize> = images.map(|image| image as usize).collect();
+    let mut visited = vec![false; images.len()];
+    let mut num_cycles = 0;
+    for start in 0..images.len() {
+        if visited[start] {
+            continue;
+        }
+        num_cycles += 1;
+        let mut current = start;
+        while !visited[current] {
+            visited[current] = true;
+            current = images[current];
+        }
+    }
+    (images.len() - num_cycles) % 2 == 1
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// A basis of a subspace of GF(2)ⁿ in row echelon form.
+#[derive(Default)]
+struct ParityBasis {
+    /// Each vector is `true` at its pivot index, and `false` at the pivot index of every earlier vector.
+    vectors: Vec<(usize, Vec<bool>)>,
+}
+
+impl ParityBasis {
+    fn reduce(&self, mut vector: Vec<bool>) -> Vec<bool> {
+        for (pivot, basis_vector) in &self.vectors {
+            if vector[*pivot] {
+                for (entry, basis_entry) in vector.iter_mut().zip(basis_vector) {
+                    *entry ^= basis_entry;
+                }
+            }
+        }
+        vector
+    }
+
+    fn insert(&mut self, vector: Vec<bool>) {
+        let vector = self.reduce(vector);
+        if let Some(pivot) = vector.iter().position(|entry| *entry) {
+            self.vectors.push((pivot, vector));
+        }
+    }
+
+    fn spans(&self, vector: Vec<bool>) -> bool {
+        !self.reduce(vector).contains(&true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cubing::{
+        alg::{parse_alg, parse_move},
+        kpuzzle::KPattern,
+        puzzles::cube3x3x3_kpuzzle,
+    };
+
+    use super::{check_reachability, ReachabilityCheck};
+
+    #[test]
+    fn reachability_test() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let generator_moves = ["U", "L", "F", "R", "B", "D"]
+            .map(|r#move| r#move.parse().unwrap())
+            .to_vec();
+        let target_patterns = vec![kpuzzle.default_pattern()];
+        let check = |pattern, generator_moves, reachability_check| {
+            check_reachability(
+                kpuzzle,
+                generator_moves,
+                pattern,
+                &target_patterns,
+                reachability_check,
+            )
+        };
+
+        let scrambled = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U F' D2 L B"))
+            .unwrap();
+        assert!(check(&scrambled, &generator_moves, ReachabilityCheck::Always).is_ok());
+
+        let mut twisted_data = scrambled.to_data();
+        let corners = twisted_data.get_mut(&"CORNERS".into()).unwrap();
+        corners.orientation[0] = (corners.orientation[0] + 1) % 3;
+        let twisted = KPattern::try_from_data(kpuzzle, &twisted_data).unwrap();
+        let error = check(&twisted, &generator_moves, ReachabilityCheck::Auto).unwrap_err();
+        assert!(error.description.contains("total orientation"));
+        assert!(check(&twisted, &generator_moves, ReachabilityCheck::Never).is_ok());
+
+        // A single `F` turn has the same parity and orientation invariants as
+        // `U` or `R`, but it is not in the group generated by them.
+        let two_gen = vec![parse_move!("U").clone(), parse_move!("R").clone()];
+        let swapped = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("F"))
+            .unwrap();
+        let error = check(&swapped, &two_gen, ReachabilityCheck::Always).unwrap_err();
+        assert!(error.description.contains("not in the group"));
+    }
+}
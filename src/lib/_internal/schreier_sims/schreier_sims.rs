@@ -0,0 +1,303 @@
+use cubing::{
+    alg::Move,
+    kpuzzle::{KPattern, KPuzzle, KPuzzleOrbitName, KTransformation},
+};
+use rand::Rng;
+
+use crate::_internal::errors::SearchError;
+
+use super::{group_order::GroupOrder, permutation::Permutation};
+
+struct StabilizerChainLevel {
+    base_point: usize,
+    /// Indexed by the image of `base_point`. Each entry holds a coset
+    /// representative and its inverse.
+    coset_representatives: Vec<Option<(Permutation, Permutation)>>,
+    generators: Vec<Permutation>,
+}
+
+/// A stabilizer chain for a permutation group, built incrementally using
+/// Knuth's variant of the Schreier-Sims algorithm.
+///
+/// Level `i` represents the subgroup that fixes the base points of all previous
+/// levels. We use every point as a base point, in order.
+pub struct StabilizerChain {
+    levels: Vec<StabilizerChainLevel>,
+}
+
+impl StabilizerChain {
+    pub(crate) fn new(num_points: usize) -> Self {
+        let levels = (0..num_points)
+            .map(|base_point| {
+                let mut coset_representatives = vec![None; num_points];
+                coset_representatives[base_point] = Some((
+                    Permutation::identity(num_points),
+                    Permutation::identity(num_points),
+                ));
+                StabilizerChainLevel {
+                    base_point,
+                    coset_representatives,
+                    generators: vec![],
+                }
+            })
+            .collect();
+        Self { levels }
+    }
+
+    pub(crate) fn add_generator(&mut self, permutation: Permutation) {
+        assert_eq!(permutation.num_points(), self.levels.len());
+        self.extend(0, permutation);
+    }
+
+    pub fn order(&self) -> GroupOrder {
+        GroupOrder::from_orbit_lengths(
+            self.levels
+                .iter()
+                .map(|level| level.coset_representatives.iter().flatten().count() as u64)
+                .filter(|orbit_length| *orbit_length > 1)
+                .collect(),
+        )
+    }
+
+    /// Returns a uniformly random element of the group.
+    pub(crate) fn random_element<R: Rng>(&self, mut rng: R) -> Permutation {
+        let mut element = Permutation::identity(self.levels.len());
+        // Every element factors uniquely as a product of one coset representative per level (from the last level to the first).
+        for level in self.levels.iter().rev() {
+            let orbit_length = level.coset_representatives.iter().flatten().count();
+            if orbit_length == 1 {
+                continue;
+            }
+            let (representative, _) = level
+                .coset_representatives
+                .iter()
+                .flatten()
+                .nth(rng.random_range(0..orbit_length))
+                .unwrap();
+            element = element.then(representative);
+        }
+        element
+    }
+
+    /// Whether the permutation is an element of the group.
+    pub(crate) fn contains(&self, permutation: Permutation) -> bool {
+        self.contains_from_level(0, permutation)
+    }
+
+    fn contains_from_level(&self, level_idx: usize, mut permutation: Permutation) -> bool {
+        for level in &self.levels[level_idx..] {
+            let image = permutation.image(level.base_point);
+            let Some((_, inverse_representative)) = &level.coset_representatives[image] else {
+                return false;
+            };
+            permutation = permutation.then(inverse_representative);
+        }
+        true
+    }
+
+    fn extend(&mut self, level_idx: usize, permutation: Permutation) {
+        if level_idx >= self.levels.len()
+            || self.contains_from_level(level_idx, permutation.clone())
+        {
+            return;
+        }
+        let level = &mut self.levels[level_idx];
+        level.generators.push(permutation.clone());
+        // New coset representatives found while closing the orbit are combined
+        // with every generator (including this one) by `close_orbit` itself, so
+        // we only need to handle the existing ones here.
+        let existing_representatives: Vec<Permutation> = level
+            .coset_representatives
+            .iter()
+            .flatten()
+            .map(|(representative, _)| representative.clone())
+            .collect();
+        for representative in existing_representatives {
+            self.close_orbit(level_idx, representative.then(&permutation));
+        }
+    }
+
+    fn close_orbit(&mut self, level_idx: usize, permutation: Permutation) {
+        let mut pending = vec![permutation];
+        while let Some(permutation) = pending.pop() {
+            let level = &mut self.levels[level_idx];
+            let image = permutation.image(level.base_point);
+            match &level.coset_representatives[image] {
+                Some((_, inverse_representative)) => {
+                    // This is a Schreier generator for the next level.
+                    let residue = permutation.then(inverse_representative);
+                    if !residue.is_identity() {
+                        self.extend(level_idx + 1, residue);
+                    }
+                }
+                None => {
+                    for generator in &level.generators {
+                        pending.push(permutation.then(generator));
+                    }
+                    let inverse = permutation.invert();
+                    level.coset_representatives[image] = Some((permutation, inverse));
+                }
+            }
+        }
+    }
+}
+
+/// Assigns a point to every (piece, orientation) pair of every orbit, so that
+/// transformations can be represented as permutations of these points.
+pub(crate) struct KPuzzlePointIndexer {
+    /// `None` for orbits that are not included.
+    orbit_offsets: Vec<Option<usize>>,
+    pub(crate) num_points: usize,
+}
+
+impl KPuzzlePointIndexer {
+    pub(crate) fn new(kpuzzle: &KPuzzle) -> Self {
+        Self::new_for_orbits(kpuzzle, &vec![true; kpuzzle.orbit_info_iter().count()])
+    }
+
+    /// Only assigns points to the orbits for which `included_orbits` is `true`
+    /// (indexed in the same order as `orbit_info_iter()`). The resulting
+    /// permutations represent the action of transformations on just those orbits.
+    pub(crate) fn new_for_orbits(kpuzzle: &KPuzzle, included_orbits: &[bool]) -> Self {
+        let mut orbit_offsets = vec![];
+        let mut num_points = 0;
+        for (orbit_info, is_included) in kpuzzle.orbit_info_iter().zip(included_orbits) {
+            if !is_included {
+                orbit_offsets.push(None);
+                continue;
+            }
+            orbit_offsets.push(Some(num_points));
+            num_points += orbit_info.num_pieces as usize * orbit_info.num_orientations as usize;
+        }
+        Self {
+            orbit_offsets,
+            num_points,
+        }
+    }
+
+    pub(crate) fn permutation(&self, transformation: &KTransformation) -> Permutation {
+        let mut images = vec![0; self.num_points];
+        for (orbit_info, orbit_offset) in transformation
+            .kpuzzle()
+            .orbit_info_iter()
+            .zip(&self.orbit_offsets)
+        {
+            let Some(orbit_offset) = orbit_offset else {
+                continue;
+            };
+            let num_orientations = orbit_info.num_orientations as usize;
+            for i in 0..orbit_info.num_pieces {
+                // The piece at position `source` moves to position `i`, and its orientation changes by `orientation_delta`.
+                let source = transformation.get_permutation_idx(orbit_info, i) as usize;
+                let orientation_delta =
+                    transformation.get_orientation_delta(orbit_info, i) as usize;
+                for orientation in 0..num_orientations {
+                    let from = orbit_offset + source * num_orientations + orientation;
+                    let to = orbit_offset
+                        + i as usize * num_orientations
+                        + (orientation + orientation_delta) % num_orientations;
+                    images[from] = to as u32;
+                }
+            }
+        }
+        Permutation::from_images(images)
+    }
+
+    /// The inverse of `permutation(…)`. The permutation must come from the
+    /// group generated by `permutation(…)` values for the same `kpuzzle`.
+    fn transformation(&self, kpuzzle: &KPuzzle, permutation: &Permutation) -> KTransformation {
+        let mut transformation = kpuzzle.identity_transformation();
+        for (orbit_info, orbit_offset) in kpuzzle.orbit_info_iter().zip(&self.orbit_offsets) {
+            let orbit_offset =
+                orbit_offset.expect("Cannot convert a partial permutation to a transformation.");
+            let num_orientations = orbit_info.num_orientations as usize;
+            for source in 0..orbit_info.num_pieces {
+                let to = permutation.image(orbit_offset + source as usize * num_orientations)
+                    - orbit_offset;
+                let i = (to / num_orientations) as u8;
+                transformation.set_permutation_idx(orbit_info, i, source);
+                transformation.set_orientation_delta(orbit_info, i, (to % num_orientations) as u8);
+            }
+        }
+        transformation
+    }
+}
+
+pub struct KPuzzleStabilizerChain {
+    kpuzzle: KPuzzle,
+    point_indexer: KPuzzlePointIndexer,
+    stabilizer_chain: StabilizerChain,
+}
+
+impl KPuzzleStabilizerChain {
+    pub fn try_new(kpuzzle: &KPuzzle, generator_moves: &[Move]) -> Result<Self, SearchError> {
+        let point_indexer = KPuzzlePointIndexer::new(kpuzzle);
+        let mut stabilizer_chain = StabilizerChain::new(point_indexer.num_points);
+        for generator_move in generator_moves {
+            let transformation = kpuzzle
+                .transformation_from_move(generator_move)
+                .map_err(|e| SearchError {
+                    description: e.to_string(),
+                })?;
+            stabilizer_chain.add_generator(point_indexer.permutation(&transformation));
+        }
+        Ok(Self {
+            kpuzzle: kpuzzle.clone(),
+            point_indexer,
+            stabilizer_chain,
+        })
+    }
+
+    pub fn order(&self) -> GroupOrder {
+        self.stabilizer_chain.order()
+    }
+
+    /// Returns a uniformly random transformation from the group generated by the generator moves.
+    pub fn random_transformation<R: Rng>(&self, rng: R) -> KTransformation {
+        self.point_indexer
+            .transformation(&self.kpuzzle, &self.stabilizer_chain.random_element(rng))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct OrbitWithIdenticalPieces {
+    pub orbit_name: KPuzzleOrbitName,
+    pub num_pieces: u8,
+    pub num_distinguishable_pieces: u8,
+    /// Whether the orientation of at least one piece is (partially) ignored using `orientationMod`.
+    pub has_ignored_orientations: bool,
+}
+
+/// Lists the orbits in which the pattern does not distinguish all the pieces
+/// from each other (or from their own reorientations). The group order counts
+/// these as separate, so it can overcount the number of reachable patterns.
+pub fn orbits_with_identical_pieces(pattern: &KPattern) -> Vec<OrbitWithIdenticalPieces> {
+    let mut orbits = vec![];
+    for orbit_info in pattern.kpuzzle().orbit_info_iter() {
+        let mut seen_pieces = vec![false; orbit_info.num_pieces as usize];
+        let mut num_distinguishable_pieces = 0;
+        let mut has_ignored_orientations = false;
+        for i in 0..orbit_info.num_pieces {
+            let piece = pattern.get_piece(orbit_info, i) as usize;
+            if !seen_pieces[piece] {
+                seen_pieces[piece] = true;
+                num_distinguishable_pieces += 1;
+            }
+            let orientation_mod = pattern
+                .get_orientation_with_mod(orbit_info, i)
+                .orientation_mod;
+            if orientation_mod != 0 && orientation_mod != orbit_info.num_orientations {
+                has_ignored_orientations = true;
+            }
+        }
+        if num_distinguishable_pieces != orbit_info.num_pieces || has_ignored_orientations {
+            orbits.push(OrbitWithIdenticalPieces {
+                orbit_name: orbit_info.name.clone(),
+                num_pieces: orbit_info.num_pieces,
+                num_distinguishable_pieces,
+                has_ignored_orientations,
+            });
+        }
+    }
+    orbits
+}
@@ -13,6 +13,8 @@ impl<TPuzzle: SemiGroupActionPuzzle> PruneTable<TPuzzle> for BlankPruneTable {
         &mut self,
         _search_depth: super::prune_table_trait::Depth,
         _approximate_num_entries: usize,
+        _num_threads: usize,
+        _should_stop: &(dyn Fn() -> bool + Sync),
     ) {
         // no-op
     }
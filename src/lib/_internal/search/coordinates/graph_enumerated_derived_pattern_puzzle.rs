@@ -17,7 +17,11 @@ use crate::{
             FlatMoveIndex, MoveTransformationInfo, SearchGenerators,
         },
         puzzle_traits::puzzle_traits::SemiGroupActionPuzzle,
-        search::{indexed_vec::IndexedVec, move_count::MoveCount, prune_table_trait::Depth},
+        search::{
+            indexed_vec::IndexedVec,
+            iterative_deepening::target_pattern_signature::HasTargetPatternSignature,
+            move_count::MoveCount, prune_table_trait::Depth,
+        },
     },
     whole_number_newtype_generic,
 };
@@ -305,3 +309,15 @@ where
         true
     }
 }
+
+impl<TSourcePuzzle: SemiGroupActionPuzzle, TPatternDeriver: PatternDeriver<TSourcePuzzle>>
+    HasTargetPatternSignature
+    for GraphEnumeratedDerivedPatternPuzzle<TSourcePuzzle, TPatternDeriver>
+where
+    TPatternDeriver::DerivedPattern: Hash,
+{
+    // Every index refers to a pattern in the same enumerated graph, so there is nothing to compare.
+    type Signature = ();
+
+    fn get_target_pattern_signature(_pattern: &Self::Pattern) -> Self::Signature {}
+}
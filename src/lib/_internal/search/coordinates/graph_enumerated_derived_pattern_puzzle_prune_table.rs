@@ -49,7 +49,13 @@ where
         self.puzzle.data.exact_prune_table[*pattern]
     }
 
-    fn extend_for_search_depth(&mut self, _search_depth: Depth, _approximate_num_entries: usize) {
+    fn extend_for_search_depth(
+        &mut self,
+        _search_depth: Depth,
+        _approximate_num_entries: usize,
+        _num_threads: usize,
+        _should_stop: &(dyn Fn() -> bool + Sync),
+    ) {
         // no-op
     }
 }
@@ -4,9 +4,11 @@ use cubing::kpuzzle::KPuzzle;
 
 use crate::_internal::puzzle_traits::puzzle_traits::SemiGroupActionPuzzle;
 
-pub trait PatternDeriver<TSourcePuzzle: SemiGroupActionPuzzle = KPuzzle>: Clone + Debug {
+pub trait PatternDeriver<TSourcePuzzle: SemiGroupActionPuzzle = KPuzzle>:
+    Clone + Debug + Send + Sync
+{
     // TODO: split out the `Pattern` type from `SemiGroupActionPuzzle` into a `PuzzleAssociatedPattern` trait? This would make a bunch of code annoying, but may prevent some gnarly type issues that can't be handled otherwise due to https://github.com/rust-lang/rust/issues/20041
-    type DerivedPattern: Eq + Clone + Debug;
+    type DerivedPattern: Eq + Clone + Debug + Send + Sync;
 
     // TODO: Should this return a `Result<…>` instead?
     fn derive_pattern(
@@ -1,7 +1,11 @@
 use cubing::{alg::Move, kpuzzle::InvalidAlgError};
 
 use crate::_internal::{
-    puzzle_traits::puzzle_traits::SemiGroupActionPuzzle, search::move_count::MoveCount,
+    puzzle_traits::puzzle_traits::SemiGroupActionPuzzle,
+    search::{
+        iterative_deepening::target_pattern_signature::HasTargetPatternSignature,
+        move_count::MoveCount,
+    },
 };
 
 use super::pattern_deriver::{DerivedPuzzle, PatternDeriver};
@@ -115,3 +119,17 @@ impl<
     for UnenumeratedDerivedPatternPuzzle<TSourcePuzzle, TDerivedPuzzle, TPatternDeriver>
 {
 }
+
+impl<
+        TSourcePuzzle: SemiGroupActionPuzzle,
+        TDerivedPuzzle: SemiGroupActionPuzzle + HasTargetPatternSignature,
+        TPatternDeriver: PatternDeriver<TSourcePuzzle, DerivedPattern = TDerivedPuzzle::Pattern>,
+    > HasTargetPatternSignature
+    for UnenumeratedDerivedPatternPuzzle<TSourcePuzzle, TDerivedPuzzle, TPatternDeriver>
+{
+    type Signature = TDerivedPuzzle::Signature;
+
+    fn get_target_pattern_signature(pattern: &Self::Pattern) -> Self::Signature {
+        TDerivedPuzzle::get_target_pattern_signature(pattern)
+    }
+}
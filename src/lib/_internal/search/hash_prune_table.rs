@@ -1,4 +1,7 @@
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
 
 use thousands::Separable;
 
@@ -10,10 +13,14 @@ use crate::_internal::puzzle_traits::puzzle_traits::{
 };
 use crate::whole_number_newtype;
 
-use super::iterative_deepening::iterative_deepening_search::ImmutableSearchData;
+use super::iterative_deepening::iterative_deepening_search::{
+    ImmutableSearchData, PARALLEL_SEARCH_MIN_SUBTREES_PER_THREAD,
+};
 use super::iterative_deepening::search_adaptations::StoredSearchAdaptations;
+use super::iterative_deepening::search_limits::SEARCH_LIMITS_CHECK_INTERVAL;
+use super::prune_table_persistence::PruneTablePersistence;
 use super::prune_table_trait::{Depth, PruneTable};
-use super::recursive_work_tracker::RecursiveWorkTracker;
+use super::recursive_work_tracker::{RecursiveWorkDepthStats, RecursiveWorkTracker};
 use super::search_logger::SearchLogger;
 
 whole_number_newtype!(DepthU8, u8);
@@ -44,6 +51,7 @@ struct HashPruneTableMutableData<TPuzzle: SemiGroupActionPuzzle + HashablePatter
     recursive_work_tracker: RecursiveWorkTracker,
     search_logger: Arc<SearchLogger>,
     population: usize,
+    persistence: Option<PruneTablePersistence>,
 }
 
 impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTableMutableData<TPuzzle> {
@@ -63,18 +71,58 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTableMutab
         }
     }
 
-    fn set_if_uninitialized(&mut self, pattern: &TPuzzle::Pattern, depth: DepthU8) {
-        let pattern_hash = self.hash_pattern(pattern);
-        if self.pattern_hash_to_depth[pattern_hash] == UNINITIALIZED_SENTINEL
-            || self.pattern_hash_to_depth[pattern_hash] == INVALID_PATTERN_SENTINEL
-        {
-            self.population += 1;
-            self.pattern_hash_to_depth[pattern_hash] = DepthU8(depth.0 + 1) // TODO: arithmetic on `Depth`
+    // Replaces the (freshly allocated) table with a stored one for the same size, if available.
+    fn read_from_persistence(&mut self) {
+        let Some(persistence) = &self.persistence else {
+            return;
         };
+        match persistence.read(self.prune_table_size) {
+            Ok(None) => {}
+            Ok(Some(stored_prune_table)) => {
+                if stored_prune_table.pruning_depth > MAX_PRUNE_TABLE_DEPTH {
+                    self.search_logger.write_warning(
+                        "[Prune table] Ignoring stored prune table with an invalid depth.",
+                    );
+                    return;
+                }
+                self.recursive_work_tracker.print_message(&format!(
+                    "Read stored prune table with {} entries (depth {:?}) from: {}",
+                    self.prune_table_size.separate_with_underscores(),
+                    *stored_prune_table.pruning_depth,
+                    persistence.file_path(self.prune_table_size).display()
+                ));
+                self.pattern_hash_to_depth = stored_prune_table.pattern_hash_to_depth;
+                self.current_pruning_depth = stored_prune_table.pruning_depth;
+            }
+            Err(e) => {
+                self.search_logger.write_warning(&format!(
+                    "[Prune table] Ignoring stored prune table at {}: {}",
+                    persistence.file_path(self.prune_table_size).display(),
+                    e
+                ));
+            }
+        }
     }
 
-    fn set_invalid_depth(&mut self, pattern: &TPuzzle::Pattern) {
-        self.set_if_uninitialized(pattern, INVALID_PATTERN_DEPTH)
+    fn write_to_persistence(&self, fill_duration: Duration) {
+        let Some(persistence) = &self.persistence else {
+            return;
+        };
+        if !persistence
+            .options
+            .write_condition
+            .should_write(fill_duration)
+        {
+            return;
+        }
+        match persistence.write(self.current_pruning_depth, &self.pattern_hash_to_depth) {
+            Ok(file_path) => self
+                .recursive_work_tracker
+                .print_message(&format!("Wrote prune table to: {}", file_path.display())),
+            Err(e) => self
+                .search_logger
+                .write_warning(&format!("[Prune table] Could not write prune table: {}", e)),
+        }
     }
 }
 
@@ -84,29 +132,223 @@ pub struct HashPruneTable<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle
     mutable: HashPruneTableMutableData<TPuzzle>,
 }
 
-impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTable<TPuzzle> {
+#[derive(Default)]
+struct HashPruneTableFillCounts {
+    num_recursive_calls: usize,
+    population: usize,
+    stopped: bool,
+}
+
+/// The patterns at which the recursion tree of a fill pass is split for a parallel fill.
+struct HashPruneTableSubtreeRoots<TPuzzle: SemiGroupActionPuzzle> {
+    remaining_depth: PruneTableEntryType,
+    subtree_roots: Vec<(TPuzzle::Pattern, CanonicalFSMState)>,
+}
+
+/// Fills the table entries for a single depth. The entries are written
+/// atomically, so that the recursion can be split across threads.
+struct HashPruneTableFill<'a, TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> {
+    immutable_data: &'a HashPruneTableImmutableData<TPuzzle>,
+    tpuzzle: &'a TPuzzle,
+    prune_table_index_mask: usize,
+    pattern_hash_to_depth: &'a [AtomicU8],
+    should_stop: &'a (dyn Fn() -> bool + Sync),
+}
+
+impl<'a, TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTableFill<'a, TPuzzle> {
+    fn new(
+        immutable_data: &'a HashPruneTableImmutableData<TPuzzle>,
+        mutable_data: &'a mut HashPruneTableMutableData<TPuzzle>,
+        should_stop: &'a (dyn Fn() -> bool + Sync),
+    ) -> Self {
+        let pattern_hash_to_depth: &mut [PruneTableEntryType] =
+            &mut mutable_data.pattern_hash_to_depth;
+        // SAFETY: `DepthU8` is `#[repr(transparent)]` over `u8`, which has the
+        // same in-memory representation as `AtomicU8`. We hold the only
+        // reference to the entries for the lifetime of the returned slice.
+        let pattern_hash_to_depth =
+            unsafe { &*(pattern_hash_to_depth as *mut [PruneTableEntryType] as *const [AtomicU8]) };
+        Self {
+            immutable_data,
+            tpuzzle: &mutable_data.tpuzzle,
+            prune_table_index_mask: mutable_data.prune_table_index_mask,
+            pattern_hash_to_depth,
+            should_stop,
+        }
+    }
+
+    fn hash_pattern(&self, pattern: &TPuzzle::Pattern) -> usize {
+        // TODO: use modulo when the size is not a power of 2.
+        self.tpuzzle.pattern_hash_u64(pattern) as usize & self.prune_table_index_mask
+    }
+
+    fn set_if_uninitialized(
+        &self,
+        counts: &mut HashPruneTableFillCounts,
+        pattern: &TPuzzle::Pattern,
+        depth: DepthU8,
+    ) {
+        let entry = &self.pattern_hash_to_depth[self.hash_pattern(pattern)];
+        let mut current_value = entry.load(Ordering::Relaxed);
+        while current_value == *UNINITIALIZED_SENTINEL || current_value == *INVALID_PATTERN_SENTINEL
+        {
+            match entry.compare_exchange_weak(
+                current_value,
+                depth.0 + 1, // TODO: arithmetic on `Depth`
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    counts.population += 1;
+                    return;
+                }
+                Err(value) => current_value = value,
+            }
+        }
+    }
+
+    fn set_invalid_depth(&self, counts: &mut HashPruneTableFillCounts, pattern: &TPuzzle::Pattern) {
+        self.set_if_uninitialized(counts, pattern, INVALID_PATTERN_DEPTH)
+    }
+
+    fn fill_depth(
+        &self,
+        depth: PruneTableEntryType,
+        num_threads: usize,
+    ) -> HashPruneTableFillCounts {
+        let target_patterns = &self.immutable_data.immutable_search_data.target_patterns;
+        if num_threads <= 1 || depth == DepthU8(0) {
+            let mut counts = HashPruneTableFillCounts::default();
+            for target_pattern in target_patterns {
+                self.recurse(
+                    &mut counts,
+                    None,
+                    target_pattern,
+                    CANONICAL_FSM_START_STATE,
+                    depth,
+                );
+            }
+            return counts;
+        }
+
+        // Each split attempt re-visits the nodes above the split, so we only count the last one.
+        let mut counts = HashPruneTableFillCounts::default();
+        let mut collector = HashPruneTableSubtreeRoots {
+            remaining_depth: depth,
+            subtree_roots: vec![],
+        };
+        for split_depth in 1..=*depth {
+            counts = HashPruneTableFillCounts::default();
+            collector = HashPruneTableSubtreeRoots {
+                remaining_depth: depth - DepthU8(split_depth),
+                subtree_roots: vec![],
+            };
+            for target_pattern in target_patterns {
+                self.recurse(
+                    &mut counts,
+                    Some(&mut collector),
+                    target_pattern,
+                    CANONICAL_FSM_START_STATE,
+                    depth,
+                );
+            }
+            if counts.stopped {
+                return counts;
+            }
+            if collector.subtree_roots.len()
+                >= num_threads * PARALLEL_SEARCH_MIN_SUBTREES_PER_THREAD
+            {
+                break;
+            }
+        }
+
+        // Unlike for search, the order in which entries are written doesn't
+        // matter: the table ends up with the same values either way.
+        let next_subtree_root_index = AtomicUsize::new(0);
+        let worker_counts: Vec<HashPruneTableFillCounts> = thread::scope(|scope| {
+            let worker_handles: Vec<_> = (0..num_threads.min(collector.subtree_roots.len()))
+                .map(|_| scope.spawn(|| self.fill_subtrees(&collector, &next_subtree_root_index)))
+                .collect();
+            worker_handles
+                .into_iter()
+                .map(|worker_handle| worker_handle.join().expect("Prune table worker panicked."))
+                .collect()
+        });
+        for worker_counts in worker_counts {
+            counts.num_recursive_calls += worker_counts.num_recursive_calls;
+            counts.population += worker_counts.population;
+            counts.stopped |= worker_counts.stopped;
+        }
+        counts
+    }
+
+    fn fill_subtrees(
+        &self,
+        collector: &HashPruneTableSubtreeRoots<TPuzzle>,
+        next_subtree_root_index: &AtomicUsize,
+    ) -> HashPruneTableFillCounts {
+        let mut counts = HashPruneTableFillCounts::default();
+        while let Some((pattern, state)) = collector
+            .subtree_roots
+            .get(next_subtree_root_index.fetch_add(1, Ordering::Relaxed))
+        {
+            if counts.stopped {
+                break;
+            }
+            self.recurse(
+                &mut counts,
+                None,
+                pattern,
+                *state,
+                collector.remaining_depth,
+            );
+        }
+        counts
+    }
+
     // TODO: dedup with IterativeDeepeningSearch?
-    // TODO: Store a reference to `immutable_search_data` so that you can't accidentally pass in the wrong `immutable_search_data`?
     fn recurse(
-        immutable_data: &HashPruneTableImmutableData<TPuzzle>,
-        mutable_data: &mut HashPruneTableMutableData<TPuzzle>,
+        &self,
+        counts: &mut HashPruneTableFillCounts,
+        mut subtree_roots: Option<&mut HashPruneTableSubtreeRoots<TPuzzle>>,
         // TODO: Use a `PatternStack` to avoid allocations.
         current_pattern: &TPuzzle::Pattern,
         current_state: CanonicalFSMState,
         remaining_depth: PruneTableEntryType,
     ) {
-        mutable_data.recursive_work_tracker.record_recursive_call();
+        if let Some(subtree_roots) = &mut subtree_roots {
+            if remaining_depth == subtree_roots.remaining_depth {
+                subtree_roots
+                    .subtree_roots
+                    .push((current_pattern.clone(), current_state));
+                return;
+            }
+        }
+        if counts.stopped {
+            return;
+        }
+        counts.num_recursive_calls += 1;
+        if counts
+            .num_recursive_calls
+            .is_multiple_of(SEARCH_LIMITS_CHECK_INTERVAL)
+            && (self.should_stop)()
+        {
+            counts.stopped = true;
+            return;
+        }
         if remaining_depth == DepthU8(0) {
-            mutable_data.set_if_uninitialized(current_pattern, remaining_depth);
+            self.set_if_uninitialized(counts, current_pattern, remaining_depth);
             return;
         }
-        for (move_class_index, move_transformation_multiples) in immutable_data
+        for (move_class_index, move_transformation_multiples) in self
+            .immutable_data
             .immutable_search_data
             .search_generators
             .by_move_class
             .iter()
         {
-            let next_state = match immutable_data
+            let next_state = match self
+                .immutable_data
                 .immutable_search_data
                 .canonical_fsm
                 .next_state(current_state, move_class_index)
@@ -118,7 +360,7 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTable<TPuz
             };
 
             for move_transformation_info in move_transformation_multiples {
-                let Some(next_pattern) = mutable_data.tpuzzle.pattern_apply_transformation(
+                let Some(next_pattern) = self.tpuzzle.pattern_apply_transformation(
                     current_pattern,
                     &move_transformation_info.transformation,
                 ) else {
@@ -129,26 +371,34 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTable<TPuz
                     continue;
                 }
 
-                if immutable_data
+                if self
+                    .immutable_data
                     .stored_search_adaptations
                     .filter_pattern(&next_pattern)
                     .is_reject()
                 {
-                    mutable_data.set_invalid_depth(&next_pattern);
+                    self.set_invalid_depth(counts, &next_pattern);
                     continue;
                 }
-                Self::recurse(
-                    immutable_data,
-                    mutable_data,
+                self.recurse(
+                    counts,
+                    subtree_roots.as_deref_mut(),
                     &next_pattern,
                     next_state,
                     remaining_depth - DepthU8(1),
-                )
+                );
+                if counts.stopped {
+                    return;
+                }
             }
         }
     }
 }
 
+pub(crate) fn format_mebibytes(num_bytes: usize) -> String {
+    format!("{:.1} MiB", (num_bytes as f64) / ((1 << 20) as f64))
+}
+
 fn previous_power_of_two(n: usize) -> usize {
     if n.is_power_of_two() {
         n
@@ -164,18 +414,53 @@ pub struct HashPruneTableSizeBounds {
     pub(crate) max_size: Option<usize>,
 }
 
+impl HashPruneTableSizeBounds {
+    /// Uses the largest power-of-two table size that fits in the given number
+    /// of bytes, allocated up front (instead of growing as the search deepens).
+    pub fn from_memory_budget(num_bytes: usize) -> Self {
+        let size = previous_power_of_two(usize::max(num_bytes / size_of::<DepthU8>(), 1));
+        Self {
+            min_size: Some(size),
+            max_size: Some(size),
+        }
+    }
+
+    pub fn from_memory_budget_mebibytes(mebibytes: usize) -> Self {
+        Self::from_memory_budget(mebibytes.saturating_mul(1 << 20))
+    }
+}
+
 impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTable<TPuzzle> {
     pub fn new(
         immutable_search_data: Arc<ImmutableSearchData<TPuzzle>>,
         stored_search_adaptations: StoredSearchAdaptations<TPuzzle>,
         size_bounds: HashPruneTableSizeBounds,
     ) -> Self {
+        Self::new_with_persistence(
+            immutable_search_data,
+            stored_search_adaptations,
+            size_bounds,
+            None,
+        )
+    }
+
+    /// If `persistence` is specified, stored tables are read instead of being filled whenever possible, and filled tables are written according to the persistence options.
+    pub(crate) fn new_with_persistence(
+        immutable_search_data: Arc<ImmutableSearchData<TPuzzle>>,
+        stored_search_adaptations: StoredSearchAdaptations<TPuzzle>,
+        size_bounds: HashPruneTableSizeBounds,
+        persistence: Option<PruneTablePersistence>,
+    ) -> Self {
+        // Note: we could return a max size, but there are some issues with calculating this statically due to the variable width of `usize`.
+        let max_size = size_bounds.max_size.map(previous_power_of_two);
         let min_size = match size_bounds.min_size {
             Some(min_size) => min_size.next_power_of_two(),
             None => DEFAULT_MIN_PRUNE_TABLE_SIZE,
         };
-        // Note: we could return a max size, but there are some issues with calculating this statically due to the variable width of `usize`.
-        let max_size = size_bounds.max_size.map(previous_power_of_two);
+        let min_size = match max_size {
+            Some(max_size) => usize::min(min_size, max_size),
+            None => min_size,
+        };
         let mut prune_table = Self {
             mutable: HashPruneTableMutableData {
                 tpuzzle: (immutable_search_data.tpuzzle).clone(),
@@ -191,15 +476,24 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> HashPruneTable<TPuz
                 ),
                 search_logger: immutable_search_data.search_logger.clone(),
                 population: 0,
+                persistence,
             },
             immutable: HashPruneTableImmutableData {
                 immutable_search_data,
                 stored_search_adaptations,
             },
         };
-        prune_table.extend_for_search_depth(Depth(0), 1);
+        prune_table.mutable.read_from_persistence();
+        prune_table.extend_for_search_depth(Depth(0), 1, 1, &|| false);
         prune_table
     }
+
+    /// Keeps the current table size from now on. Growing the table discards
+    /// its contents, so this preserves a table that has been filled up front.
+    pub fn pin_size(&mut self) {
+        self.mutable.min_size = self.mutable.prune_table_size;
+        self.mutable.max_size = Some(self.mutable.prune_table_size);
+    }
 }
 
 impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> PruneTable<TPuzzle>
@@ -212,7 +506,13 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> PruneTable<TPuzzle>
 
     // TODO: dedup with IterativeDeepeningSearch?
     // TODO: Store a reference to `immutable_search_data` so that you can't accidentally pass in the wrong `immutable_search_data`?
-    fn extend_for_search_depth(&mut self, search_depth: Depth, approximate_num_entries: usize) {
+    fn extend_for_search_depth(
+        &mut self,
+        search_depth: Depth,
+        approximate_num_entries: usize,
+        num_threads: usize,
+        should_stop: &(dyn Fn() -> bool + Sync),
+    ) {
         let mut new_pruning_depth = DepthU8(
             std::convert::TryInto::<u8>::try_into(search_depth.0 / 2)
                 .expect("Prune table depth exceeded available size"),
@@ -252,26 +552,41 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> PruneTable<TPuzzle>
                 self.mutable.prune_table_size = new_prune_table_size;
                 self.mutable.prune_table_index_mask = new_prune_table_size - 1;
                 self.mutable.current_pruning_depth = DepthU8(0);
+                self.mutable.search_logger.write_info(&format!(
+                    "[Prune table] Using {} of memory.",
+                    format_mebibytes(self.approximate_num_bytes())
+                ));
+                self.mutable.read_from_persistence();
+                if new_pruning_depth <= self.mutable.current_pruning_depth {
+                    return;
+                }
             }
         }
 
         self.mutable.population = 0;
 
+        let fill_start_time = instant::Instant::now();
         for depth_as_u8 in (*self.mutable.current_pruning_depth + 1)..(*new_pruning_depth + 1) {
+            if should_stop() {
+                return;
+            }
             let depth = DepthU8(depth_as_u8);
             self.mutable
                 .recursive_work_tracker
                 .start_depth(Depth(*depth as usize), None);
-            for target_pattern in &self.immutable.immutable_search_data.target_patterns {
-                Self::recurse(
-                    &self.immutable,
-                    &mut self.mutable,
-                    target_pattern,
-                    CANONICAL_FSM_START_STATE,
-                    depth,
-                );
-            }
+            let counts = HashPruneTableFill::new(&self.immutable, &mut self.mutable, should_stop)
+                .fill_depth(depth, num_threads);
+            self.mutable
+                .recursive_work_tracker
+                .record_recursive_calls(counts.num_recursive_calls);
+            self.mutable.population += counts.population;
             self.mutable.recursive_work_tracker.finish_latest_depth();
+            if counts.stopped {
+                // The entries written so far are still valid lower bounds, so
+                // the table remains usable at the last fully filled depth.
+                return;
+            }
+            self.mutable.current_pruning_depth = depth;
 
             self.mutable.search_logger.write_info(&format!(
                 "[Prune table] Population is {} entries (≈{}% of {} slots, ≈{}% of latest depth recursive calls).",
@@ -283,6 +598,133 @@ impl<TPuzzle: SemiGroupActionPuzzle + HashablePatternPuzzle> PruneTable<TPuzzle>
                     as usize,
             ));
         }
-        self.mutable.current_pruning_depth = new_pruning_depth;
+        self.mutable
+            .write_to_persistence(instant::Instant::now() - fill_start_time);
+    }
+
+    fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        self.mutable.recursive_work_tracker.finished_depth_stats()
+    }
+
+    fn approximate_num_bytes(&self) -> usize {
+        self.mutable.pattern_hash_to_depth.len() * size_of::<DepthU8>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use cubing::{kpuzzle::KPuzzle, puzzles::cube2x2x2_kpuzzle};
+
+    use crate::_internal::search::{
+        iterative_deepening::iterative_deepening_search::{
+            ImmutableSearchData, ImmutableSearchDataConstructionOptions,
+        },
+        prune_table_trait::{Depth, PruneTable},
+    };
+
+    use super::{HashPruneTable, HashPruneTableSizeBounds};
+
+    fn cube2x2x2_immutable_search_data() -> Arc<ImmutableSearchData<KPuzzle>> {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        Arc::new(
+            ImmutableSearchData::<KPuzzle>::try_from_common_options_with_auto_search_generators(
+                kpuzzle.clone(),
+                kpuzzle.definition().moves.keys().cloned().collect(),
+                vec![kpuzzle.default_pattern()],
+                ImmutableSearchDataConstructionOptions::default(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn parallel_fill_matches_single_threaded_fill() {
+        let immutable_search_data = cube2x2x2_immutable_search_data();
+        let filled_table = |num_threads: usize| {
+            let mut prune_table = HashPruneTable::new(
+                immutable_search_data.clone(),
+                Default::default(),
+                Default::default(),
+            );
+            prune_table.extend_for_search_depth(Depth(10), 1, num_threads, &|| false);
+            prune_table.mutable.pattern_hash_to_depth
+        };
+        assert_eq!(filled_table(4), filled_table(1));
+    }
+
+    #[test]
+    fn stopped_fill_can_be_resumed() {
+        let immutable_search_data = cube2x2x2_immutable_search_data();
+        let new_prune_table = || {
+            HashPruneTable::new(
+                immutable_search_data.clone(),
+                Default::default(),
+                Default::default(),
+            )
+        };
+
+        let mut uninterrupted_prune_table = new_prune_table();
+        uninterrupted_prune_table.extend_for_search_depth(Depth(12), 1, 1, &|| false);
+
+        for num_threads in [1, 4] {
+            // Stop partway through a depth.
+            let num_checks = AtomicUsize::new(0);
+            let mut prune_table = new_prune_table();
+            prune_table.extend_for_search_depth(Depth(12), 1, num_threads, &|| {
+                num_checks.fetch_add(1, Ordering::Relaxed) >= 10
+            });
+            assert!(*prune_table.mutable.current_pruning_depth < 6);
+
+            prune_table.extend_for_search_depth(Depth(12), 1, num_threads, &|| false);
+            assert_eq!(*prune_table.mutable.current_pruning_depth, 6);
+            assert_eq!(
+                prune_table.mutable.pattern_hash_to_depth,
+                uninterrupted_prune_table.mutable.pattern_hash_to_depth
+            );
+        }
+    }
+
+    #[test]
+    fn memory_budget_size_bounds() {
+        let immutable_search_data = cube2x2x2_immutable_search_data();
+        // 3 MiB rounds down to 2 MiB (the table has one byte per entry).
+        let mut prune_table = HashPruneTable::new(
+            immutable_search_data.clone(),
+            Default::default(),
+            HashPruneTableSizeBounds::from_memory_budget_mebibytes(3),
+        );
+        assert_eq!(prune_table.approximate_num_bytes(), 2 << 20);
+        prune_table.extend_for_search_depth(Depth(8), 1 << 30, 1, &|| false);
+        assert_eq!(prune_table.approximate_num_bytes(), 2 << 20);
+
+        // Budgets smaller than the default minimum size are respected.
+        let prune_table = HashPruneTable::new(
+            immutable_search_data,
+            Default::default(),
+            HashPruneTableSizeBounds::from_memory_budget(1000),
+        );
+        assert_eq!(prune_table.approximate_num_bytes(), 512);
+    }
+
+    #[test]
+    fn pinned_size_keeps_filled_depth() {
+        let immutable_search_data = cube2x2x2_immutable_search_data();
+        let mut prune_table = HashPruneTable::new(
+            immutable_search_data,
+            Default::default(),
+            Default::default(),
+        );
+        prune_table.extend_for_search_depth(Depth(8), 1, 1, &|| false);
+        prune_table.pin_size();
+        let num_bytes = prune_table.approximate_num_bytes();
+        // Without pinning, this would grow the table and start filling it from scratch.
+        prune_table.extend_for_search_depth(Depth(4), 1 << 30, 1, &|| false);
+        assert_eq!(prune_table.approximate_num_bytes(), num_bytes);
+        assert_eq!(*prune_table.mutable.current_pruning_depth, 4);
     }
 }
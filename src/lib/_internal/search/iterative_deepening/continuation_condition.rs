@@ -9,6 +9,7 @@ use crate::_internal::search::{
 
 // TODO: also handle "before" cases.
 #[derive(Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
 pub enum ContinuationCondition {
     #[default]
     None,
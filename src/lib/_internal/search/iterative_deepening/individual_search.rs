@@ -1,13 +1,20 @@
+use std::{cmp::max, fmt::Display, sync::Arc, time::Duration};
+
 use cubing::alg::Move;
-use serde::{Deserialize, Serialize};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::_internal::puzzle_traits::puzzle_traits::SemiGroupActionPuzzle;
 
 use super::{
-    super::{prune_table_trait::Depth, recursive_work_tracker::RecursiveWorkTracker},
+    super::{
+        prune_table_trait::Depth, recursive_work_tracker::RecursiveWorkTracker,
+        search_logger::SearchLogger,
+    },
     continuation_condition::ContinuationCondition,
-    iterative_deepening_search::IterativeDeepeningSearch,
+    iterative_deepening_search::{IterativeDeepeningSearch, SubtreeRootCollector},
     search_adaptations::IndividualSearchAdaptations,
+    search_checkpoint::{CheckpointSchedule, SearchCheckpoint, SearchCheckpointer},
+    search_limits::{SearchCancellationToken, SearchLimits, SEARCH_LIMITS_CHECK_INTERVAL},
 };
 
 // TODO: right now we return 0 solutions if we blow past this, should we return an explicit error,
@@ -33,11 +40,14 @@ pub struct IndividualSearchOptions {
     pub max_depth_exclusive: Option<Depth>, // exclusive
     pub canonical_fsm_pre_moves: Option<Vec<Move>>,
     pub canonical_fsm_post_moves: Option<Vec<Move>>,
+    /// The number of threads to search with. Defaults to 1.
+    ///
+    /// Solutions are returned in the same order regardless of the number of
+    /// threads.
+    pub num_threads: Option<usize>,
     // Recursive calls use modified continuation conditions derived from this.
     // This is called the "root" continuation condition to distinguish it from
     // the recursive ones.
-    //
-    // TODO: support (de)serialization.
     /// Note that:
     /// - If the depth of (i.e. number of moves in) the condition exceeds `min_depth_inclusive`:
     ///     - The `root_continuation_condition` will be used to set the initial search depth.
@@ -48,8 +58,50 @@ pub struct IndividualSearchOptions {
     ///
     /// This allows resuming a search from a previous solution by just passing `ContinuationCondition::After(/* previous solution */)` without also passing a min depth.
     /// However, if the continuation condition corresponds to an intermediate search call rather than the base case the min depth must be specified.
-    #[serde(skip_serializing, skip_deserializing)]
+    ///
+    /// See [`SearchCheckpoint::resume`] for a convenient way to set this together with the min depth.
+    #[serde(default)]
     pub root_continuation_condition: ContinuationCondition,
+
+    /// If specified, the search stops once this much time has passed since
+    /// the search was created (e.g. using [`IterativeDeepeningSearch::search`]). Note that the limits in these options are only
+    /// checked periodically during the search (including while extending the
+    /// prune table).
+    #[serde(
+        rename = "timeoutSeconds",
+        default,
+        serialize_with = "serialize_optional_duration_seconds",
+        deserialize_with = "deserialize_optional_duration_seconds"
+    )]
+    pub timeout: Option<Duration>,
+    /// If specified, the search stops once it has made this many recursive
+    /// calls (i.e. visited this many nodes). This does not include the work to
+    /// populate the prune table.
+    #[serde(rename = "maxNodes")]
+    pub max_num_recursive_calls: Option<usize>,
+    /// If specified, the search stops once this is cancelled.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub cancellation_token: Option<SearchCancellationToken>,
+    /// If specified, checkpoints are passed to this periodically while searching.
+    #[serde(skip_serializing, skip_deserializing)]
+    pub checkpointer: Option<SearchCheckpointer>,
+}
+
+fn serialize_optional_duration_seconds<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    duration
+        .map(|duration| duration.as_secs_f64())
+        .serialize(serializer)
+}
+
+fn deserialize_optional_duration_seconds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    Option::<f64>::deserialize(deserializer)?
+        .map(|seconds| Duration::try_from_secs_f64(seconds).map_err(D::Error::custom))
+        .transpose()
 }
 
 impl IndividualSearchOptions {
@@ -63,6 +115,54 @@ impl IndividualSearchOptions {
         self.max_depth_exclusive
             .unwrap_or(MAX_SUPPORTED_SEARCH_DEPTH)
     }
+    pub fn get_num_threads(&self) -> usize {
+        self.num_threads.unwrap_or(1).max(1)
+    }
+}
+
+/// Why a search has stopped returning solutions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchTerminationReason {
+    /// All depths up to the maximum supported search depth were searched.
+    Exhausted,
+    /// All depths below the `max_depth_exclusive` option were searched.
+    MaxDepthReached,
+    /// The `min_num_solutions` option was reached.
+    MinNumSolutionsReached,
+    /// The `cancellation_token` option was cancelled.
+    Cancelled,
+    /// The `timeout` option was reached.
+    TimeLimitReached,
+    /// The `max_num_recursive_calls` option was reached.
+    NodeLimitReached,
+}
+
+impl Display for SearchTerminationReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            SearchTerminationReason::Exhausted => "exhausted",
+            SearchTerminationReason::MaxDepthReached => "max depth reached",
+            SearchTerminationReason::MinNumSolutionsReached => "min number of solutions reached",
+            SearchTerminationReason::Cancelled => "cancelled",
+            SearchTerminationReason::TimeLimitReached => "time limit reached",
+            SearchTerminationReason::NodeLimitReached => "node limit reached",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl SearchTerminationReason {
+    /// Whether the search was stopped early by a cancellation or limit (in
+    /// which case there may be more solutions).
+    pub fn is_interrupted(&self) -> bool {
+        matches!(
+            self,
+            SearchTerminationReason::Cancelled
+                | SearchTerminationReason::TimeLimitReached
+                | SearchTerminationReason::NodeLimitReached
+        )
+    }
 }
 
 pub(crate) struct IndividualSearchData<TPuzzle: SemiGroupActionPuzzle> {
@@ -70,7 +170,19 @@ pub(crate) struct IndividualSearchData<TPuzzle: SemiGroupActionPuzzle> {
     pub(crate) individual_search_options: IndividualSearchOptions,
     pub(crate) recursive_work_tracker: RecursiveWorkTracker,
     pub(crate) num_solutions_sofar: usize,
+    /// `None` until the search has stopped.
+    pub(crate) termination_reason: Option<SearchTerminationReason>,
+    pub(crate) search_limits: Arc<SearchLimits>,
+    num_recursive_calls_since_search_limits_check: usize,
+    /// The latest point from which the search can be resumed exactly. This is
+    /// only updated when needed (i.e. when a checkpoint is due or the search
+    /// stops), and never by parallel search workers.
+    pub(crate) checkpoint: SearchCheckpoint,
+    checkpoint_schedule: Option<Arc<CheckpointSchedule>>,
+    is_parallel_search_worker: bool,
     pub(crate) individual_search_adaptations: IndividualSearchAdaptations<TPuzzle>,
+    /// Only used while splitting the search tree for a parallel search.
+    pub(crate) subtree_root_collector: Option<SubtreeRootCollector<TPuzzle>>,
 }
 
 impl<TPuzzle: SemiGroupActionPuzzle> IndividualSearchData<TPuzzle> {
@@ -103,6 +215,22 @@ impl<TPuzzle: SemiGroupActionPuzzle> IndividualSearchData<TPuzzle> {
         }
 
         let search_pattern = search_pattern.clone();
+        let search_limits = Arc::new(SearchLimits::new(&individual_search_options));
+        let checkpoint = SearchCheckpoint {
+            depth: max(
+                individual_search_options.get_min_depth(),
+                individual_search_options
+                    .root_continuation_condition
+                    .min_depth(),
+            ),
+            continuation_condition: individual_search_options
+                .root_continuation_condition
+                .clone(),
+        };
+        let checkpoint_schedule = individual_search_options
+            .checkpointer
+            .clone()
+            .map(|checkpointer| Arc::new(CheckpointSchedule::new(checkpointer)));
 
         Self {
             search_pattern,
@@ -112,7 +240,35 @@ impl<TPuzzle: SemiGroupActionPuzzle> IndividualSearchData<TPuzzle> {
                 search.immutable_search_data.search_logger.clone(),
             ),
             num_solutions_sofar: 0,
+            termination_reason: None,
+            search_limits,
+            num_recursive_calls_since_search_limits_check: 0,
+            checkpoint,
+            checkpoint_schedule,
+            is_parallel_search_worker: false,
             individual_search_adaptations,
+            subtree_root_collector: None,
+        }
+    }
+
+    /// Constructs the data for a worker thread of a parallel search.
+    pub(crate) fn new_parallel_search_worker(&self, search_logger: Arc<SearchLogger>) -> Self {
+        Self {
+            search_pattern: self.search_pattern.clone(),
+            individual_search_options: self.individual_search_options.clone(),
+            recursive_work_tracker: RecursiveWorkTracker::new(
+                "Search worker".to_owned(),
+                search_logger,
+            ),
+            num_solutions_sofar: 0,
+            termination_reason: None,
+            search_limits: self.search_limits.clone(),
+            num_recursive_calls_since_search_limits_check: 0,
+            checkpoint: self.checkpoint.clone(),
+            checkpoint_schedule: self.checkpoint_schedule.clone(),
+            is_parallel_search_worker: true,
+            individual_search_adaptations: self.individual_search_adaptations.clone(),
+            subtree_root_collector: None,
         }
     }
 
@@ -122,4 +278,56 @@ impl<TPuzzle: SemiGroupActionPuzzle> IndividualSearchData<TPuzzle> {
         };
         self.num_solutions_sofar >= min_num_solutions
     }
+
+    /// Call this once per recursive call, with a function that returns the
+    /// checkpoint for the current node. Returns whether a search limit has been
+    /// reached. The limits (and whether a checkpoint is due) are only checked
+    /// periodically.
+    pub(crate) fn should_stop(
+        &mut self,
+        current_checkpoint: impl FnOnce() -> SearchCheckpoint,
+    ) -> bool {
+        self.num_recursive_calls_since_search_limits_check += 1;
+        if self.num_recursive_calls_since_search_limits_check < SEARCH_LIMITS_CHECK_INTERVAL {
+            return false;
+        }
+        let should_stop = self.search_limits.check(std::mem::take(
+            &mut self.num_recursive_calls_since_search_limits_check,
+        ));
+        // Parallel search workers (and the pass that splits the search tree for
+        // them) don't visit nodes in search order, so their current node is not
+        // a valid checkpoint.
+        if self.is_parallel_search_worker || self.subtree_root_collector.is_some() {
+            return should_stop;
+        }
+        let checkpoint_schedule = self
+            .checkpoint_schedule
+            .clone()
+            .filter(|checkpoint_schedule| checkpoint_schedule.is_checkpoint_due());
+        if should_stop || checkpoint_schedule.is_some() {
+            self.checkpoint = current_checkpoint();
+        }
+        if let Some(checkpoint_schedule) = checkpoint_schedule {
+            checkpoint_schedule.write_checkpoint(&self.checkpoint);
+        }
+        should_stop
+    }
+
+    /// Writes a checkpoint if one is due. This is meant to be called between
+    /// the subtrees of a parallel search, where the caller knows the position
+    /// of the search as a whole.
+    pub(crate) fn write_checkpoint_if_due(
+        &self,
+        current_checkpoint: impl FnOnce() -> Option<SearchCheckpoint>,
+    ) {
+        let Some(checkpoint_schedule) = &self.checkpoint_schedule else {
+            return;
+        };
+        if !checkpoint_schedule.is_checkpoint_due() {
+            return;
+        }
+        if let Some(checkpoint) = current_checkpoint() {
+            checkpoint_schedule.write_checkpoint(&checkpoint);
+        }
+    }
 }
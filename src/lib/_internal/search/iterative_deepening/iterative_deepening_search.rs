@@ -1,4 +1,11 @@
-use std::{cmp::max, sync::Arc};
+use std::{
+    cmp::max,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
 
 use crate::_internal::{
     canonical_fsm::{
@@ -15,6 +22,7 @@ use crate::_internal::{
         hash_prune_table::{HashPruneTable, HashPruneTableSizeBounds},
         pattern_stack::PatternStack,
         prune_table_trait::PruneTable,
+        recursive_work_tracker::RecursiveWorkDepthStats,
     },
 };
 use cubing::{
@@ -25,9 +33,13 @@ use cubing::{
 use super::{
     super::{prune_table_trait::Depth, search_logger::SearchLogger},
     continuation_condition::ContinuationCondition,
-    individual_search::{IndividualSearchData, IndividualSearchOptions},
+    individual_search::{IndividualSearchData, IndividualSearchOptions, SearchTerminationReason},
     search_adaptations::{IndividualSearchAdaptations, StoredSearchAdaptations},
+    search_checkpoint::SearchCheckpoint,
     solution_moves::{alg_to_moves, SolutionMoves},
+    target_pattern_signature::{
+        check_target_pattern_consistency_single_iter, HasTargetPatternSignature,
+    },
 };
 
 // TODO: use https://doc.rust-lang.org/std/ops/enum.ControlFlow.html as a wrapper instead?
@@ -36,6 +48,116 @@ enum SearchRecursionResult {
     ContinueSearchingDefault,
     ContinueSearchingExcludingCurrentMoveClass,
     FoundSolution(Alg),
+    /// A search limit was reached.
+    Stopped,
+}
+
+// Splitting a tree into more subtrees than threads helps to balance out the
+// work, since subtrees can have wildly different sizes.
+pub(crate) const PARALLEL_SEARCH_MIN_SUBTREES_PER_THREAD: usize = 16;
+
+/// A node at which the search tree is split for a parallel search.
+pub(crate) struct SubtreeRoot<TPuzzle: SemiGroupActionPuzzle> {
+    moves: Vec<Move>,
+    pattern: TPuzzle::Pattern,
+    canonical_fsm_state: CanonicalFSMState,
+    remaining_depth: Depth,
+    continuation_condition: ContinuationCondition,
+}
+
+pub(crate) struct SubtreeRootCollector<TPuzzle: SemiGroupActionPuzzle> {
+    remaining_depth: Depth,
+    subtree_roots: Vec<SubtreeRoot<TPuzzle>>,
+}
+
+struct ParallelSearchWorkerResult {
+    num_recursive_calls: usize,
+    /// Pairs of subtree root index and the first solution in that subtree.
+    solutions: Vec<(usize, Alg)>,
+}
+
+struct ParallelSearch<'a, TPuzzle: SemiGroupActionPuzzle> {
+    search: &'a IterativeDeepeningSearch<TPuzzle>,
+    individual_search_data: &'a IndividualSearchData<TPuzzle>,
+    subtree_roots: &'a [SubtreeRoot<TPuzzle>],
+    next_subtree_root_index: AtomicUsize,
+    first_solution_subtree_root_index: AtomicUsize,
+    /// Whether each subtree has been searched completely (without finding a solution).
+    completed_subtree_roots: Vec<AtomicBool>,
+}
+
+impl<TPuzzle: SemiGroupActionPuzzle> ParallelSearch<'_, TPuzzle> {
+    fn run_worker(&self) -> ParallelSearchWorkerResult {
+        let mut individual_search_data = self
+            .individual_search_data
+            .new_parallel_search_worker(self.search.immutable_search_data.search_logger.clone());
+        let mut solutions = vec![];
+        loop {
+            let subtree_root_index = self.next_subtree_root_index.fetch_add(1, Ordering::Relaxed);
+            // Subtrees after a solution can't affect the result.
+            if subtree_root_index >= self.subtree_roots.len()
+                || subtree_root_index
+                    > self
+                        .first_solution_subtree_root_index
+                        .load(Ordering::Relaxed)
+            {
+                break;
+            }
+            let subtree_root = &self.subtree_roots[subtree_root_index];
+            let mut pattern_stack = PatternStack::new(
+                self.search.immutable_search_data.tpuzzle.clone(),
+                subtree_root.pattern.clone(),
+            );
+            let recursion_result =
+                SolutionMoves::default().with_pushed_moves(&subtree_root.moves, |solution_moves| {
+                    self.search.recurse_children(
+                        &mut individual_search_data,
+                        &mut pattern_stack,
+                        subtree_root.canonical_fsm_state,
+                        subtree_root.remaining_depth,
+                        solution_moves.clone(),
+                        subtree_root.continuation_condition.clone(),
+                    )
+                });
+            match recursion_result {
+                SearchRecursionResult::FoundSolution(alg) => {
+                    self.first_solution_subtree_root_index
+                        .fetch_min(subtree_root_index, Ordering::Relaxed);
+                    solutions.push((subtree_root_index, alg));
+                }
+                SearchRecursionResult::Stopped => break,
+                _ => {
+                    self.completed_subtree_roots[subtree_root_index].store(true, Ordering::Relaxed);
+                    individual_search_data.write_checkpoint_if_due(|| self.checkpoint());
+                }
+            }
+        }
+        ParallelSearchWorkerResult {
+            num_recursive_calls: individual_search_data
+                .recursive_work_tracker
+                .latest_depth_num_recursive_calls,
+            solutions,
+        }
+    }
+
+    /// A subtree in which a solution was found is never complete, so all
+    /// solutions before this subtree have already been returned.
+    fn first_incomplete_subtree_root_index(&self) -> Option<usize> {
+        self.completed_subtree_roots
+            .iter()
+            .position(|completed| !completed.load(Ordering::Relaxed))
+    }
+
+    /// The start of the earliest subtree that has not been searched completely.
+    /// Returns `None` if all subtrees have been searched completely.
+    fn checkpoint(&self) -> Option<SearchCheckpoint> {
+        let subtree_root = &self.subtree_roots[self.first_incomplete_subtree_root_index()?];
+        Some(SearchCheckpoint::new(
+            Depth(subtree_root.moves.len()) + subtree_root.remaining_depth,
+            subtree_root.moves.clone(),
+            &subtree_root.continuation_condition,
+        ))
+    }
 }
 
 pub struct IterativeDeepeningSearchCursor<'a, TPuzzle: SemiGroupActionPuzzle = KPuzzle> {
@@ -43,14 +165,43 @@ pub struct IterativeDeepeningSearchCursor<'a, TPuzzle: SemiGroupActionPuzzle = K
     individual_search_data: IndividualSearchData<TPuzzle>,
 }
 
+impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearchCursor<'_, TPuzzle> {
+    /// Stats for every search depth that has been finished so far.
+    pub fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        self.individual_search_data
+            .recursive_work_tracker
+            .finished_depth_stats()
+    }
+
+    /// `None` if the search may still return more solutions.
+    pub fn termination_reason(&self) -> Option<SearchTerminationReason> {
+        self.individual_search_data.termination_reason
+    }
+
+    pub fn prune_table(&self) -> &dyn PruneTable<TPuzzle> {
+        self.search.prune_table.as_ref()
+    }
+
+    /// Where to resume the search in order to get the solutions after the
+    /// ones that have been returned so far.
+    pub fn checkpoint(&self) -> &SearchCheckpoint {
+        &self.individual_search_data.checkpoint
+    }
+}
+
 impl<TPuzzle: SemiGroupActionPuzzle> Iterator for IterativeDeepeningSearchCursor<'_, TPuzzle> {
     type Item = Alg;
 
     fn next(&mut self) -> Option<Alg> {
+        if self.individual_search_data.termination_reason.is_some() {
+            return None;
+        }
         if self
             .individual_search_data
             .has_min_num_solutions_been_reached()
         {
+            self.individual_search_data.termination_reason =
+                Some(SearchTerminationReason::MinNumSolutionsReached);
             return None;
         }
         self.search
@@ -63,14 +214,43 @@ pub struct OwnedIterativeDeepeningSearchCursor<TPuzzle: SemiGroupActionPuzzle =
     individual_search_data: IndividualSearchData<TPuzzle>,
 }
 
+impl<TPuzzle: SemiGroupActionPuzzle> OwnedIterativeDeepeningSearchCursor<TPuzzle> {
+    /// Stats for every search depth that has been finished so far.
+    pub fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        self.individual_search_data
+            .recursive_work_tracker
+            .finished_depth_stats()
+    }
+
+    /// `None` if the search may still return more solutions.
+    pub fn termination_reason(&self) -> Option<SearchTerminationReason> {
+        self.individual_search_data.termination_reason
+    }
+
+    pub fn prune_table(&self) -> &dyn PruneTable<TPuzzle> {
+        self.search.prune_table.as_ref()
+    }
+
+    /// Where to resume the search in order to get the solutions after the
+    /// ones that have been returned so far.
+    pub fn checkpoint(&self) -> &SearchCheckpoint {
+        &self.individual_search_data.checkpoint
+    }
+}
+
 impl<TPuzzle: SemiGroupActionPuzzle> Iterator for OwnedIterativeDeepeningSearchCursor<TPuzzle> {
     type Item = Alg;
 
     fn next(&mut self) -> Option<Alg> {
+        if self.individual_search_data.termination_reason.is_some() {
+            return None;
+        }
         if self
             .individual_search_data
             .has_min_num_solutions_been_reached()
         {
+            self.individual_search_data.termination_reason =
+                Some(SearchTerminationReason::MinNumSolutionsReached);
             return None;
         }
         self.search
@@ -92,13 +272,15 @@ pub struct ImmutableSearchDataConstructionOptions {
     pub canonical_fsm_construction_options: CanonicalFSMConstructionOptions,
 }
 
-impl<TPuzzle: SemiGroupActionPuzzle> ImmutableSearchData<TPuzzle> {
+impl<TPuzzle: SemiGroupActionPuzzle + HasTargetPatternSignature> ImmutableSearchData<TPuzzle> {
     pub fn try_from_common_options(
         tpuzzle: TPuzzle,
         search_generators: SearchGenerators<TPuzzle>, // TODO: turn this back into `Generators`
         target_patterns: Vec<TPuzzle::Pattern>,
         options: ImmutableSearchDataConstructionOptions,
     ) -> Result<Self, SearchError> {
+        check_target_pattern_consistency_single_iter::<TPuzzle>(&mut target_patterns.iter())?;
+
         let canonical_fsm = CanonicalFSM::try_new(
             // TODO: avoid clones
             tpuzzle.clone(),
@@ -299,16 +481,36 @@ impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearch<TPuzzle> {
                 .get_max_depth()
         {
             let remaining_depth = Depth(remaining_depth);
+            individual_search_data.checkpoint = SearchCheckpoint {
+                depth: remaining_depth,
+                continuation_condition: initial_depth_continuation_condition.clone(),
+            };
+            if individual_search_data.search_limits.check(0) {
+                individual_search_data.termination_reason =
+                    individual_search_data.search_limits.stop_reason();
+                return None;
+            }
             self.immutable_search_data
                 .search_logger
                 .write_info("----------------");
 
+            let search_limits = individual_search_data.search_limits.clone();
             self.prune_table.extend_for_search_depth(
                 remaining_depth,
                 individual_search_data
                     .recursive_work_tracker
                     .estimate_next_level_num_recursive_calls(),
+                individual_search_data
+                    .individual_search_options
+                    .get_num_threads(),
+                &|| search_limits.check(0),
             );
+            // Filling the prune table can take a while, and stops early if a limit is reached.
+            if individual_search_data.search_limits.check(0) {
+                individual_search_data.termination_reason =
+                    individual_search_data.search_limits.stop_reason();
+                return None;
+            }
             individual_search_data
                 .recursive_work_tracker
                 .start_depth(remaining_depth, Some("Starting search…"));
@@ -320,28 +522,74 @@ impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearch<TPuzzle> {
                         .canonical_fsm_pre_moves,
                 )
                 .expect("TODO: invalid canonical FSM pre-moves.");
-            let recursion_result = self.recurse(
-                individual_search_data,
-                &mut pattern_stack,
-                initial_state,
-                remaining_depth,
-                SolutionMoves::default(),
-                initial_depth_continuation_condition,
-            );
+            let num_threads = individual_search_data
+                .individual_search_options
+                .get_num_threads();
+            let recursion_result = if num_threads > 1 && remaining_depth > Depth(1) {
+                self.recurse_in_parallel(
+                    individual_search_data,
+                    &mut pattern_stack,
+                    initial_state,
+                    remaining_depth,
+                    initial_depth_continuation_condition,
+                    num_threads,
+                )
+            } else {
+                self.recurse(
+                    individual_search_data,
+                    &mut pattern_stack,
+                    initial_state,
+                    remaining_depth,
+                    SolutionMoves::default(),
+                    initial_depth_continuation_condition,
+                )
+            };
             individual_search_data
                 .recursive_work_tracker
                 .finish_latest_depth();
-            if let SearchRecursionResult::FoundSolution(alg) = recursion_result {
-                // TODO: should we avoid writing into `root_continuation_condition`?
-                individual_search_data
-                    .individual_search_options
-                    .root_continuation_condition =
-                    ContinuationCondition::After(alg_to_moves(&alg).unwrap());
-                return Some(alg);
+            match recursion_result {
+                SearchRecursionResult::FoundSolution(alg) => {
+                    let continuation_condition =
+                        ContinuationCondition::After(alg_to_moves(&alg).unwrap());
+                    individual_search_data.checkpoint = SearchCheckpoint {
+                        depth: remaining_depth,
+                        continuation_condition: continuation_condition.clone(),
+                    };
+                    // TODO: should we avoid writing into `root_continuation_condition`?
+                    individual_search_data
+                        .individual_search_options
+                        .root_continuation_condition = continuation_condition;
+                    return Some(alg);
+                }
+                SearchRecursionResult::Stopped => {
+                    individual_search_data.termination_reason =
+                        individual_search_data.search_limits.stop_reason();
+                    return None;
+                }
+                _ => {}
             }
             initial_depth_continuation_condition = ContinuationCondition::None;
         }
 
+        // Resuming from here finds solutions if `max_depth_exclusive` is increased.
+        individual_search_data.checkpoint = SearchCheckpoint {
+            depth: max(
+                initial_search_depth,
+                individual_search_data
+                    .individual_search_options
+                    .get_max_depth(),
+            ),
+            continuation_condition: ContinuationCondition::None,
+        };
+        individual_search_data.termination_reason = Some(
+            match individual_search_data
+                .individual_search_options
+                .max_depth_exclusive
+            {
+                Some(_) => SearchTerminationReason::MaxDepthReached,
+                None => SearchTerminationReason::Exhausted,
+            },
+        );
         None
     }
 
@@ -362,7 +610,6 @@ impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearch<TPuzzle> {
         //     }
         // );
         // dbg!(&continuation_condition);
-        let mut continuation_condition = continuation_condition;
         let current_pattern = pattern_stack.current_pattern();
         // TODO: apply invalid checks only to intermediate state (i.e. exclude remaining_depth == 0)?
         if self
@@ -376,6 +623,16 @@ impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearch<TPuzzle> {
         individual_search_data
             .recursive_work_tracker
             .record_recursive_call();
+        if individual_search_data.should_stop(|| {
+            let moves = solution_moves.snapshot_moves();
+            SearchCheckpoint::new(
+                Depth(moves.len()) + remaining_depth,
+                moves,
+                &continuation_condition,
+            )
+        }) {
+            return SearchRecursionResult::Stopped;
+        }
         if remaining_depth == Depth(0) {
             return self.base_case(
                 individual_search_data,
@@ -393,6 +650,39 @@ impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearch<TPuzzle> {
             return SearchRecursionResult::ContinueSearchingDefault;
         }
 
+        if let Some(subtree_root_collector) = &mut individual_search_data.subtree_root_collector {
+            if remaining_depth == subtree_root_collector.remaining_depth {
+                subtree_root_collector.subtree_roots.push(SubtreeRoot {
+                    moves: solution_moves.snapshot_moves(),
+                    pattern: current_pattern.clone(),
+                    canonical_fsm_state: current_state,
+                    remaining_depth,
+                    continuation_condition,
+                });
+                return SearchRecursionResult::ContinueSearchingDefault;
+            }
+        }
+
+        self.recurse_children(
+            individual_search_data,
+            pattern_stack,
+            current_state,
+            remaining_depth,
+            solution_moves,
+            continuation_condition,
+        )
+    }
+
+    fn recurse_children(
+        &self,
+        individual_search_data: &mut IndividualSearchData<TPuzzle>,
+        pattern_stack: &mut PatternStack<TPuzzle>,
+        current_state: CanonicalFSMState,
+        remaining_depth: Depth,
+        solution_moves: SolutionMoves,
+        continuation_condition: ContinuationCondition,
+    ) -> SearchRecursionResult {
+        let mut continuation_condition = continuation_condition;
         for (move_class_index, move_transformation_multiples) in self
             .immutable_search_data
             .search_generators
@@ -463,12 +753,130 @@ impl<TPuzzle: SemiGroupActionPuzzle> IterativeDeepeningSearch<TPuzzle> {
                     SearchRecursionResult::FoundSolution(alg) => {
                         return SearchRecursionResult::FoundSolution(alg)
                     }
+                    SearchRecursionResult::Stopped => return SearchRecursionResult::Stopped,
                 }
             }
         }
         SearchRecursionResult::ContinueSearchingDefault
     }
 
+    /// Equivalent to calling `.recurse(…)` on the root of the search tree, but
+    /// searches subtrees on multiple threads.
+    ///
+    /// We split the tree at the shallowest depth that gives every thread enough
+    /// subtrees to work on. The subtrees are collected in the same order that
+    /// `.recurse(…)` would visit them, so we return the first solution of the
+    /// earliest subtree that has one. This requires waiting for all earlier
+    /// subtrees to finish, but ensures that solutions are returned in the same
+    /// order as a single-threaded search.
+    fn recurse_in_parallel(
+        &self,
+        individual_search_data: &mut IndividualSearchData<TPuzzle>,
+        pattern_stack: &mut PatternStack<TPuzzle>,
+        current_state: CanonicalFSMState,
+        remaining_depth: Depth,
+        continuation_condition: ContinuationCondition,
+        num_threads: usize,
+    ) -> SearchRecursionResult {
+        // Each split attempt re-visits the nodes above the split, so we only count the last one.
+        let num_recursive_calls_before_split = individual_search_data
+            .recursive_work_tracker
+            .latest_depth_num_recursive_calls;
+        let mut subtree_roots = vec![];
+        for split_depth in 1..*remaining_depth {
+            individual_search_data
+                .recursive_work_tracker
+                .latest_depth_num_recursive_calls = num_recursive_calls_before_split;
+            individual_search_data.subtree_root_collector = Some(SubtreeRootCollector {
+                remaining_depth: remaining_depth - Depth(split_depth),
+                subtree_roots: vec![],
+            });
+            // This cannot find a solution, since the base case is below the split.
+            let recursion_result = self.recurse(
+                individual_search_data,
+                pattern_stack,
+                current_state,
+                remaining_depth,
+                SolutionMoves::default(),
+                continuation_condition.clone(),
+            );
+            subtree_roots = individual_search_data
+                .subtree_root_collector
+                .take()
+                .unwrap()
+                .subtree_roots;
+            if let SearchRecursionResult::Stopped = recursion_result {
+                return SearchRecursionResult::Stopped;
+            }
+            if subtree_roots.len() >= num_threads * PARALLEL_SEARCH_MIN_SUBTREES_PER_THREAD {
+                break;
+            }
+        }
+
+        let parallel_search = ParallelSearch {
+            search: self,
+            individual_search_data,
+            subtree_roots: &subtree_roots,
+            next_subtree_root_index: AtomicUsize::new(0),
+            first_solution_subtree_root_index: AtomicUsize::new(usize::MAX),
+            completed_subtree_roots: subtree_roots
+                .iter()
+                .map(|_| AtomicBool::new(false))
+                .collect(),
+        };
+        let worker_results: Vec<ParallelSearchWorkerResult> = thread::scope(|scope| {
+            let worker_handles: Vec<_> = (0..num_threads.min(subtree_roots.len()))
+                .map(|_| scope.spawn(|| parallel_search.run_worker()))
+                .collect();
+            worker_handles
+                .into_iter()
+                .map(|worker_handle| worker_handle.join().expect("Search worker panicked."))
+                .collect()
+        });
+
+        let first_incomplete_subtree_root_index =
+            parallel_search.first_incomplete_subtree_root_index();
+        let checkpoint = parallel_search.checkpoint();
+        let mut first_solution: Option<(usize, Alg)> = None;
+        for worker_result in worker_results {
+            individual_search_data
+                .recursive_work_tracker
+                .record_recursive_calls(worker_result.num_recursive_calls);
+            for (subtree_root_index, alg) in worker_result.solutions {
+                if first_solution
+                    .as_ref()
+                    .is_none_or(|(first_index, _)| subtree_root_index < *first_index)
+                {
+                    first_solution = Some((subtree_root_index, alg));
+                }
+            }
+        }
+        // If the search was stopped, we can only return a solution if all
+        // earlier subtrees were searched completely.
+        if let (Some(_), Some(checkpoint), Some(first_incomplete_subtree_root_index)) = (
+            individual_search_data.search_limits.stop_reason(),
+            checkpoint,
+            first_incomplete_subtree_root_index,
+        ) {
+            if first_solution
+                .as_ref()
+                .is_none_or(|(subtree_root_index, _)| {
+                    first_incomplete_subtree_root_index < *subtree_root_index
+                })
+            {
+                individual_search_data.checkpoint = checkpoint;
+                return SearchRecursionResult::Stopped;
+            }
+        }
+        match first_solution {
+            Some((_, alg)) => {
+                individual_search_data.num_solutions_sofar += 1;
+                SearchRecursionResult::FoundSolution(alg)
+            }
+            None => SearchRecursionResult::ContinueSearchingDefault,
+        }
+    }
+
     // Returns `None` if the moves cannot be applied, else returns the result of applying the moves.
     fn apply_optional_fsm_moves(
         &self,
@@ -2,5 +2,7 @@ pub mod continuation_condition;
 pub mod individual_search;
 pub mod iterative_deepening_search;
 pub mod search_adaptations;
+pub mod search_checkpoint;
+pub mod search_limits;
 pub mod solution_moves;
 pub mod target_pattern_signature;
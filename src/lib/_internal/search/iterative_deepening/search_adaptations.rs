@@ -26,10 +26,11 @@ impl<TPuzzle: SemiGroupActionPuzzle> Default for StoredSearchAdaptations<TPuzzle
     }
 }
 
+#[derive(Clone)]
 #[allow(clippy::type_complexity)] // TODO
 pub struct IndividualSearchAdaptations<TPuzzle: SemiGroupActionPuzzle> {
     pub filter_search_solution_fn:
-        Option<Arc<dyn Fn(&TPuzzle::Pattern, &SolutionMoves) -> FilteringDecision>>,
+        Option<Arc<dyn Fn(&TPuzzle::Pattern, &SolutionMoves) -> FilteringDecision + Send + Sync>>,
 }
 
 impl<TPuzzle: SemiGroupActionPuzzle> Default for IndividualSearchAdaptations<TPuzzle> {
@@ -0,0 +1,116 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use cubing::alg::Move;
+use serde::{Deserialize, Serialize};
+
+use crate::_internal::search::prune_table_trait::Depth;
+
+use super::{
+    continuation_condition::ContinuationCondition, individual_search::IndividualSearchOptions,
+};
+
+/// A point in the search order from which a search can be resumed exactly:
+/// all solutions before it have already been returned, and none after it have.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchCheckpoint {
+    /// The search depth (i.e. solution length) at which to resume.
+    pub depth: Depth,
+    pub continuation_condition: ContinuationCondition,
+}
+
+impl SearchCheckpoint {
+    /// `moves` lead to a node in the search tree, which is visited with
+    /// `continuation_condition`.
+    pub(crate) fn new(
+        depth: Depth,
+        moves: Vec<Move>,
+        continuation_condition: &ContinuationCondition,
+    ) -> Self {
+        let continuation_condition = match continuation_condition {
+            ContinuationCondition::None => ContinuationCondition::At(moves),
+            ContinuationCondition::At(remaining_moves) => {
+                ContinuationCondition::At([moves, remaining_moves.clone()].concat())
+            }
+            ContinuationCondition::After(remaining_moves) => {
+                ContinuationCondition::After([moves, remaining_moves.clone()].concat())
+            }
+        };
+        Self {
+            depth,
+            continuation_condition,
+        }
+    }
+
+    /// Sets the options to resume a search from this checkpoint. This
+    /// overwrites `min_depth_inclusive` and `root_continuation_condition`.
+    pub fn resume(&self, individual_search_options: &mut IndividualSearchOptions) {
+        individual_search_options.min_depth_inclusive = Some(self.depth);
+        individual_search_options.root_continuation_condition = self.continuation_condition.clone();
+    }
+}
+
+/// Receives checkpoints periodically while searching, so that a long search
+/// can be resumed after it is interrupted (e.g. by a crash).
+///
+/// Note that a parallel search only creates checkpoints between the subtrees
+/// that it searches on separate threads, so checkpoints may be less frequent
+/// than `interval`.
+#[derive(Clone)]
+pub struct SearchCheckpointer {
+    pub interval: Duration,
+    pub checkpoint_fn: Arc<dyn Fn(&SearchCheckpoint) + Send + Sync>,
+}
+
+impl Debug for SearchCheckpointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchCheckpointer")
+            .field("interval", &self.interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SearchCheckpointer {
+    fn eq(&self, other: &Self) -> bool {
+        self.interval == other.interval && Arc::ptr_eq(&self.checkpoint_fn, &other.checkpoint_fn)
+    }
+}
+
+/// Decides when to call the checkpoint function. This is shared between all
+/// the threads of a parallel search.
+pub(crate) struct CheckpointSchedule {
+    checkpointer: SearchCheckpointer,
+    next_checkpoint_time: Mutex<instant::Instant>,
+}
+
+impl CheckpointSchedule {
+    pub(crate) fn new(checkpointer: SearchCheckpointer) -> Self {
+        let next_checkpoint_time = instant::Instant::now() + checkpointer.interval;
+        Self {
+            checkpointer,
+            next_checkpoint_time: Mutex::new(next_checkpoint_time),
+        }
+    }
+
+    /// If this returns `true`, the caller must call [`CheckpointSchedule::write_checkpoint`].
+    pub(crate) fn is_checkpoint_due(&self) -> bool {
+        let now = instant::Instant::now();
+        let mut next_checkpoint_time = self
+            .next_checkpoint_time
+            .lock()
+            .expect("Internal error: could not access checkpoint schedule");
+        if now < *next_checkpoint_time {
+            return false;
+        }
+        *next_checkpoint_time = now + self.checkpointer.interval;
+        true
+    }
+
+    pub(crate) fn write_checkpoint(&self, checkpoint: &SearchCheckpoint) {
+        (self.checkpointer.checkpoint_fn)(checkpoint);
+    }
+}
@@ -0,0 +1,100 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, OnceLock,
+};
+
+use super::individual_search::{IndividualSearchOptions, SearchTerminationReason};
+
+// Checking the limits (in particular, the time) on every recursive call would
+// slow down the search, so we only check them periodically.
+pub(crate) const SEARCH_LIMITS_CHECK_INTERVAL: usize = 1024;
+
+/// Allows cancelling a search from another thread. Clones share the same
+/// cancellation state.
+#[derive(Clone, Debug, Default)]
+pub struct SearchCancellationToken(Arc<AtomicBool>);
+
+impl SearchCancellationToken {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for SearchCancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// The cancellation token, deadline and node budget of an individual search.
+/// This is shared between all the threads of a parallel search, so that they
+/// stop together.
+pub(crate) struct SearchLimits {
+    cancellation_token: Option<SearchCancellationToken>,
+    deadline: Option<instant::Instant>,
+    max_num_recursive_calls: Option<usize>,
+    num_recursive_calls: AtomicUsize,
+    stop_reason: OnceLock<SearchTerminationReason>,
+}
+
+impl SearchLimits {
+    /// The timeout is measured from when this is called.
+    pub(crate) fn new(individual_search_options: &IndividualSearchOptions) -> Self {
+        Self {
+            cancellation_token: individual_search_options.cancellation_token.clone(),
+            deadline: individual_search_options
+                .timeout
+                .map(|timeout| instant::Instant::now() + timeout),
+            max_num_recursive_calls: individual_search_options.max_num_recursive_calls,
+            num_recursive_calls: AtomicUsize::new(0),
+            stop_reason: OnceLock::new(),
+        }
+    }
+
+    /// Adds `num_new_recursive_calls` to the total, and returns whether the
+    /// search should stop.
+    pub(crate) fn check(&self, num_new_recursive_calls: usize) -> bool {
+        if self.stop_reason.get().is_some() {
+            return true;
+        }
+        let num_recursive_calls = self
+            .num_recursive_calls
+            .fetch_add(num_new_recursive_calls, Ordering::Relaxed)
+            + num_new_recursive_calls;
+        let stop_reason = if self
+            .cancellation_token
+            .as_ref()
+            .is_some_and(|cancellation_token| cancellation_token.is_cancelled())
+        {
+            SearchTerminationReason::Cancelled
+        } else if self
+            .max_num_recursive_calls
+            .is_some_and(|max_num_recursive_calls| num_recursive_calls >= max_num_recursive_calls)
+        {
+            SearchTerminationReason::NodeLimitReached
+        } else if self
+            .deadline
+            .is_some_and(|deadline| instant::Instant::now() >= deadline)
+        {
+            SearchTerminationReason::TimeLimitReached
+        } else {
+            return false;
+        };
+        // If another thread got here first, we keep its reason.
+        let _ = self.stop_reason.set(stop_reason);
+        true
+    }
+
+    /// `None` unless a limit has been reached.
+    pub(crate) fn stop_reason(&self) -> Option<SearchTerminationReason> {
+        self.stop_reason.get().copied()
+    }
+}
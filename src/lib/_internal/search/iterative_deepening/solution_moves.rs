@@ -42,6 +42,22 @@ impl SolutionMoves<'_> {
         }
     }
 
+    pub fn snapshot_moves(&self) -> Vec<Move> {
+        let mut moves: Vec<Move> = self.reverse_move_iter().cloned().collect();
+        moves.reverse();
+        moves
+    }
+
+    /// Calls `f` with the result of pushing all of the given moves (in order).
+    pub fn with_pushed_moves<R, F: FnOnce(&SolutionMoves) -> R>(&self, moves: &[Move], f: F) -> R {
+        match moves.split_first() {
+            Some((first, rest)) => {
+                SolutionMoves::from(&self.push(first)).with_pushed_moves(rest, f)
+            }
+            None => f(self),
+        }
+    }
+
     pub fn reverse_move_iter(&self) -> SolutionMovesReverseIterator<'_> {
         SolutionMovesReverseIterator {
             solution_moves: self,
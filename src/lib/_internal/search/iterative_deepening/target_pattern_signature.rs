@@ -49,7 +49,6 @@ pub fn check_target_pattern_basic_consistency<
     target_patterns: &mut dyn Iterator<Item = &TPuzzle::Pattern>,
 ) -> Result<(), SearchError> {
     // dbg!(&reference_pattern);
-    // TODO: Push this further down into `try_new_prune_table_construction_shim` so it's used for 4x4x4
     let reference_pattern_orbit_signature =
         TPuzzle::get_target_pattern_signature(reference_pattern);
     // dbg!(&reference_pattern_orbit_signature);
@@ -7,7 +7,8 @@ pub mod iterative_deepening;
 pub(crate) mod mask_pattern;
 pub mod move_count;
 pub(crate) mod pattern_stack;
+pub mod prune_table_persistence;
 pub mod prune_table_trait;
-pub(crate) mod recursive_work_tracker;
+pub mod recursive_work_tracker;
 pub mod search_logger;
 pub mod whole_number_newtype;
@@ -0,0 +1,340 @@
+use std::{
+    fs::{create_dir_all, rename, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::PathBuf,
+    time::Duration,
+};
+
+use cubing::kpuzzle::{KPattern, KPuzzle};
+use sha2::{Digest, Sha256};
+
+use crate::_internal::canonical_fsm::search_generators::SearchGenerators;
+
+use super::hash_prune_table::DepthU8;
+
+// Bump this whenever the file format or the contents of a prune table change
+// meaning (e.g. a change to pattern hashing or sentinel values).
+const PRUNE_TABLE_FILE_FORMAT_VERSION: u32 = 1;
+const PRUNE_TABLE_FILE_MAGIC: &[u8; 8] = b"TWIPSPRT";
+const PRUNE_TABLE_FILE_EXTENSION: &str = "prune-table";
+
+// Pattern hashes are computed from the in-memory layout of `KPattern`, which is
+// defined by the `cubing` crate. Keep this in sync with the `cubing` dependency.
+const CUBING_VERSION: &str = "0.15.0-pre.1";
+
+/// Tables that take at least this long to fill are written to disk when using [`PruneTableWriteCondition::Auto`].
+const AUTO_WRITE_MIN_FILL_DURATION: Duration = Duration::from_secs(10);
+
+const IO_CHUNK_SIZE: usize = 1 << 20;
+
+pub type PruneTableFingerprint = [u8; 32];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PruneTableWriteCondition {
+    /// Only write tables that took a significant amount of time to fill.
+    #[default]
+    Auto,
+    Never,
+    Always,
+}
+
+#[derive(Clone, Debug)]
+pub struct PruneTablePersistenceOptions {
+    /// Prune tables are read from (and written to) this folder.
+    pub cache_dir: PathBuf,
+    pub write_condition: PruneTableWriteCondition,
+}
+
+impl PruneTableWriteCondition {
+    pub(crate) fn should_write(&self, fill_duration: Duration) -> bool {
+        match self {
+            PruneTableWriteCondition::Auto => fill_duration >= AUTO_WRITE_MIN_FILL_DURATION,
+            PruneTableWriteCondition::Never => false,
+            PruneTableWriteCondition::Always => true,
+        }
+    }
+}
+
+pub(crate) struct PruneTablePersistence {
+    pub(crate) options: PruneTablePersistenceOptions,
+    /// Identifies everything that determines the contents of the table, except for its size.
+    pub(crate) fingerprint: PruneTableFingerprint,
+}
+
+pub(crate) struct StoredPruneTable {
+    pub(crate) pruning_depth: DepthU8,
+    pub(crate) pattern_hash_to_depth: Vec<DepthU8>,
+}
+
+struct PruneTableFileHeader {
+    fingerprint: PruneTableFingerprint,
+    prune_table_size: u64,
+    pruning_depth: u8,
+}
+
+impl PruneTableFileHeader {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = PRUNE_TABLE_FILE_MAGIC.to_vec();
+        bytes.extend_from_slice(&PRUNE_TABLE_FILE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.fingerprint);
+        bytes.extend_from_slice(&self.prune_table_size.to_le_bytes());
+        bytes.push(self.pruning_depth);
+        bytes
+    }
+
+    fn read_from(reader: &mut impl Read) -> Result<Self, String> {
+        let mut magic = [0u8; 8];
+        read_exact(reader, &mut magic)?;
+        if &magic != PRUNE_TABLE_FILE_MAGIC {
+            return Err("not a prune table file".to_owned());
+        }
+        let mut version = [0u8; 4];
+        read_exact(reader, &mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != PRUNE_TABLE_FILE_FORMAT_VERSION {
+            return Err(format!(
+                "unsupported file format version {} (expected: {})",
+                version, PRUNE_TABLE_FILE_FORMAT_VERSION
+            ));
+        }
+        let mut fingerprint = PruneTableFingerprint::default();
+        read_exact(reader, &mut fingerprint)?;
+        let mut prune_table_size = [0u8; 8];
+        read_exact(reader, &mut prune_table_size)?;
+        let mut pruning_depth = [0u8; 1];
+        read_exact(reader, &mut pruning_depth)?;
+        Ok(Self {
+            fingerprint,
+            prune_table_size: u64::from_le_bytes(prune_table_size),
+            pruning_depth: pruning_depth[0],
+        })
+    }
+}
+
+fn read_exact(reader: &mut impl Read, buf: &mut [u8]) -> Result<(), String> {
+    reader
+        .read_exact(buf)
+        .map_err(|e| format!("could not read file ({})", e))
+}
+
+impl PruneTablePersistence {
+    pub(crate) fn file_path(&self, prune_table_size: usize) -> PathBuf {
+        self.options.cache_dir.join(format!(
+            "{}-{}.{}",
+            hex::encode(self.fingerprint),
+            prune_table_size,
+            PRUNE_TABLE_FILE_EXTENSION
+        ))
+    }
+
+    /// Returns `Ok(None)` if there is no stored table, and `Err(…)` if there is
+    /// a stored table that cannot be used.
+    pub(crate) fn read(&self, prune_table_size: usize) -> Result<Option<StoredPruneTable>, String> {
+        let file = match File::open(self.file_path(prune_table_size)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("could not open file ({})", e)),
+        };
+        let mut reader = BufReader::new(file);
+        let mut hasher = Sha256::new();
+
+        let header = PruneTableFileHeader::read_from(&mut reader)?;
+        if header.fingerprint != self.fingerprint {
+            return Err("fingerprint mismatch".to_owned());
+        }
+        if header.prune_table_size != prune_table_size as u64 {
+            return Err("size mismatch".to_owned());
+        }
+        hasher.update(header.to_bytes());
+
+        let mut pattern_hash_to_depth = Vec::with_capacity(prune_table_size);
+        let mut chunk = vec![0u8; IO_CHUNK_SIZE];
+        while pattern_hash_to_depth.len() < prune_table_size {
+            let chunk_size = usize::min(
+                IO_CHUNK_SIZE,
+                prune_table_size - pattern_hash_to_depth.len(),
+            );
+            let chunk = &mut chunk[..chunk_size];
+            read_exact(&mut reader, chunk)?;
+            hasher.update(&chunk);
+            pattern_hash_to_depth.extend(chunk.iter().map(|value| DepthU8(*value)));
+        }
+
+        let mut checksum = [0u8; 32];
+        read_exact(&mut reader, &mut checksum)?;
+        if checksum != <[u8; 32]>::from(hasher.finalize()) {
+            return Err("checksum mismatch (the file may be corrupted)".to_owned());
+        }
+        if reader.read(&mut [0u8; 1]).map_err(|e| e.to_string())? != 0 {
+            return Err("unexpected trailing data".to_owned());
+        }
+
+        Ok(Some(StoredPruneTable {
+            pruning_depth: DepthU8(header.pruning_depth),
+            pattern_hash_to_depth,
+        }))
+    }
+
+    pub(crate) fn write(
+        &self,
+        pruning_depth: DepthU8,
+        pattern_hash_to_depth: &[DepthU8],
+    ) -> Result<PathBuf, String> {
+        create_dir_all(&self.options.cache_dir)
+            .map_err(|e| format!("could not create cache dir ({})", e))?;
+        let file_path = self.file_path(pattern_hash_to_depth.len());
+        // Write to a temporary file first so that an interrupted write never leaves a truncated table at the final path.
+        let temp_file_path = file_path.with_extension(format!(
+            "{}.partial-{}",
+            PRUNE_TABLE_FILE_EXTENSION,
+            std::process::id()
+        ));
+
+        let write_error = |e: std::io::Error| format!("could not write file ({})", e);
+        let mut writer = BufWriter::new(File::create(&temp_file_path).map_err(write_error)?);
+        let mut hasher = Sha256::new();
+
+        let header_bytes = PruneTableFileHeader {
+            fingerprint: self.fingerprint,
+            prune_table_size: pattern_hash_to_depth.len() as u64,
+            pruning_depth: pruning_depth.0,
+        }
+        .to_bytes();
+        writer.write_all(&header_bytes).map_err(write_error)?;
+        hasher.update(&header_bytes);
+
+        for chunk in pattern_hash_to_depth.chunks(IO_CHUNK_SIZE) {
+            let chunk: Vec<u8> = chunk.iter().map(|value| value.0).collect();
+            writer.write_all(&chunk).map_err(write_error)?;
+            hasher.update(&chunk);
+        }
+        writer
+            .write_all(&<[u8; 32]>::from(hasher.finalize()))
+            .map_err(write_error)?;
+        writer.flush().map_err(write_error)?;
+        drop(writer);
+
+        rename(&temp_file_path, &file_path).map_err(write_error)?;
+        Ok(file_path)
+    }
+}
+
+fn hash_kpattern(hasher: &mut Sha256, pattern: &KPattern) {
+    for orbit_info in pattern.kpuzzle().orbit_info_iter() {
+        for i in 0..orbit_info.num_pieces {
+            let orientation_with_mod = pattern.get_orientation_with_mod(orbit_info, i);
+            hasher.update([
+                pattern.get_piece(orbit_info, i),
+                orientation_with_mod.orientation,
+                orientation_with_mod.orientation_mod,
+            ]);
+        }
+    }
+}
+
+/// Computes a fingerprint for everything that affects the contents of a hash
+/// prune table for a `KPuzzle` search (except for the table size).
+pub fn kpuzzle_prune_table_fingerprint(
+    kpuzzle: &KPuzzle,
+    search_generators: &SearchGenerators<KPuzzle>,
+    target_patterns: &[KPattern],
+) -> PruneTableFingerprint {
+    let mut hasher = Sha256::new();
+    // Pattern hashes depend on the in-memory representation of patterns, which
+    // is not guaranteed to be stable across versions.
+    hasher.update(format!("twips v{}\n", env!("CARGO_PKG_VERSION")));
+    hasher.update(format!("cubing v{}\n", CUBING_VERSION));
+    for orbit_info in kpuzzle.orbit_info_iter() {
+        hasher.update(format!(
+            "orbit {} {} {}\n",
+            orbit_info.name, orbit_info.num_pieces, orbit_info.num_orientations
+        ));
+    }
+    // The generator order can vary between runs (and does not affect the table contents), so we sort the moves first.
+    let mut move_fingerprints: Vec<Vec<u8>> = search_generators
+        .flat
+        .iter()
+        .map(|(_, move_transformation_info)| {
+            let mut move_fingerprint =
+                format!("move {}\n", move_transformation_info.r#move).into_bytes();
+            let transformation = &move_transformation_info.transformation;
+            for orbit_info in kpuzzle.orbit_info_iter() {
+                for i in 0..orbit_info.num_pieces {
+                    move_fingerprint.push(transformation.get_permutation_idx(orbit_info, i));
+                    move_fingerprint.push(transformation.get_orientation_delta(orbit_info, i));
+                }
+            }
+            move_fingerprint
+        })
+        .collect();
+    move_fingerprints.sort();
+    for move_fingerprint in move_fingerprints {
+        hasher.update(move_fingerprint);
+    }
+    for target_pattern in target_patterns {
+        hasher.update("target pattern\n");
+        hash_kpattern(&mut hasher, target_pattern);
+    }
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{read, remove_dir_all, write};
+
+    use super::{
+        DepthU8, PruneTablePersistence, PruneTablePersistenceOptions, PruneTableWriteCondition,
+        CUBING_VERSION,
+    };
+
+    #[test]
+    fn cubing_version_matches_dependency() {
+        let workspace_manifest =
+            std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../Cargo.toml"))
+                .unwrap();
+        assert!(workspace_manifest.contains(&format!("\ncubing = \"{}\"\n", CUBING_VERSION)));
+    }
+
+    #[test]
+    fn prune_table_persistence_round_trip() {
+        let cache_dir = std::env::temp_dir().join(format!(
+            "twips-prune-table-persistence-test-{}",
+            std::process::id()
+        ));
+        let persistence = PruneTablePersistence {
+            options: PruneTablePersistenceOptions {
+                cache_dir: cache_dir.clone(),
+                write_condition: PruneTableWriteCondition::Always,
+            },
+            fingerprint: [3; 32],
+        };
+        let pattern_hash_to_depth: Vec<DepthU8> =
+            (0..(1 << 12)).map(|i| DepthU8((i % 7) as u8)).collect();
+
+        assert!(persistence.read(1 << 12).unwrap().is_none());
+        let file_path = persistence
+            .write(DepthU8(5), &pattern_hash_to_depth)
+            .unwrap();
+        let stored_prune_table = persistence.read(1 << 12).unwrap().unwrap();
+        assert_eq!(stored_prune_table.pruning_depth, DepthU8(5));
+        assert_eq!(
+            stored_prune_table.pattern_hash_to_depth,
+            pattern_hash_to_depth
+        );
+
+        // A different fingerprint must not match.
+        let other_persistence = PruneTablePersistence {
+            options: persistence.options.clone(),
+            fingerprint: [4; 32],
+        };
+        assert!(other_persistence.read(1 << 12).unwrap().is_none());
+
+        // Corrupted data must be rejected.
+        let mut bytes = read(&file_path).unwrap();
+        bytes[100] ^= 1;
+        write(&file_path, bytes).unwrap();
+        assert!(persistence.read(1 << 12).is_err());
+
+        remove_dir_all(cache_dir).unwrap();
+    }
+}
@@ -1,10 +1,31 @@
 use crate::{_internal::puzzle_traits::puzzle_traits::SemiGroupActionPuzzle, whole_number_newtype};
 
+use super::recursive_work_tracker::RecursiveWorkDepthStats;
+
 whole_number_newtype!(Depth, usize);
 
 pub trait PruneTable<TPuzzle: SemiGroupActionPuzzle> {
     fn lookup(&self, pattern: &TPuzzle::Pattern) -> Depth;
 
     // TODO: generalize to more powerful notions of "extend"?
-    fn extend_for_search_depth(&mut self, search_depth: Depth, approximate_num_entries: usize);
+    /// Implementations may use up to `num_threads` threads (including the
+    /// calling thread). They may also check `should_stop` periodically and
+    /// stop early, leaving the table valid for a lower depth.
+    fn extend_for_search_depth(
+        &mut self,
+        search_depth: Depth,
+        approximate_num_entries: usize,
+        num_threads: usize,
+        should_stop: &(dyn Fn() -> bool + Sync),
+    );
+
+    /// Stats for the work done to populate the table so far (if the table keeps track).
+    fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        &[]
+    }
+
+    /// The approximate memory used by the table entries (if the table keeps track).
+    fn approximate_num_bytes(&self) -> usize {
+        0
+    }
 }
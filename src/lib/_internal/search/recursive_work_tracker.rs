@@ -4,6 +4,13 @@ use thousands::Separable;
 
 use super::{prune_table_trait::Depth, search_logger::SearchLogger};
 
+#[derive(Clone, Debug)]
+pub struct RecursiveWorkDepthStats {
+    pub depth: Depth,
+    pub num_recursive_calls: usize,
+    pub duration: Duration,
+}
+
 pub(crate) struct RecursiveWorkTracker {
     work_name: String,
     // TODO: support custom writes intead of sending to stdout/stderr
@@ -15,6 +22,8 @@ pub(crate) struct RecursiveWorkTracker {
 
     previous_depth_num_recursive_calls: usize,
 
+    finished_depth_stats: Vec<RecursiveWorkDepthStats>,
+
     search_logger: Arc<SearchLogger>,
 }
 
@@ -28,6 +37,7 @@ impl RecursiveWorkTracker {
             latest_depth_duration: Duration::ZERO,
             latest_depth_finished: true,
             latest_depth_num_recursive_calls: 0,
+            finished_depth_stats: vec![],
             search_logger,
         }
     }
@@ -76,12 +86,33 @@ impl RecursiveWorkTracker {
             rate.separate_with_underscores()
         ));
         self.latest_depth_finished = true;
+        // A search that is resumed after returning a solution finishes the same depth again.
+        match self.finished_depth_stats.last_mut() {
+            Some(stats) if stats.depth == self.latest_depth => {
+                stats.num_recursive_calls += self.latest_depth_num_recursive_calls;
+                stats.duration += self.latest_depth_duration;
+            }
+            _ => self.finished_depth_stats.push(RecursiveWorkDepthStats {
+                depth: self.latest_depth,
+                num_recursive_calls: self.latest_depth_num_recursive_calls,
+                duration: self.latest_depth_duration,
+            }),
+        }
+    }
+
+    /// Stats for every depth that has been finished so far, in order. Each depth is listed once.
+    pub fn finished_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        &self.finished_depth_stats
     }
 
     pub fn record_recursive_call(&mut self) {
         self.latest_depth_num_recursive_calls += 1;
     }
 
+    pub fn record_recursive_calls(&mut self, num_recursive_calls: usize) {
+        self.latest_depth_num_recursive_calls += num_recursive_calls;
+    }
+
     pub fn estimate_next_level_num_recursive_calls(&self) -> usize {
         if self.previous_depth_num_recursive_calls == 0 {
             return self.latest_depth_num_recursive_calls;
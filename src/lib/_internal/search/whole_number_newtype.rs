@@ -8,6 +8,7 @@
 macro_rules! whole_number_newtype {
     ($e: ident, $u_type: ident) => {
         #[derive(PartialEq, Eq, Copy, Clone, Debug, Hash, Default, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+        #[repr(transparent)]
         pub struct $e(pub $u_type);
 
         // TODO: Make this a derived trait?
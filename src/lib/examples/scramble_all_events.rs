@@ -22,10 +22,10 @@ pub fn main() {
         // Event::Cube4x4x4Blindfolded,
         Event::Cube5x5x5Blindfolded,
         Event::Cube3x3x3MultiBlind,
-        //Event::FTOSpeedsolving
-        //Event::MasterTetraminxSpeedsolving
+        Event::FTOSpeedsolving,
+        Event::MasterTetraminxSpeedsolving,
         Event::KilominxSpeedsolving,
-        //Event::RediCubeSpeedsolving
+        Event::RediCubeSpeedsolving,
         Event::BabyFTOSpeedsolving,
     ] {
         let start_time = Instant::now();
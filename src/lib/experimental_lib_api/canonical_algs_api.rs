@@ -0,0 +1,263 @@
+use std::collections::HashSet;
+
+use cubing::{
+    alg::{Alg, AlgNode, Move},
+    kpuzzle::{KPattern, KPuzzle},
+};
+
+use crate::_internal::{
+    canonical_fsm::{
+        canonical_fsm::{CanonicalFSM, CanonicalFSMState, CANONICAL_FSM_START_STATE},
+        search_generators::{
+            Generators, KPuzzleWithGeneratorMoves, SearchGenerators,
+            SearchGeneratorsConstructorOptions,
+        },
+    },
+    errors::{SearchError, TwipsError},
+    notation::metric::TurnMetric,
+    puzzle_traits::puzzle_traits::SemiGroupActionPuzzle,
+    search::prune_table_trait::Depth,
+};
+
+#[derive(Default)]
+pub struct CanonicalAlgsOptions {
+    /// The pattern that the canonical algs are applied to when counting
+    /// distinct patterns. Defaults to the default pattern of the definition.
+    pub start_pattern: Option<KPattern>,
+    pub generators: Generators,
+    pub metric: Option<TurnMetric>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanonicalAlgsDepthCounts {
+    pub depth: Depth,
+    /// The number of algs of exactly this depth that are accepted by the canonical FSM.
+    pub num_canonical_algs: usize,
+    /// The number of distinct patterns produced by applying these algs to the start pattern.
+    pub num_distinct_patterns: usize,
+}
+
+/// Enumerates the move sequences accepted by the canonical FSM, one depth at a time.
+///
+/// Usage example:
+///
+/// ```
+/// use cubing::{alg::parse_move, puzzles::cube2x2x2_kpuzzle};
+/// use twips::{
+///     _internal::{canonical_fsm::search_generators::Generators, search::prune_table_trait::Depth},
+///     experimental_lib_api::{CanonicalAlgs, CanonicalAlgsOptions},
+/// };
+///
+/// let canonical_algs = CanonicalAlgs::try_new(
+///     cube2x2x2_kpuzzle(),
+///     CanonicalAlgsOptions {
+///         generators: Generators::from(vec![parse_move!("U").clone(), parse_move!("R").clone()]),
+///         ..Default::default()
+///     },
+/// )
+/// .unwrap();
+/// let counts = canonical_algs.enumerate_depth(Depth(2), None);
+/// assert_eq!(counts.num_canonical_algs, 18);
+/// ```
+pub struct CanonicalAlgs {
+    kpuzzle_with_generator_moves: KPuzzleWithGeneratorMoves,
+    search_generators: SearchGenerators<KPuzzle>,
+    canonical_fsm: CanonicalFSM<KPuzzle>,
+    start_pattern: KPattern,
+}
+
+impl CanonicalAlgs {
+    pub fn try_new(kpuzzle: &KPuzzle, options: CanonicalAlgsOptions) -> Result<Self, TwipsError> {
+        let kpuzzle_with_generator_moves =
+            options.generators.kpuzzle_with_generator_moves(kpuzzle)?;
+        let start_pattern = kpuzzle_with_generator_moves.convert_pattern(
+            &options
+                .start_pattern
+                .unwrap_or_else(|| kpuzzle.default_pattern()),
+        )?;
+        let search_generators = SearchGenerators::try_new(
+            &kpuzzle_with_generator_moves.kpuzzle,
+            kpuzzle_with_generator_moves.generator_moves.clone(),
+            SearchGeneratorsConstructorOptions {
+                metric: options.metric,
+                random_start: Some(false),
+            },
+        )?;
+        let canonical_fsm = CanonicalFSM::try_new(
+            kpuzzle_with_generator_moves.kpuzzle.clone(),
+            search_generators.clone(),
+            Default::default(),
+        )
+        .map_err(|e| SearchError {
+            description: e.to_string(),
+        })?;
+        Ok(Self {
+            kpuzzle_with_generator_moves,
+            search_generators,
+            canonical_fsm,
+            start_pattern,
+        })
+    }
+
+    /// Enumerates all canonical algs of exactly the given depth. If
+    /// `on_canonical_alg` is specified, it is called for each alg (with any alg
+    /// generators expanded).
+    pub fn enumerate_depth(
+        &self,
+        depth: Depth,
+        mut on_canonical_alg: Option<&mut dyn FnMut(&Alg)>,
+    ) -> CanonicalAlgsDepthCounts {
+        let mut enumeration = DepthEnumeration {
+            num_canonical_algs: 0,
+            distinct_patterns: HashSet::new(),
+            moves: vec![],
+        };
+        self.recurse(
+            &mut enumeration,
+            &mut on_canonical_alg,
+            &self.start_pattern,
+            CANONICAL_FSM_START_STATE,
+            depth,
+        );
+        CanonicalAlgsDepthCounts {
+            depth,
+            num_canonical_algs: enumeration.num_canonical_algs,
+            num_distinct_patterns: enumeration.distinct_patterns.len(),
+        }
+    }
+
+    fn recurse(
+        &self,
+        enumeration: &mut DepthEnumeration,
+        on_canonical_alg: &mut Option<&mut dyn FnMut(&Alg)>,
+        current_pattern: &KPattern,
+        current_state: CanonicalFSMState,
+        remaining_depth: Depth,
+    ) {
+        if remaining_depth == Depth(0) {
+            enumeration.num_canonical_algs += 1;
+            enumeration
+                .distinct_patterns
+                .insert(current_pattern.clone());
+            if let Some(on_canonical_alg) = on_canonical_alg {
+                let alg = Alg {
+                    nodes: enumeration
+                        .moves
+                        .iter()
+                        .cloned()
+                        .map(AlgNode::MoveNode)
+                        .collect(),
+                };
+                on_canonical_alg(
+                    &self
+                        .kpuzzle_with_generator_moves
+                        .expand_alg_generators(&alg),
+                );
+            }
+            return;
+        }
+        for (move_class_index, move_transformation_multiples) in
+            self.search_generators.by_move_class.iter()
+        {
+            let Some(next_state) = self
+                .canonical_fsm
+                .next_state(current_state, move_class_index)
+            else {
+                continue;
+            };
+            for move_transformation_info in move_transformation_multiples {
+                let Some(next_pattern) = self
+                    .kpuzzle_with_generator_moves
+                    .kpuzzle
+                    .pattern_apply_transformation(
+                        current_pattern,
+                        &move_transformation_info.transformation,
+                    )
+                else {
+                    continue;
+                };
+                enumeration
+                    .moves
+                    .push(move_transformation_info.r#move.clone());
+                self.recurse(
+                    enumeration,
+                    on_canonical_alg,
+                    &next_pattern,
+                    next_state,
+                    remaining_depth - Depth(1),
+                );
+                enumeration.moves.pop();
+            }
+        }
+    }
+}
+
+struct DepthEnumeration {
+    num_canonical_algs: usize,
+    distinct_patterns: HashSet<KPattern>,
+    moves: Vec<Move>,
+}
+
+#[cfg(test)]
+mod tests {
+    use cubing::{alg::parse_move, puzzles::cube3x3x3_kpuzzle};
+
+    use crate::{
+        _internal::{
+            canonical_fsm::search_generators::Generators, notation::metric::TurnMetric,
+            search::prune_table_trait::Depth,
+        },
+        experimental_lib_api::{CanonicalAlgs, CanonicalAlgsOptions},
+    };
+
+    #[test]
+    fn canonical_algs_api_test() {
+        let canonical_algs = CanonicalAlgs::try_new(
+            cube3x3x3_kpuzzle(),
+            CanonicalAlgsOptions {
+                generators: Generators::from(
+                    ["U", "L", "F", "R", "B", "D"]
+                        .map(|r#move| r#move.parse().unwrap())
+                        .to_vec(),
+                ),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // https://oeis.org/A080601
+        let num_canonical_algs: Vec<usize> = (0..=3)
+            .map(|depth| {
+                canonical_algs
+                    .enumerate_depth(Depth(depth), None)
+                    .num_canonical_algs
+            })
+            .collect();
+        assert_eq!(num_canonical_algs, vec![1, 18, 243, 3240]);
+        // Every canonical alg of depth 3 or less produces a distinct pattern.
+        assert_eq!(
+            canonical_algs
+                .enumerate_depth(Depth(3), None)
+                .num_distinct_patterns,
+            3240
+        );
+
+        let canonical_algs = CanonicalAlgs::try_new(
+            cube3x3x3_kpuzzle(),
+            CanonicalAlgsOptions {
+                generators: Generators::from(vec![
+                    parse_move!("U").clone(),
+                    parse_move!("R").clone(),
+                ]),
+                metric: Some(TurnMetric::Quantum),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let mut algs = vec![];
+        let counts =
+            canonical_algs.enumerate_depth(Depth(2), Some(&mut |alg| algs.push(alg.to_string())));
+        // Quarter turns only, and a move family is never repeated.
+        assert_eq!(counts.num_canonical_algs, 8);
+        assert_eq!(algs.len(), 8);
+    }
+}
@@ -1,6 +1,11 @@
 use derive_more::From;
+use serde::Deserialize;
 
-use std::path::PathBuf;
+use std::{
+    fs::read_to_string,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use crate::_internal::{errors::ArgumentError, read_to_json::read_to_json};
 use cubing::{
@@ -30,6 +35,14 @@ impl KPuzzleSource {
     }
 }
 
+/// A pattern file can contain either a single pattern or a list of patterns.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KPatternDataFileContents {
+    Single(KPatternData),
+    Multiple(Vec<KPatternData>),
+}
+
 // TODO: can we afford to make these fields into references?
 #[derive(Debug, From, Default)]
 pub enum PatternSource {
@@ -69,4 +82,150 @@ impl PatternSource {
             }
         })
     }
+
+    /// Like [`PatternSource::kpattern`], except that a file may contain a list of patterns:
+    ///
+    /// - a `.json` file with a single pattern or a JSON list of patterns,
+    /// - a `.jsonl` file with one pattern per line, or
+    /// - a `.txt` file with one alg per line (applied to the default pattern).
+    ///
+    /// Blank lines are ignored in `.jsonl` and `.txt` files.
+    pub fn kpatterns(
+        &self,
+        kpuzzle: &KPuzzle,
+    ) -> Result<Option<Vec<KPattern>>, ArgumentError /* TODO */> {
+        let PatternSource::FilePath(path_buf) = self else {
+            return Ok(self.kpattern(kpuzzle)?.map(|kpattern| vec![kpattern]));
+        };
+        if let Some(line_format) = PatternListLineFormat::from_path(path_buf) {
+            let input_str = read_to_string(path_buf).or(Err("Could not read input file."))?;
+            let mut kpatterns = vec![];
+            for (i, line) in input_str.lines().enumerate() {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let kpattern = match line_format {
+                    PatternListLineFormat::KPatternData => serde_json::from_str(line)
+                        .map_err(|e| e.to_string())
+                        .and_then(|kpattern_data: KPatternData| {
+                            KPattern::try_from_data(kpuzzle, &kpattern_data)
+                                .map_err(|e| e.to_string())
+                        }),
+                    PatternListLineFormat::Alg => Alg::from_str(line)
+                        .map_err(|e| e.to_string())
+                        .and_then(|alg| {
+                            kpuzzle
+                                .default_pattern()
+                                .apply_alg(&alg)
+                                .map_err(|e| e.to_string())
+                        }),
+                };
+                kpatterns.push(kpattern.map_err(|e| ArgumentError {
+                    description: format!("Invalid pattern on line {}: {}", i + 1, e),
+                })?);
+            }
+            return Ok(Some(kpatterns));
+        }
+        let kpattern_data_list = match read_to_json(path_buf)? {
+            KPatternDataFileContents::Single(kpattern_data) => vec![kpattern_data],
+            KPatternDataFileContents::Multiple(kpattern_data_list) => kpattern_data_list,
+        };
+        let kpatterns = kpattern_data_list
+            .iter()
+            .map(|kpattern_data| {
+                KPattern::try_from_data(kpuzzle, kpattern_data).map_err(|e| ArgumentError {
+                    description: e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<KPattern>, ArgumentError>>()?;
+        Ok(Some(kpatterns))
+    }
+
+    /// Whether this is a file that lists patterns line by line (see [`PatternSource::kpatterns`]).
+    pub fn is_pattern_list_file(&self) -> bool {
+        match self {
+            PatternSource::FilePath(path_buf) => {
+                PatternListLineFormat::from_path(path_buf).is_some()
+            }
+            _ => false,
+        }
+    }
+}
+
+enum PatternListLineFormat {
+    KPatternData,
+    Alg,
+}
+
+impl PatternListLineFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "jsonl" => Some(Self::KPatternData),
+            "txt" => Some(Self::Alg),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::{remove_dir_all, write};
+
+    use cubing::{alg::parse_alg, puzzles::cube2x2x2_kpuzzle};
+
+    use super::PatternSource;
+
+    #[test]
+    fn pattern_list_files() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let dir = std::env::temp_dir().join(format!(
+            "twips-pattern-list-files-test-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pattern1 = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U"))
+            .unwrap();
+        let pattern2 = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("F2 L'"))
+            .unwrap();
+
+        let jsonl_path = dir.join("patterns.jsonl");
+        write(
+            &jsonl_path,
+            format!(
+                "{}\n\n{}\n",
+                serde_json::to_string(&pattern1.to_data()).unwrap(),
+                serde_json::to_string(&pattern2.to_data()).unwrap()
+            ),
+        )
+        .unwrap();
+        let jsonl_source = PatternSource::FilePath(jsonl_path);
+        assert!(jsonl_source.is_pattern_list_file());
+        assert_eq!(
+            jsonl_source.kpatterns(kpuzzle).unwrap(),
+            Some(vec![pattern1.clone(), pattern2.clone()])
+        );
+
+        let txt_path = dir.join("scrambles.txt");
+        write(&txt_path, "R U\n  \nF2 L'\n").unwrap();
+        let txt_source = PatternSource::FilePath(txt_path.clone());
+        assert!(txt_source.is_pattern_list_file());
+        assert_eq!(
+            txt_source.kpatterns(kpuzzle).unwrap(),
+            Some(vec![pattern1, pattern2])
+        );
+
+        // Errors report the (1-indexed) line, counting blank lines.
+        write(&txt_path, "R U\n\nR (U\n").unwrap();
+        let error = txt_source.kpatterns(kpuzzle).unwrap_err();
+        assert!(error.description.starts_with("Invalid pattern on line 3:"));
+
+        assert!(!PatternSource::FilePath(dir.join("pattern.json")).is_pattern_list_file());
+
+        remove_dir_all(&dir).unwrap();
+    }
 }
@@ -7,11 +7,31 @@ pub use common::KPuzzleSource;
 pub use common::PatternSource; // TODO // TODO
 
 mod search_api;
-pub use search_api::{search, SearchOptions};
+pub use search_api::{
+    search, BatchSearch, BatchSearchSolutions, ExpandedSearchSolutions, SearchOptions,
+    SearchSolutions, SearchSolutionsCursor,
+};
 
 mod gods_algorithm_api;
 pub use gods_algorithm_api::{gods_algorithm, GodsAlgorithmOptions};
 
+mod canonical_algs_api;
+pub use canonical_algs_api::{CanonicalAlgs, CanonicalAlgsDepthCounts, CanonicalAlgsOptions};
+
+mod schreier_sims_api;
+pub use schreier_sims_api::{schreier_sims, SchreierSimsOptions, SchreierSimsResult};
+
+mod random_move_scramble_api;
+pub use random_move_scramble_api::{
+    derive_random_move_scramble, random_move_scramble, RandomMoveScrambleOptions,
+    MAX_NUM_FILTERED_CANDIDATES,
+};
+
+mod random_state_scramble_api;
+pub use random_state_scramble_api::{
+    derive_random_state_scramble, random_state_scramble, RandomStateScrambleOptions,
+};
+
 mod multi_phase_search;
 pub use multi_phase_search::{MultiPhaseSearch, MultiPhaseSearchOptions};
 
@@ -0,0 +1,382 @@
+use std::str::FromStr;
+
+use cubing::{
+    alg::{Alg, AlgNode},
+    kpuzzle::{KPattern, KPuzzle, OrientationWithMod},
+};
+use rand::{seq::IndexedRandom, Rng};
+
+use crate::{
+    _internal::{
+        canonical_fsm::{
+            canonical_fsm::{CanonicalFSM, CANONICAL_FSM_START_STATE},
+            move_class_mask::MoveClassIndex,
+            search_generators::{Generators, SearchGenerators, SearchGeneratorsConstructorOptions},
+        },
+        errors::{SearchError, TwipsError},
+        notation::metric::TurnMetric,
+        search::move_count::MoveCount,
+    },
+    scramble::{
+        puzzles::canonicalizing_solved_kpattern_depth_filter::{
+            CanonicalizingSolvedKPatternDepthFilter,
+            CanonicalizingSolvedKPatternDepthFilterConstructionParameters,
+        },
+        DerivationSalt, DerivationSeed, DerivationSeedRng,
+    },
+};
+
+#[derive(Debug, Default)]
+pub struct RandomMoveScrambleOptions {
+    pub generators: Generators,
+    pub metric: Option<TurnMetric>,
+    pub num_random_moves: usize,
+    /// If specified, scrambles that can be solved in fewer moves than this
+    /// (using the generators) are rejected. An error is returned if none of the
+    /// first [`MAX_NUM_FILTERED_CANDIDATES`] scrambles pass (e.g. if this is
+    /// larger than the diameter of the puzzle).
+    pub min_optimal_solution_move_count: Option<MoveCount>,
+}
+
+/// The number of candidate scrambles to try before giving up on finding one
+/// that passes the depth filter.
+pub const MAX_NUM_FILTERED_CANDIDATES: usize = 10_000;
+
+// Arbitrary definitions don't tell us which moves are rotations, so every piece is masked out and canonicalization is a no-op.
+fn fully_masked_pattern(kpuzzle: &KPuzzle) -> KPattern {
+    let mut mask = kpuzzle.default_pattern();
+    for orbit_info in kpuzzle.orbit_info_iter() {
+        for i in 0..orbit_info.num_pieces {
+            mask.set_piece(orbit_info, i, 0);
+            mask.set_orientation_with_mod(
+                orbit_info,
+                i,
+                &OrientationWithMod {
+                    orientation: 0,
+                    orientation_mod: 1,
+                },
+            );
+        }
+    }
+    mask
+}
+
+fn generate_unfiltered_random_move_scramble<R: Rng>(
+    generators: &SearchGenerators<KPuzzle>,
+    canonical_fsm: &CanonicalFSM<KPuzzle>,
+    num_random_moves: usize,
+    mut rng: R,
+) -> Result<Alg, SearchError> {
+    let mut current_fsm_state = CANONICAL_FSM_START_STATE;
+    let mut nodes = Vec::<AlgNode>::default();
+    for _ in 0..num_random_moves {
+        let valid_move_classes: Vec<_> = (0..generators.by_move_class.len())
+            .map(MoveClassIndex)
+            .filter_map(|move_class_index| {
+                canonical_fsm
+                    .next_state(current_fsm_state, move_class_index)
+                    .map(|next| (move_class_index, next))
+            })
+            .collect();
+        let Some((move_class_index, next)) = valid_move_classes.choose(&mut rng) else {
+            return Err(SearchError {
+                description: format!(
+                    "The generators cannot produce a scramble with {} moves without cancellations.",
+                    num_random_moves
+                ),
+            });
+        };
+        nodes.push(AlgNode::MoveNode(
+            generators.by_move_class[*move_class_index]
+                .choose(&mut rng)
+                .unwrap()
+                .r#move
+                .clone(),
+        ));
+        current_fsm_state = *next;
+    }
+    Ok(Alg { nodes })
+}
+
+/// Generates a random-move scramble for an arbitrary definition. Moves are
+/// chosen using the canonical FSM for the generators, so that scrambles never
+/// contain cancellations or redundant orderings of commuting moves.
+///
+/// This is much cheaper than [`crate::experimental_lib_api::derive_random_state_scramble`],
+/// but the resulting patterns are not uniformly random.
+///
+/// Usage example:
+///
+/// ```
+/// use cubing::puzzles::cube3x3x3_kpuzzle;
+/// use twips::{
+///     experimental_lib_api::{derive_random_move_scramble, RandomMoveScrambleOptions},
+///     scramble::DerivationSeed,
+/// };
+///
+/// let scramble = derive_random_move_scramble(
+///     cube3x3x3_kpuzzle(),
+///     RandomMoveScrambleOptions {
+///         num_random_moves: 25,
+///         ..Default::default()
+///     },
+///     DerivationSeed::from_thread_rng(),
+/// )
+/// .unwrap();
+/// assert_eq!(scramble.nodes.len(), 25);
+/// ```
+pub fn derive_random_move_scramble(
+    kpuzzle: &KPuzzle,
+    options: RandomMoveScrambleOptions,
+    derivation_seed: DerivationSeed,
+) -> Result<Alg, TwipsError> {
+    let kpuzzle_with_generator_moves = options.generators.kpuzzle_with_generator_moves(kpuzzle)?;
+    let search_kpuzzle = &kpuzzle_with_generator_moves.kpuzzle;
+    let generators = SearchGenerators::try_new(
+        search_kpuzzle,
+        kpuzzle_with_generator_moves.generator_moves.clone(),
+        SearchGeneratorsConstructorOptions {
+            metric: options.metric,
+            random_start: Some(false),
+        },
+    )?;
+    if let Some(min_optimal_solution_move_count) = options.min_optimal_solution_move_count {
+        // A scramble can always be solved by inverting it, so no candidate could pass the filter.
+        if min_optimal_solution_move_count.0 > options.num_random_moves {
+            return Err(SearchError {
+                description: format!(
+                    "The minimum optimal solution move count ({}) cannot exceed the number of random moves ({}).",
+                    min_optimal_solution_move_count.0, options.num_random_moves
+                ),
+            }
+            .into());
+        }
+    }
+    if generators.by_move_class.0.is_empty() && options.num_random_moves > 0 {
+        return Err(SearchError {
+            description: "At least one generator move is required.".to_owned(),
+        }
+        .into());
+    }
+    let canonical_fsm = CanonicalFSM::try_new(
+        search_kpuzzle.clone(),
+        generators.clone(),
+        Default::default(),
+    )
+    .map_err(|e| SearchError {
+        description: e.to_string(),
+    })?;
+    let mut depth_filter = match options.min_optimal_solution_move_count {
+        Some(min_optimal_solution_move_count) => {
+            Some(CanonicalizingSolvedKPatternDepthFilter::try_new(
+                CanonicalizingSolvedKPatternDepthFilterConstructionParameters {
+                    canonicalization_mask: fully_masked_pattern(search_kpuzzle),
+                    canonicalization_generator_moves: kpuzzle_with_generator_moves
+                        .generator_moves
+                        .clone(),
+                    max_canonicalizing_move_count_below: MoveCount(1),
+                    solved_pattern: search_kpuzzle.default_pattern(),
+                    depth_filtering_generator_moves: kpuzzle_with_generator_moves
+                        .generator_moves
+                        .clone(),
+                    min_optimal_solution_move_count,
+                },
+            )?)
+        }
+        None => None,
+    };
+
+    for i in 1..=MAX_NUM_FILTERED_CANDIDATES {
+        let salt = DerivationSalt::from_str(&format!("candidate{}", i)).unwrap();
+        let rng = DerivationSeedRng::new(derivation_seed.derive(&salt));
+        let scramble = generate_unfiltered_random_move_scramble(
+            &generators,
+            &canonical_fsm,
+            options.num_random_moves,
+            rng,
+        )?;
+        if let Some(depth_filter) = &mut depth_filter {
+            let pattern = search_kpuzzle
+                .default_pattern()
+                .apply_alg(&scramble)
+                .map_err(|e| SearchError {
+                    description: e.to_string(),
+                })?;
+            if depth_filter.depth_filter(&pattern)?.is_reject() {
+                continue;
+            }
+        }
+        return Ok(kpuzzle_with_generator_moves.expand_alg_generators(&scramble));
+    }
+    Err(SearchError {
+        description: format!(
+            "None of {} candidate scrambles passed the depth filter. The minimum optimal solution move count may be larger than the diameter of the puzzle.",
+            MAX_NUM_FILTERED_CANDIDATES
+        ),
+    }
+    .into())
+}
+
+/// Like [`derive_random_move_scramble`], using a derivation seed from the thread RNG.
+pub fn random_move_scramble(
+    kpuzzle: &KPuzzle,
+    options: RandomMoveScrambleOptions,
+) -> Result<Alg, TwipsError> {
+    let derivation_seed = DerivationSeed::from_thread_rng();
+    eprintln!("Derivation seed from thread RNG: {}", derivation_seed);
+    derive_random_move_scramble(kpuzzle, options, derivation_seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cubing::{
+        alg::{parse_move, AlgNode},
+        kpuzzle::{KPuzzle, KPuzzleDefinition},
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    };
+
+    use crate::{
+        _internal::{
+            canonical_fsm::search_generators::Generators,
+            search::{
+                iterative_deepening::individual_search::IndividualSearchOptions,
+                move_count::MoveCount, prune_table_trait::Depth,
+            },
+        },
+        experimental_lib_api::{
+            derive_random_move_scramble, search, RandomMoveScrambleOptions, SearchOptions,
+        },
+        scramble::{DerivationSalt, DerivationSeed},
+    };
+
+    #[test]
+    fn random_move_scramble_api_test() -> Result<(), String> {
+        let derivation_seed = DerivationSeed::from_str(
+            "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+        )?
+        .derive(&DerivationSalt::from_str("random-move")?);
+
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let options = || RandomMoveScrambleOptions {
+            num_random_moves: 30,
+            ..Default::default()
+        };
+        let scramble = derive_random_move_scramble(kpuzzle, options(), derivation_seed).unwrap();
+        assert_eq!(scramble.nodes.len(), 30);
+        // No move is followed by another move of the same family.
+        for pair in scramble.nodes.windows(2) {
+            let (AlgNode::MoveNode(move1), AlgNode::MoveNode(move2)) = (&pair[0], &pair[1]) else {
+                panic!("Unexpected alg node.");
+            };
+            assert_ne!(move1.quantum, move2.quantum);
+        }
+        // Reproducible from the same seed.
+        assert_eq!(
+            derive_random_move_scramble(kpuzzle, options(), derivation_seed)
+                .unwrap()
+                .to_string(),
+            scramble.to_string()
+        );
+
+        // Canonical move sequences can still have shorter solutions, which the
+        // depth filter must reject.
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let generators = || {
+            Generators::from(vec![
+                parse_move!("U").clone(),
+                parse_move!("F").clone(),
+                parse_move!("R").clone(),
+            ])
+        };
+        for i in 0..5 {
+            let scramble = derive_random_move_scramble(
+                kpuzzle,
+                RandomMoveScrambleOptions {
+                    generators: generators(),
+                    num_random_moves: 4,
+                    min_optimal_solution_move_count: Some(MoveCount(4)),
+                    ..Default::default()
+                },
+                derivation_seed.derive(&DerivationSalt::from_str(&format!("test{}", i))?),
+            )
+            .unwrap();
+            let pattern = kpuzzle.default_pattern().apply_alg(&scramble).unwrap();
+            assert!(search(
+                kpuzzle,
+                &pattern,
+                SearchOptions {
+                    generators: generators(),
+                    individual_search_options: IndividualSearchOptions {
+                        max_depth_exclusive: Some(Depth(4)),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .next()
+            .is_none());
+        }
+
+        // `R R` is not canonical, so `<R>` only has scrambles of length 1.
+        let r_options =
+            |num_random_moves, min_optimal_solution_move_count| RandomMoveScrambleOptions {
+                generators: Generators::from(vec![parse_move!("R").clone()]),
+                num_random_moves,
+                min_optimal_solution_move_count,
+                ..Default::default()
+            };
+        assert!(derive_random_move_scramble(kpuzzle, r_options(1, None), derivation_seed).is_ok());
+        assert!(derive_random_move_scramble(kpuzzle, r_options(3, None), derivation_seed).is_err());
+        assert!(derive_random_move_scramble(
+            kpuzzle,
+            r_options(1, Some(MoveCount(2))),
+            derivation_seed
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn min_optimal_solution_move_count_above_diameter() -> Result<(), String> {
+        // `A` and `B` generate the symmetric group on 3 pieces, which has a
+        // diameter of 3. Canonical sequences can be arbitrarily long, but no
+        // scramble needs 4 moves to solve.
+        let kpuzzle = KPuzzle::try_new(
+            serde_json::from_str::<KPuzzleDefinition>(
+                r#"{
+                    "name": "S3",
+                    "orbits": [{ "orbitName": "PIECES", "numPieces": 3, "numOrientations": 1 }],
+                    "defaultPattern": {
+                        "PIECES": { "pieces": [0, 1, 2], "orientation": [0, 0, 0] }
+                    },
+                    "moves": {
+                        "A": {
+                            "PIECES": { "permutation": [1, 0, 2], "orientationDelta": [0, 0, 0] }
+                        },
+                        "B": {
+                            "PIECES": { "permutation": [0, 2, 1], "orientationDelta": [0, 0, 0] }
+                        }
+                    }
+                }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let options =
+            |num_random_moves, min_optimal_solution_move_count| RandomMoveScrambleOptions {
+                num_random_moves,
+                min_optimal_solution_move_count: Some(MoveCount(min_optimal_solution_move_count)),
+                ..Default::default()
+            };
+        let derivation_seed = DerivationSeed::from_str(
+            "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+        )?;
+        // `A B A` (or `B A B`) is the only pattern at depth 3.
+        assert!(derive_random_move_scramble(&kpuzzle, options(3, 3), derivation_seed).is_ok());
+        assert!(derive_random_move_scramble(&kpuzzle, options(5, 4), derivation_seed).is_err());
+        Ok(())
+    }
+}
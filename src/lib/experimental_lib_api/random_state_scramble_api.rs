@@ -0,0 +1,241 @@
+use std::str::FromStr;
+
+use cubing::{
+    alg::Alg,
+    kpuzzle::{KPattern, KPuzzle},
+};
+
+use crate::{
+    _internal::{
+        canonical_fsm::search_generators::Generators,
+        errors::{SearchError, TwipsError},
+        notation::metric::TurnMetric,
+        schreier_sims::{reachability::ReachabilityCheck, schreier_sims::KPuzzleStabilizerChain},
+        search::search_logger::VerbosityLevel,
+    },
+    experimental_lib_api::{search, SearchOptions},
+    scramble::{DerivationSalt, DerivationSeed, DerivationSeedRng},
+};
+
+#[derive(Debug, Default)]
+pub struct RandomStateScrambleOptions {
+    pub generators: Generators,
+    pub metric: Option<TurnMetric>,
+    pub verbosity: Option<VerbosityLevel>,
+}
+
+/// Generates a random-state scramble for an arbitrary definition: a uniformly
+/// random pattern that is reachable from the default pattern (using the
+/// generators), followed by the inverse of an optimal solution for it.
+///
+/// The pattern is sampled from a stabilizer chain for the group generated by
+/// the generators, so any constraints between orbits (parity, orientation
+/// sums, fixed pieces, etc.) are respected automatically. Patterns are uniform
+/// even if the default pattern has identical pieces.
+///
+/// Note that the solution is found using a single optimal search, so this is
+/// only practical for puzzles that can be solved optimally in reasonable time.
+///
+/// Usage example:
+///
+/// ```no_run
+/// use cubing::puzzles::cube2x2x2_kpuzzle;
+/// use twips::{
+///     experimental_lib_api::derive_random_state_scramble,
+///     scramble::DerivationSeed,
+/// };
+///
+/// let derivation_seed = DerivationSeed::from_thread_rng();
+/// let scramble =
+///     derive_random_state_scramble(cube2x2x2_kpuzzle(), Default::default(), derivation_seed)
+///         .unwrap();
+/// println!("{}", scramble);
+/// ```
+pub fn derive_random_state_scramble(
+    kpuzzle: &KPuzzle,
+    options: RandomStateScrambleOptions,
+    derivation_seed: DerivationSeed,
+) -> Result<Alg, TwipsError> {
+    let pattern = derive_random_state_pattern(kpuzzle, &options.generators, derivation_seed)?;
+    let Some(solution) = search(
+        kpuzzle,
+        &pattern,
+        SearchOptions {
+            generators: options.generators,
+            metric: options.metric,
+            random_start: Some(false),
+            // The pattern is reachable by construction.
+            check_before_solve: ReachabilityCheck::Never,
+            verbosity: options.verbosity,
+            ..Default::default()
+        },
+    )?
+    .next() else {
+        return Err(SearchError {
+            description: "Could not find a solution for a random pattern.".to_owned(),
+        }
+        .into());
+    };
+    Ok(solution.invert())
+}
+
+/// Samples the (non-default) pattern that [`derive_random_state_scramble`] produces a scramble for.
+fn derive_random_state_pattern(
+    kpuzzle: &KPuzzle,
+    generators: &Generators,
+    derivation_seed: DerivationSeed,
+) -> Result<KPattern, TwipsError> {
+    let kpuzzle_with_generator_moves = generators.kpuzzle_with_generator_moves(kpuzzle)?;
+    // If no generator changes the default pattern, neither does any sequence of them (even if
+    // the generators permute identical pieces), and no candidate below would be accepted.
+    let default_pattern = kpuzzle_with_generator_moves.kpuzzle.default_pattern();
+    let mut changes_default_pattern = false;
+    for generator_move in &kpuzzle_with_generator_moves.generator_moves {
+        let pattern = default_pattern
+            .apply_move(generator_move)
+            .map_err(|e| SearchError {
+                description: e.to_string(),
+            })?;
+        if pattern != default_pattern {
+            changes_default_pattern = true;
+            break;
+        }
+    }
+    if !changes_default_pattern {
+        return Err(SearchError {
+            description: "The generators cannot reach any pattern other than the default pattern."
+                .to_owned(),
+        }
+        .into());
+    }
+
+    let stabilizer_chain = KPuzzleStabilizerChain::try_new(
+        &kpuzzle_with_generator_moves.kpuzzle,
+        &kpuzzle_with_generator_moves.generator_moves,
+    )?;
+
+    let mut i = 1;
+    loop {
+        let salt = DerivationSalt::from_str(&format!("candidate{}", i)).unwrap();
+        let rng = DerivationSeedRng::new(derivation_seed.derive(&salt));
+        let pattern =
+            default_pattern.apply_transformation(&stabilizer_chain.random_transformation(rng));
+        // Convert the pattern back for the original puzzle (in case there are alg generators).
+        let pattern =
+            KPattern::try_from_data(kpuzzle, &pattern.to_data()).map_err(|e| SearchError {
+                description: e.to_string(),
+            })?;
+        if pattern != kpuzzle.default_pattern() {
+            return Ok(pattern);
+        }
+        i += 1;
+    }
+}
+
+/// Like [`derive_random_state_scramble`], using a derivation seed from the thread RNG.
+pub fn random_state_scramble(
+    kpuzzle: &KPuzzle,
+    options: RandomStateScrambleOptions,
+) -> Result<Alg, TwipsError> {
+    let derivation_seed = DerivationSeed::from_thread_rng();
+    eprintln!("Derivation seed from thread RNG: {}", derivation_seed);
+    derive_random_state_scramble(kpuzzle, options, derivation_seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use cubing::{
+        alg::parse_move,
+        kpuzzle::{KPuzzle, KPuzzleDefinition},
+        puzzles::cube2x2x2_kpuzzle,
+    };
+
+    use crate::{
+        _internal::canonical_fsm::search_generators::Generators,
+        experimental_lib_api::{derive_random_state_scramble, RandomStateScrambleOptions},
+        scramble::{DerivationSalt, DerivationSeed},
+    };
+
+    use super::derive_random_state_pattern;
+
+    #[test]
+    fn random_state_scramble_api_test() -> Result<(), String> {
+        let derivation_seed = DerivationSeed::from_str(
+            "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+        )?
+        .derive(&DerivationSalt::from_str("random-state")?);
+
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let options = || RandomStateScrambleOptions {
+            generators: Generators::from(vec![
+                parse_move!("U").clone(),
+                parse_move!("F").clone(),
+                parse_move!("R").clone(),
+            ]),
+            ..Default::default()
+        };
+        let scramble = derive_random_state_scramble(kpuzzle, options(), derivation_seed).unwrap();
+        assert!(!scramble.nodes.is_empty());
+        assert_eq!(
+            kpuzzle.default_pattern().apply_alg(&scramble).unwrap(),
+            derive_random_state_pattern(kpuzzle, &options().generators, derivation_seed).unwrap()
+        );
+        // Reproducible from the same seed.
+        assert_eq!(
+            derive_random_state_scramble(kpuzzle, options(), derivation_seed)
+                .unwrap()
+                .to_string(),
+            scramble.to_string()
+        );
+
+        // `<R, U>` only reaches a subgroup, which keeps the `DLB` corner fixed.
+        let scramble = derive_random_state_scramble(
+            kpuzzle,
+            RandomStateScrambleOptions {
+                generators: Generators::from(vec![
+                    parse_move!("R").clone(),
+                    parse_move!("U").clone(),
+                ]),
+                ..Default::default()
+            },
+            derivation_seed,
+        )
+        .unwrap();
+        assert!(scramble.to_string().chars().all(|c| "RU2' ".contains(c)));
+        Ok(())
+    }
+
+    #[test]
+    fn identical_pieces_only() -> Result<(), String> {
+        // `R` only swaps two identical pieces, so it never changes the default pattern.
+        let kpuzzle = KPuzzle::try_new(
+            serde_json::from_str::<KPuzzleDefinition>(
+                r#"{
+                    "name": "identical pieces",
+                    "orbits": [{ "orbitName": "PIECES", "numPieces": 2, "numOrientations": 1 }],
+                    "defaultPattern": {
+                        "PIECES": { "pieces": [0, 0], "orientation": [0, 0] }
+                    },
+                    "moves": {
+                        "R": {
+                            "PIECES": { "permutation": [1, 0], "orientationDelta": [0, 0] }
+                        }
+                    }
+                }"#,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert!(derive_random_state_pattern(
+            &kpuzzle,
+            &Generators::Default,
+            DerivationSeed::from_str(
+                "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+            )?,
+        )
+        .is_err());
+        Ok(())
+    }
+}
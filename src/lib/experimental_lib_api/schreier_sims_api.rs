@@ -0,0 +1,112 @@
+use cubing::kpuzzle::{KPattern, KPuzzle};
+
+use crate::_internal::{
+    canonical_fsm::search_generators::Generators,
+    errors::TwipsError,
+    schreier_sims::{
+        group_order::GroupOrder,
+        schreier_sims::{
+            orbits_with_identical_pieces, KPuzzleStabilizerChain, OrbitWithIdenticalPieces,
+        },
+    },
+};
+
+#[derive(Default)]
+pub struct SchreierSimsOptions {
+    /// Used to detect identical pieces. Defaults to the default pattern of the definition.
+    pub start_pattern: Option<KPattern>,
+    pub generators: Generators,
+}
+
+pub struct SchreierSimsResult {
+    /// The number of elements in the group generated by the generators. This
+    /// is the same as the number of reachable patterns if and only if
+    /// `orbits_with_identical_pieces` is empty.
+    pub group_order: GroupOrder,
+    pub orbits_with_identical_pieces: Vec<OrbitWithIdenticalPieces>,
+}
+
+/// Usage example:
+///
+/// ```
+/// use cubing::puzzles::cube2x2x2_kpuzzle;
+/// use twips::experimental_lib_api::{schreier_sims, SchreierSimsOptions};
+///
+/// let result = schreier_sims(cube2x2x2_kpuzzle(), Default::default()).unwrap();
+/// assert_eq!(result.group_order.to_string(), "88179840");
+/// ```
+pub fn schreier_sims(
+    kpuzzle: &KPuzzle,
+    options: SchreierSimsOptions,
+) -> Result<SchreierSimsResult, TwipsError> {
+    let start_pattern = options
+        .start_pattern
+        .unwrap_or_else(|| kpuzzle.default_pattern());
+    let kpuzzle_with_generator_moves = options.generators.kpuzzle_with_generator_moves(kpuzzle)?;
+    let stabilizer_chain = KPuzzleStabilizerChain::try_new(
+        &kpuzzle_with_generator_moves.kpuzzle,
+        &kpuzzle_with_generator_moves.generator_moves,
+    )?;
+    Ok(SchreierSimsResult {
+        group_order: stabilizer_chain.order(),
+        orbits_with_identical_pieces: orbits_with_identical_pieces(&start_pattern),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cubing::{
+        alg::parse_move,
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
+    };
+
+    use crate::{
+        _internal::canonical_fsm::search_generators::Generators,
+        experimental_lib_api::{schreier_sims, SchreierSimsOptions},
+    };
+
+    #[test]
+    fn schreier_sims_api_test() {
+        let result = schreier_sims(
+            cube2x2x2_kpuzzle(),
+            SchreierSimsOptions {
+                generators: Generators::from(vec![
+                    parse_move!("U").clone(),
+                    parse_move!("F").clone(),
+                    parse_move!("R").clone(),
+                ]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(result.group_order.to_u128(), Some(3674160));
+        assert_eq!(
+            result.group_order.factorization().to_string(),
+            "2⁴ × 3⁸ × 5¹ × 7¹"
+        );
+        assert!(result.orbits_with_identical_pieces.is_empty());
+
+        let result = schreier_sims(
+            cube3x3x3_kpuzzle(),
+            SchreierSimsOptions {
+                generators: Generators::from(vec![
+                    parse_move!("U").clone(),
+                    parse_move!("L").clone(),
+                    parse_move!("F").clone(),
+                    parse_move!("R").clone(),
+                    parse_move!("B").clone(),
+                    parse_move!("D").clone(),
+                ]),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        // 43,252,003,274,489,856,000 patterns × 2,048 center orientations.
+        assert_eq!(result.group_order.to_string(), "88580102706155225088000");
+        assert_eq!(result.orbits_with_identical_pieces.len(), 1);
+        assert_eq!(
+            result.orbits_with_identical_pieces[0].orbit_name.0,
+            "CENTERS"
+        );
+    }
+}
@@ -2,34 +2,60 @@ use std::sync::Arc;
 
 use crate::_internal::{
     canonical_fsm::search_generators::{
-        Generators, SearchGenerators, SearchGeneratorsConstructorOptions,
+        Generators, KPuzzleWithGeneratorMoves, SearchGenerators, SearchGeneratorsConstructorOptions,
     },
-    errors::TwipsError,
+    errors::{ArgumentError, TwipsError},
     notation::metric::TurnMetric,
+    schreier_sims::reachability::{check_reachability, ReachabilityCheck},
     search::{
-        hash_prune_table::HashPruneTableSizeBounds,
+        hash_prune_table::{format_mebibytes, HashPruneTable, HashPruneTableSizeBounds},
         iterative_deepening::{
-            individual_search::IndividualSearchOptions,
+            individual_search::{IndividualSearchOptions, SearchTerminationReason},
             iterative_deepening_search::{
                 ImmutableSearchData, ImmutableSearchDataConstructionOptions,
-                IterativeDeepeningSearch, OwnedIterativeDeepeningSearchCursor,
+                IterativeDeepeningSearch, IterativeDeepeningSearchCursor,
+                OwnedIterativeDeepeningSearchCursor,
             },
             search_adaptations::StoredSearchAdaptations,
+            search_checkpoint::SearchCheckpoint,
         },
+        prune_table_persistence::{
+            kpuzzle_prune_table_fingerprint, PruneTablePersistence, PruneTablePersistenceOptions,
+        },
+        prune_table_trait::{Depth, PruneTable},
+        recursive_work_tracker::RecursiveWorkDepthStats,
         search_logger::{SearchLogger, VerbosityLevel},
     },
 };
-use cubing::kpuzzle::{KPattern, KPuzzle};
+use cubing::{
+    alg::Alg,
+    kpuzzle::{KPattern, KPuzzle},
+};
 
 #[derive(Debug, Default)]
 pub struct SearchOptions {
-    pub target_pattern: Option<KPattern>,
+    /// Solutions are found for reaching any of the target patterns. If
+    /// unspecified, the default pattern of the definition is used.
+    pub target_patterns: Option<Vec<KPattern>>,
     // TODO: make this optional, or move it out of `SearchOptions`.
     pub generators: Generators,
     pub metric: Option<TurnMetric>,
     pub random_start: Option<bool>,
+    /// Checks that the search pattern can reach a target pattern before
+    /// searching, since a search for an unreachable pattern never terminates.
+    pub check_before_solve: ReachabilityCheck,
     pub verbosity: Option<VerbosityLevel>,
     pub individual_search_options: IndividualSearchOptions,
+    /// If specified, the prune table is read from disk when possible (and written according to the options).
+    pub prune_table_persistence: Option<PruneTablePersistenceOptions>,
+    /// If specified, the prune table uses the largest power-of-two size that
+    /// fits in this budget. Otherwise, the prune table grows as needed.
+    pub memory_mebibytes: Option<usize>,
+    /// If specified, the prune table is filled to this depth before the search
+    /// starts (instead of being extended gradually as the search deepens). The
+    /// prune table then keeps its initial size, so this is best combined with
+    /// [`SearchOptions::memory_mebibytes`].
+    pub start_prune_depth: Option<Depth>,
 }
 
 impl From<&SearchOptions> for SearchGeneratorsConstructorOptions {
@@ -64,48 +90,344 @@ pub fn search(
     kpuzzle: &KPuzzle,
     search_pattern: &KPattern,
     options: SearchOptions,
-) -> Result<OwnedIterativeDeepeningSearchCursor, TwipsError> {
-    let generator_moves = options.generators.enumerate_moves_for_kpuzzle(kpuzzle);
-    let search_generators = SearchGenerators::try_new(kpuzzle, generator_moves, (&options).into())?;
-    let target_pattern = match options.target_pattern {
-        Some(target_pattern) => target_pattern,
-        None => kpuzzle.default_pattern(),
-    };
-    let iterative_deepening_search = <IterativeDeepeningSearch<KPuzzle>>::new_with_hash_prune_table(
-        ImmutableSearchData::try_from_common_options(
-            kpuzzle.clone(),
+) -> Result<SearchSolutions, TwipsError> {
+    let search_setup = SearchSetup::try_new(kpuzzle, &options)?;
+    // Check the pattern before constructing the prune table, which can be expensive.
+    let search_pattern = prepare_search_pattern(
+        &search_setup.kpuzzle_with_generator_moves,
+        &search_setup.target_patterns,
+        search_pattern,
+        options.check_before_solve,
+    )?;
+    let individual_search_options = options.individual_search_options.clone();
+    let (iterative_deepening_search, kpuzzle_with_generator_moves, _) =
+        search_setup.into_iterative_deepening_search(options)?;
+
+    let cursor = iterative_deepening_search.owned_search(
+        &search_pattern,
+        individual_search_options,
+        Default::default(),
+    );
+
+    Ok(SearchSolutions {
+        cursor,
+        kpuzzle_with_generator_moves: Arc::new(kpuzzle_with_generator_moves),
+    })
+}
+
+/// Everything needed to search that does not depend on the search pattern.
+struct SearchSetup {
+    kpuzzle_with_generator_moves: KPuzzleWithGeneratorMoves,
+    search_generators: SearchGenerators<KPuzzle>,
+    target_patterns: Vec<KPattern>,
+}
+
+impl SearchSetup {
+    fn try_new(kpuzzle: &KPuzzle, options: &SearchOptions) -> Result<Self, TwipsError> {
+        let kpuzzle_with_generator_moves =
+            options.generators.kpuzzle_with_generator_moves(kpuzzle)?;
+        let search_kpuzzle = &kpuzzle_with_generator_moves.kpuzzle;
+        let search_generators = SearchGenerators::try_new(
+            search_kpuzzle,
+            kpuzzle_with_generator_moves.generator_moves.clone(),
+            options.into(),
+        )?;
+        let target_patterns = match &options.target_patterns {
+            Some(target_patterns) => {
+                if target_patterns.is_empty() {
+                    return Err(ArgumentError {
+                        description: "At least one target pattern must be specified.".to_owned(),
+                    }
+                    .into());
+                }
+                target_patterns
+                    .iter()
+                    .map(|target_pattern| {
+                        kpuzzle_with_generator_moves.convert_pattern(target_pattern)
+                    })
+                    .collect::<Result<Vec<KPattern>, _>>()?
+            }
+            None => vec![search_kpuzzle.default_pattern()],
+        };
+        Ok(Self {
+            kpuzzle_with_generator_moves,
             search_generators,
-            vec![target_pattern], // TODO: support multiple target patterns in API
+            target_patterns,
+        })
+    }
+
+    fn into_iterative_deepening_search(
+        self,
+        options: SearchOptions,
+    ) -> Result<
+        (
+            IterativeDeepeningSearch<KPuzzle>,
+            KPuzzleWithGeneratorMoves,
+            Vec<KPattern>,
+        ),
+        TwipsError,
+    > {
+        let search_kpuzzle = &self.kpuzzle_with_generator_moves.kpuzzle;
+        let persistence =
+            options
+                .prune_table_persistence
+                .map(|persistence_options| PruneTablePersistence {
+                    options: persistence_options,
+                    fingerprint: kpuzzle_prune_table_fingerprint(
+                        search_kpuzzle,
+                        &self.search_generators,
+                        &self.target_patterns,
+                    ),
+                });
+        let immutable_search_data = Arc::new(ImmutableSearchData::try_from_common_options(
+            search_kpuzzle.clone(),
+            self.search_generators,
+            self.target_patterns.clone(),
             ImmutableSearchDataConstructionOptions {
                 search_logger: Arc::new(SearchLogger {
                     verbosity: options.verbosity.unwrap_or_default(),
                 }),
                 ..Default::default()
             },
-        )?,
-        StoredSearchAdaptations::default(),
-        HashPruneTableSizeBounds::default(),
-    );
+        )?);
+        let size_bounds = match options.memory_mebibytes {
+            Some(memory_mebibytes) => {
+                HashPruneTableSizeBounds::from_memory_budget_mebibytes(memory_mebibytes)
+            }
+            None => HashPruneTableSizeBounds::default(),
+        };
+        let mut prune_table = Box::new(HashPruneTable::new_with_persistence(
+            immutable_search_data.clone(),
+            StoredSearchAdaptations::default(),
+            size_bounds,
+            persistence,
+        ));
+        if let Some(start_prune_depth) = options.start_prune_depth {
+            // The prune table is filled to half the search depth.
+            prune_table.extend_for_search_depth(
+                Depth(*start_prune_depth * 2),
+                1,
+                options.individual_search_options.get_num_threads(),
+                &|| false,
+            );
+            // Otherwise, the search could grow the table and discard the filled depths.
+            prune_table.pin_size();
+        }
+        immutable_search_data.search_logger.write_info(&format!(
+            "[Prune table] Using {} of memory.",
+            format_mebibytes(prune_table.approximate_num_bytes())
+        ));
+        let iterative_deepening_search = <IterativeDeepeningSearch<KPuzzle>>::new(
+            immutable_search_data,
+            StoredSearchAdaptations::default(),
+            prune_table,
+        );
+        Ok((
+            iterative_deepening_search,
+            self.kpuzzle_with_generator_moves,
+            self.target_patterns,
+        ))
+    }
+}
 
-    let solutions = iterative_deepening_search.owned_search(
-        search_pattern,
-        options.individual_search_options,
-        Default::default(),
-    );
+fn prepare_search_pattern(
+    kpuzzle_with_generator_moves: &KPuzzleWithGeneratorMoves,
+    target_patterns: &[KPattern],
+    search_pattern: &KPattern,
+    check_before_solve: ReachabilityCheck,
+) -> Result<KPattern, TwipsError> {
+    let search_pattern = kpuzzle_with_generator_moves.convert_pattern(search_pattern)?;
+    check_reachability(
+        &kpuzzle_with_generator_moves.kpuzzle,
+        &kpuzzle_with_generator_moves.generator_moves,
+        &search_pattern,
+        target_patterns,
+        check_before_solve,
+    )?;
+    Ok(search_pattern)
+}
+
+/// Solves many search patterns (e.g. a file of scrambles) using a single
+/// search, so that the prune table is only constructed once and stays warm
+/// between patterns.
+///
+/// Usage example:
+///
+/// ```
+/// use cubing::{alg::parse_alg, puzzles::cube2x2x2_kpuzzle};
+/// use twips::experimental_lib_api::BatchSearch;
+///
+/// let kpuzzle = cube2x2x2_kpuzzle();
+/// let mut batch_search = BatchSearch::try_new(kpuzzle, Default::default()).unwrap();
+/// for scramble in [parse_alg!("R U R'"), parse_alg!("F2 U' R")] {
+///     let search_pattern = kpuzzle.default_pattern().apply_alg(scramble).unwrap();
+///     let solution = batch_search.search(&search_pattern).unwrap().next().unwrap();
+///     println!("{}", solution);
+/// }
+/// ```
+pub struct BatchSearch {
+    iterative_deepening_search: IterativeDeepeningSearch<KPuzzle>,
+    kpuzzle_with_generator_moves: Arc<KPuzzleWithGeneratorMoves>,
+    target_patterns: Vec<KPattern>,
+    check_before_solve: ReachabilityCheck,
+    individual_search_options: IndividualSearchOptions,
+}
+
+impl BatchSearch {
+    /// The options are used for every search pattern.
+    pub fn try_new(kpuzzle: &KPuzzle, options: SearchOptions) -> Result<Self, TwipsError> {
+        let check_before_solve = options.check_before_solve;
+        let individual_search_options = options.individual_search_options.clone();
+        let (iterative_deepening_search, kpuzzle_with_generator_moves, target_patterns) =
+            SearchSetup::try_new(kpuzzle, &options)?.into_iterative_deepening_search(options)?;
+        Ok(Self {
+            iterative_deepening_search,
+            kpuzzle_with_generator_moves: Arc::new(kpuzzle_with_generator_moves),
+            target_patterns,
+            check_before_solve,
+            individual_search_options,
+        })
+    }
+
+    /// Note that search is pull-based, like [`search`].
+    pub fn search(
+        &mut self,
+        search_pattern: &KPattern,
+    ) -> Result<BatchSearchSolutions<'_>, TwipsError> {
+        let search_pattern = prepare_search_pattern(
+            &self.kpuzzle_with_generator_moves,
+            &self.target_patterns,
+            search_pattern,
+            self.check_before_solve,
+        )?;
+        let cursor = self.iterative_deepening_search.search(
+            &search_pattern,
+            self.individual_search_options.clone(),
+            Default::default(),
+        );
+        Ok(BatchSearchSolutions {
+            cursor,
+            kpuzzle_with_generator_moves: self.kpuzzle_with_generator_moves.clone(),
+        })
+    }
+}
 
-    Ok(solutions)
+/// The cursor behind [`SearchSolutions`] or [`BatchSearchSolutions`].
+pub trait SearchSolutionsCursor: Iterator<Item = Alg> {
+    fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats];
+    fn termination_reason(&self) -> Option<SearchTerminationReason>;
+    fn prune_table(&self) -> &dyn PruneTable<KPuzzle>;
+    fn checkpoint(&self) -> &SearchCheckpoint;
+}
+
+impl SearchSolutionsCursor for OwnedIterativeDeepeningSearchCursor {
+    fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        self.recursive_work_depth_stats()
+    }
+
+    fn termination_reason(&self) -> Option<SearchTerminationReason> {
+        self.termination_reason()
+    }
+
+    fn prune_table(&self) -> &dyn PruneTable<KPuzzle> {
+        self.prune_table()
+    }
+
+    fn checkpoint(&self) -> &SearchCheckpoint {
+        self.checkpoint()
+    }
+}
+
+impl SearchSolutionsCursor for IterativeDeepeningSearchCursor<'_, KPuzzle> {
+    fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        self.recursive_work_depth_stats()
+    }
+
+    fn termination_reason(&self) -> Option<SearchTerminationReason> {
+        self.termination_reason()
+    }
+
+    fn prune_table(&self) -> &dyn PruneTable<KPuzzle> {
+        self.prune_table()
+    }
+
+    fn checkpoint(&self) -> &SearchCheckpoint {
+        self.checkpoint()
+    }
+}
+
+/// The solutions for a search. Any alg generators are expanded.
+pub struct ExpandedSearchSolutions<TCursor: SearchSolutionsCursor> {
+    cursor: TCursor,
+    kpuzzle_with_generator_moves: Arc<KPuzzleWithGeneratorMoves>,
+}
+
+/// The solutions for a call to [`search`].
+pub type SearchSolutions = ExpandedSearchSolutions<OwnedIterativeDeepeningSearchCursor>;
+
+/// The solutions for a call to [`BatchSearch::search`].
+pub type BatchSearchSolutions<'a> =
+    ExpandedSearchSolutions<IterativeDeepeningSearchCursor<'a, KPuzzle>>;
+
+impl<TCursor: SearchSolutionsCursor> ExpandedSearchSolutions<TCursor> {
+    /// Stats for every search depth that has been finished so far.
+    pub fn recursive_work_depth_stats(&self) -> &[RecursiveWorkDepthStats] {
+        self.cursor.recursive_work_depth_stats()
+    }
+
+    /// `None` if the search may still return more solutions.
+    pub fn termination_reason(&self) -> Option<SearchTerminationReason> {
+        self.cursor.termination_reason()
+    }
+
+    pub fn prune_table(&self) -> &dyn PruneTable<KPuzzle> {
+        self.cursor.prune_table()
+    }
+
+    /// See [`SearchCheckpoint::resume`].
+    pub fn checkpoint(&self) -> &SearchCheckpoint {
+        self.cursor.checkpoint()
+    }
+}
+
+impl<TCursor: SearchSolutionsCursor> Iterator for ExpandedSearchSolutions<TCursor> {
+    type Item = Alg;
+
+    fn next(&mut self) -> Option<Alg> {
+        let solution = self.cursor.next()?;
+        Some(
+            self.kpuzzle_with_generator_moves
+                .expand_alg_generators(&solution),
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
     use cubing::{
         alg::{parse_alg, parse_move},
-        puzzles::cube3x3x3_kpuzzle,
+        puzzles::{cube2x2x2_kpuzzle, cube3x3x3_kpuzzle},
     };
 
     use crate::{
-        _internal::canonical_fsm::search_generators::Generators,
-        experimental_lib_api::{search, search_api::SearchOptions},
+        _internal::{
+            canonical_fsm::search_generators::Generators,
+            search::{
+                iterative_deepening::{
+                    individual_search::{IndividualSearchOptions, SearchTerminationReason},
+                    iterative_deepening_search::ImmutableSearchData,
+                    search_checkpoint::{SearchCheckpoint, SearchCheckpointer},
+                    search_limits::SearchCancellationToken,
+                },
+                prune_table_trait::Depth,
+            },
+        },
+        experimental_lib_api::{search, search_api::SearchOptions, BatchSearch},
     };
 
     #[test]
@@ -133,4 +455,386 @@ mod tests {
         .unwrap();
         assert_eq!(solutions.next().unwrap().nodes.len(), 3);
     }
+
+    #[test]
+    fn alg_generators_search_api_test() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("(R U R' U')2 U' [R, U]"))
+            .expect("Invalid alg for puzzle.");
+        let solution = search(
+            kpuzzle,
+            &search_pattern,
+            SearchOptions {
+                generators: Generators::Custom {
+                    moves: vec![parse_move!("U").clone()],
+                    algs: vec![
+                        parse_alg!("R U R' U'").clone(),
+                        parse_alg!("[R, U]").clone(),
+                    ],
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+        assert_eq!(solution.to_string(), "(R U R' U')' U (R U R' U')2'");
+        assert_eq!(
+            search_pattern.apply_alg(&solution).unwrap(),
+            kpuzzle.default_pattern()
+        );
+    }
+
+    #[test]
+    fn batch_search_api_test() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let mut batch_search = BatchSearch::try_new(
+            kpuzzle,
+            SearchOptions {
+                generators: Generators::Custom {
+                    moves: vec![parse_move!("U").clone(), parse_move!("R").clone()],
+                    algs: vec![parse_alg!("F R").clone()],
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for scramble in [
+            parse_alg!("R U R'"),
+            parse_alg!("F R U2"),
+            parse_alg!("U' R2 U F R"),
+        ] {
+            let search_pattern = kpuzzle.default_pattern().apply_alg(scramble).unwrap();
+            let mut solutions = batch_search.search(&search_pattern).unwrap();
+            let solution = solutions.next().unwrap();
+            assert_eq!(
+                search_pattern.apply_alg(&solution).unwrap(),
+                kpuzzle.default_pattern()
+            );
+            assert_eq!(solutions.termination_reason(), None);
+        }
+
+        // Each pattern is checked before searching.
+        let unreachable_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("L"))
+            .unwrap();
+        assert!(batch_search.search(&unreachable_pattern).is_err());
+    }
+
+    #[test]
+    fn multiple_target_patterns_search_api_test() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U R'"))
+            .expect("Invalid alg for puzzle.");
+        let other_target_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U"))
+            .expect("Invalid alg for puzzle.");
+        let solution = search(
+            kpuzzle,
+            &search_pattern,
+            SearchOptions {
+                target_patterns: Some(vec![
+                    kpuzzle.default_pattern(),
+                    other_target_pattern.clone(),
+                ]),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+        assert_eq!(solution.to_string(), "R");
+        assert_eq!(
+            search_pattern.apply_alg(&solution).unwrap(),
+            other_target_pattern
+        );
+
+        assert!(search(
+            kpuzzle,
+            &search_pattern,
+            SearchOptions {
+                target_patterns: Some(vec![]),
+                ..Default::default()
+            },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn inconsistent_target_patterns_test() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let mut inconsistent_target_pattern = kpuzzle.default_pattern();
+        let orbit_info = &kpuzzle.data.ordered_orbit_info[0];
+        // Duplicates a piece, so that the orbit has a different multiset of pieces.
+        inconsistent_target_pattern.set_piece(orbit_info, 0, 1);
+        // The check happens during construction, so that it applies to all callers (not just the lib API).
+        assert!(
+            ImmutableSearchData::try_from_common_options_with_auto_search_generators(
+                kpuzzle.clone(),
+                Generators::Default.enumerate_moves_for_kpuzzle(kpuzzle),
+                vec![kpuzzle.default_pattern(), inconsistent_target_pattern],
+                Default::default(),
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn parallel_search_api_test() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U R' U' F"))
+            .expect("Invalid alg for puzzle.");
+        let solutions = |num_threads: usize| -> Vec<String> {
+            search(
+                kpuzzle,
+                &search_pattern,
+                SearchOptions {
+                    individual_search_options: IndividualSearchOptions {
+                        max_depth_exclusive: Some(Depth(7)),
+                        num_threads: Some(num_threads),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .map(|solution| solution.to_string())
+            .collect()
+        };
+        let single_threaded_solutions = solutions(1);
+        assert!(single_threaded_solutions.len() > 1);
+        assert_eq!(solutions(4), single_threaded_solutions);
+    }
+
+    #[test]
+    fn termination_reason_search_api_test() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U"))
+            .expect("Invalid alg for puzzle.");
+        let mut solutions = search(
+            kpuzzle,
+            &search_pattern,
+            SearchOptions {
+                individual_search_options: IndividualSearchOptions {
+                    min_num_solutions: Some(1),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(solutions.next().is_some());
+        assert_eq!(solutions.termination_reason(), None);
+        assert!(solutions.next().is_none());
+        assert_eq!(
+            solutions.termination_reason(),
+            Some(SearchTerminationReason::MinNumSolutionsReached)
+        );
+        assert_eq!(
+            solutions.recursive_work_depth_stats().last().unwrap().depth,
+            Depth(2)
+        );
+
+        let mut solutions = search(
+            kpuzzle,
+            &search_pattern,
+            SearchOptions {
+                individual_search_options: IndividualSearchOptions {
+                    max_depth_exclusive: Some(Depth(2)),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(solutions.next().is_none());
+        assert_eq!(
+            solutions.termination_reason(),
+            Some(SearchTerminationReason::MaxDepthReached)
+        );
+    }
+
+    #[test]
+    fn recursive_work_depth_stats_search_api_test() {
+        let kpuzzle = cube2x2x2_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U"))
+            .expect("Invalid alg for puzzle.");
+        let mut solutions = search(
+            kpuzzle,
+            &search_pattern,
+            SearchOptions {
+                individual_search_options: IndividualSearchOptions {
+                    min_num_solutions: Some(5),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        while let Some(solution) = solutions.next() {
+            assert_eq!(Depth(solution.nodes.len()), solutions.checkpoint().depth);
+        }
+        // Depths that return multiple solutions are only listed once.
+        let depths: Vec<Depth> = solutions
+            .recursive_work_depth_stats()
+            .iter()
+            .map(|stats| stats.depth)
+            .collect();
+        assert!(depths.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn search_limits_search_api_test() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U F D L B R2 U' F'"))
+            .expect("Invalid alg for puzzle.");
+        let termination_reason = |individual_search_options: IndividualSearchOptions| {
+            let mut solutions = search(
+                kpuzzle,
+                &search_pattern,
+                SearchOptions {
+                    individual_search_options,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            assert!(solutions.next().is_none());
+            solutions.termination_reason()
+        };
+
+        assert_eq!(
+            termination_reason(IndividualSearchOptions {
+                max_num_recursive_calls: Some(10_000),
+                ..Default::default()
+            }),
+            Some(SearchTerminationReason::NodeLimitReached)
+        );
+        assert_eq!(
+            termination_reason(IndividualSearchOptions {
+                timeout: Some(Duration::ZERO),
+                ..Default::default()
+            }),
+            Some(SearchTerminationReason::TimeLimitReached)
+        );
+        let cancellation_token = SearchCancellationToken::new();
+        cancellation_token.cancel();
+        assert_eq!(
+            termination_reason(IndividualSearchOptions {
+                cancellation_token: Some(cancellation_token),
+                num_threads: Some(2),
+                ..Default::default()
+            }),
+            Some(SearchTerminationReason::Cancelled)
+        );
+
+        let individual_search_options: IndividualSearchOptions =
+            serde_json::from_str(r#"{ "timeoutSeconds": 1.5, "maxNodes": 100 }"#).unwrap();
+        assert_eq!(
+            individual_search_options.timeout,
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(individual_search_options.max_num_recursive_calls, Some(100));
+    }
+
+    #[test]
+    fn search_checkpoint_search_api_test() {
+        let kpuzzle = cube3x3x3_kpuzzle();
+        let search_pattern = kpuzzle
+            .default_pattern()
+            .apply_alg(parse_alg!("R U'"))
+            .expect("Invalid alg for puzzle.");
+        let solutions = |individual_search_options: IndividualSearchOptions| {
+            let mut solutions = search(
+                kpuzzle,
+                &search_pattern,
+                SearchOptions {
+                    individual_search_options: IndividualSearchOptions {
+                        max_depth_exclusive: Some(Depth(4)),
+                        ..individual_search_options
+                    },
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let algs: Vec<String> = solutions.by_ref().map(|alg| alg.to_string()).collect();
+            (algs, solutions.checkpoint().clone())
+        };
+        let resume = |checkpoint: &SearchCheckpoint, num_threads: usize| {
+            // Checkpoints are meant to be saved, so we make sure they survive serialization.
+            let checkpoint: SearchCheckpoint =
+                serde_json::from_str(&serde_json::to_string(checkpoint).unwrap()).unwrap();
+            let mut individual_search_options = IndividualSearchOptions {
+                num_threads: Some(num_threads),
+                ..Default::default()
+            };
+            checkpoint.resume(&mut individual_search_options);
+            solutions(individual_search_options).0
+        };
+
+        let (all_solutions, _) = solutions(IndividualSearchOptions {
+            num_threads: Some(1),
+            ..Default::default()
+        });
+        assert!(all_solutions.len() > 1);
+
+        for num_threads in [1, 4] {
+            // Stop the search at various points, and check that resuming from
+            // there returns exactly the remaining solutions.
+            for max_num_recursive_calls in [1, 5_000, 50_000] {
+                let (solutions_before_stopping, checkpoint) = solutions(IndividualSearchOptions {
+                    num_threads: Some(num_threads),
+                    max_num_recursive_calls: Some(max_num_recursive_calls),
+                    ..Default::default()
+                });
+                let mut resumed_solutions = solutions_before_stopping;
+                resumed_solutions.extend(resume(&checkpoint, num_threads));
+                assert_eq!(resumed_solutions, all_solutions);
+            }
+
+            // Each periodic checkpoint must resume to the solutions that come
+            // after it.
+            let checkpoints = Arc::new(Mutex::new(vec![]));
+            let checkpoint_fn_checkpoints = checkpoints.clone();
+            let (solutions_with_checkpoints, _) = solutions(IndividualSearchOptions {
+                num_threads: Some(num_threads),
+                checkpointer: Some(SearchCheckpointer {
+                    interval: Duration::ZERO,
+                    checkpoint_fn: Arc::new(move |checkpoint| {
+                        checkpoint_fn_checkpoints
+                            .lock()
+                            .unwrap()
+                            .push(checkpoint.clone())
+                    }),
+                }),
+                ..Default::default()
+            });
+            assert_eq!(solutions_with_checkpoints, all_solutions);
+            let checkpoints = checkpoints.lock().unwrap();
+            assert!(!checkpoints.is_empty());
+            for checkpoint in checkpoints.iter().step_by(checkpoints.len().div_ceil(4)) {
+                let resumed_solutions = resume(checkpoint, num_threads);
+                assert!(all_solutions.ends_with(&resumed_solutions));
+            }
+        }
+
+        let (_, checkpoint) = solutions(IndividualSearchOptions {
+            min_num_solutions: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(resume(&checkpoint, 1), all_solutions[1..]);
+    }
 }
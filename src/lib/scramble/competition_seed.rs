@@ -0,0 +1,198 @@
+use std::str::FromStr;
+
+use rand::RngCore;
+
+use crate::{
+    _internal::errors::ArgumentError,
+    scramble::{DerivationSalt, DerivationSeed, DERIVATION_SEED_BYTE_LENGTH},
+};
+
+// See: `docs/ADRs/2025-11-02 — Scramble derivation/competitionSeedCommitment.ts`
+const COMMITMENT_SALT: &str = "commitment";
+
+// See: `docs/ADRs/2025-11-02 — Scramble derivation/obfuscateRootSeed.ts`
+pub const NUM_ROOT_SEED_OBFUSCATION_SHARDS: usize = 1024;
+
+fn check_root_level(root_derivation_seed: &DerivationSeed) -> Result<(), ArgumentError> {
+    if root_derivation_seed.level() != 0 {
+        return Err("Root derivation seed must be at level 0 (second byte must be `0x00`).".into());
+    }
+    Ok(())
+}
+
+/// The public commitment for a root seed, which is sent to the auditor before
+/// they send back the auditor salt.
+pub fn competition_seed_commitment(
+    root_derivation_seed: &DerivationSeed,
+) -> Result<DerivationSeed, ArgumentError> {
+    check_root_level(root_derivation_seed)?;
+    Ok(root_derivation_seed.derive(&DerivationSalt::from_str(COMMITMENT_SALT).unwrap()))
+}
+
+/// Checks a revealed root seed against a previously published commitment.
+pub fn verify_competition_seed_commitment(
+    root_derivation_seed: &DerivationSeed,
+    commitment: &DerivationSeed,
+) -> Result<bool, ArgumentError> {
+    Ok(competition_seed_commitment(root_derivation_seed)?.bytes() == commitment.bytes())
+}
+
+/// Spreads the root seed across [`NUM_ROOT_SEED_OBFUSCATION_SHARDS`] random
+/// shards (one hex line each) that XOR to the root seed. This makes it
+/// impractical to share the root seed except by copying the whole file.
+pub fn obfuscate_root_derivation_seed<R: RngCore>(
+    root_derivation_seed: &DerivationSeed,
+    mut rng: R,
+) -> Result<String, ArgumentError> {
+    check_root_level(root_derivation_seed)?;
+    let mut remainder = *root_derivation_seed.bytes();
+    let mut shards = Vec::<[u8; DERIVATION_SEED_BYTE_LENGTH]>::default();
+    for _ in 0..(NUM_ROOT_SEED_OBFUSCATION_SHARDS - 1) {
+        let mut shard = [0; DERIVATION_SEED_BYTE_LENGTH];
+        rng.fill_bytes(&mut shard);
+        xor_into(&mut remainder, &shard);
+        shards.push(shard);
+    }
+    shards.push(remainder);
+    Ok(shards
+        .iter()
+        .map(hex::encode)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+pub fn deobfuscate_root_derivation_seed(
+    obfuscated_root_derivation_seed: &str,
+) -> Result<DerivationSeed, ArgumentError> {
+    let mut root_derivation_seed_bytes = [0; DERIVATION_SEED_BYTE_LENGTH];
+    for (i, line) in obfuscated_root_derivation_seed.lines().enumerate() {
+        let shard: [u8; DERIVATION_SEED_BYTE_LENGTH] = hex::decode(line.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| ArgumentError {
+                description: format!("Invalid shard on line {}.", i + 1),
+            })?;
+        xor_into(&mut root_derivation_seed_bytes, &shard);
+    }
+    let root_derivation_seed = DerivationSeed::try_new(root_derivation_seed_bytes)?;
+    check_root_level(&root_derivation_seed)?;
+    Ok(root_derivation_seed)
+}
+
+fn xor_into(
+    accumulator: &mut [u8; DERIVATION_SEED_BYTE_LENGTH],
+    bytes: &[u8; DERIVATION_SEED_BYTE_LENGTH],
+) {
+    for (a, b) in accumulator.iter_mut().zip(bytes) {
+        *a ^= b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use rand::RngCore;
+    use rand_core::impls;
+    use sha2::{Digest, Sha256};
+
+    use crate::scramble::{
+        competition_seed::{
+            competition_seed_commitment, deobfuscate_root_derivation_seed,
+            obfuscate_root_derivation_seed, verify_competition_seed_commitment,
+        },
+        DerivationSalt, DerivationSeed,
+    };
+
+    // From the ADR.
+    const ROOT_DERIVATION_SEED: &str =
+        "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67";
+    const AUDITOR_SALT: &str = "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF";
+
+    // Test vectors calculated using the TypeScript reference implementations.
+    #[test]
+    fn commitment() -> Result<(), String> {
+        let root_derivation_seed = DerivationSeed::from_str(ROOT_DERIVATION_SEED)?;
+        let commitment =
+            competition_seed_commitment(&root_derivation_seed).map_err(|e| e.description)?;
+        assert_eq!(
+            commitment.to_string(),
+            "67016c7c5b0c6be46599bc3cdd9fc940554a4422f9c308c2852bf9ed8f2adc7f"
+        );
+        assert!(
+            verify_competition_seed_commitment(&root_derivation_seed, &commitment)
+                .map_err(|e| e.description)?
+        );
+        assert!(!verify_competition_seed_commitment(
+            &root_derivation_seed,
+            &root_derivation_seed.derive(&DerivationSalt::from_str(AUDITOR_SALT)?)
+        )
+        .map_err(|e| e.description)?);
+        assert_eq!(
+            root_derivation_seed
+                .derive(&DerivationSalt::from_str(AUDITOR_SALT)?)
+                .to_string(),
+            "6701881f76fec0b68067fadd787854e41c4c7407b8416ee26b4bacd14000900b"
+        );
+        Ok(())
+    }
+
+    /// Stands in for `crypto.getRandomValues(…)`: each shard is the SHA-256
+    /// hash of its index (as a decimal string). Requests of other sizes are
+    /// filled from consecutive hashes.
+    struct ShardIndexHashRng {
+        shard_index: usize,
+    }
+
+    impl RngCore for ShardIndexHashRng {
+        fn next_u32(&mut self) -> u32 {
+            impls::next_u32_via_fill(self)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            impls::next_u64_via_fill(self)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for chunk in dest.chunks_mut(32) {
+                let hash = Sha256::digest(self.shard_index.to_string());
+                chunk.copy_from_slice(&hash[..chunk.len()]);
+                self.shard_index += 1;
+            }
+        }
+    }
+
+    // Calculated using `toShards(…)` from `obfuscateRootSeed.ts` (under
+    // Node.js), with `randomUint8Array()` replaced by the same shard index
+    // hashes as `ShardIndexHashRng`.
+    #[test]
+    fn obfuscation() -> Result<(), String> {
+        let root_derivation_seed = DerivationSeed::from_str(ROOT_DERIVATION_SEED)?;
+        let obfuscated = obfuscate_root_derivation_seed(
+            &root_derivation_seed,
+            ShardIndexHashRng { shard_index: 0 },
+        )
+        .map_err(|e| e.description)?;
+        let lines: Vec<&str> = obfuscated.lines().collect();
+        assert_eq!(lines.len(), 1024);
+        assert_eq!(
+            lines[0],
+            "5feceb66ffc86f38d952786c6d696c79c2dbc239dd4e91b46729d73a27fb57e9"
+        );
+        assert_eq!(
+            lines[1023],
+            "be219a4d6bbd51eafd7304b4c76ed48de2915c058850e0aadeb486b8e8776b77"
+        );
+        assert_eq!(
+            hex::encode(Sha256::digest(&obfuscated)),
+            "9d55d2e6b4f2190cebedeb16b3c950ceaba28622b4afc229ceeda2979577534c"
+        );
+        assert_eq!(
+            deobfuscate_root_derivation_seed(&obfuscated)
+                .map_err(|e| e.description)?
+                .to_string(),
+            ROOT_DERIVATION_SEED
+        );
+        Ok(())
+    }
+}
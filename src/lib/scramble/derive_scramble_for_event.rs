@@ -9,7 +9,10 @@ use sha2::{Digest, Sha256};
 
 use crate::{
     _internal::errors::ArgumentError,
-    scramble::{random_scramble_for_event::derive_scramble_for_event, Event},
+    scramble::{
+        random_scramble_for_event::{derive_multi_blind_scramble_set, derive_scramble_for_event},
+        Event,
+    },
 };
 
 // A fixed (non-zero) value to distinguish a valid byte string from a random
@@ -65,10 +68,23 @@ impl DerivationSeed {
         DerivationSeed::try_new(bytes).unwrap()
     }
 
+    // Sets the level to `0x00`.
+    pub fn generate_root() -> Self {
+        let mut bytes: [u8; DERIVATION_SEED_BYTE_LENGTH] = [0; DERIVATION_SEED_BYTE_LENGTH];
+        rng().fill_bytes(&mut bytes);
+        bytes[0] = 0x67;
+        bytes[1] = 0x00;
+        DerivationSeed::try_new(bytes).unwrap()
+    }
+
     pub fn level(&self) -> u8 {
         self.0[1]
     }
 
+    pub fn bytes(&self) -> &[u8; DERIVATION_SEED_BYTE_LENGTH] {
+        &self.0
+    }
+
     pub fn derive_hierarchy<'a>(
         &self,
         derivation_salts: impl IntoIterator<Item = &'a DerivationSalt>,
@@ -194,6 +210,30 @@ pub fn derive_scramble_for_event_seeded(
     derive_scramble_for_event(subevent, derivation_seed).map_err(|e| e.description)
 }
 
+const ATTEMPT_DERIVATION_LEVEL: u8 = 6;
+/// The salt hierarchy must end at the attempt level (e.g. `…/scrambles/333mbf/r1/g1/a1`).
+pub fn derive_multi_blind_scramble_set_seeded(
+    derivation_seed: &DerivationSeed,
+    attempt_derivation_salt_hierarchy: &Vec<DerivationSalt>,
+    num_cubes: usize,
+) -> Result<Vec<Alg>, String> {
+    if attempt_derivation_salt_hierarchy.len() > 2
+        && attempt_derivation_salt_hierarchy[2].unhashed_salt() != Event::Cube3x3x3MultiBlind.id()
+    {
+        return Err("Mismatched event in third level of hierarchy".to_owned());
+    }
+    let derivation_seed = derivation_seed.derive_hierarchy(attempt_derivation_salt_hierarchy);
+
+    if derivation_seed.level() != ATTEMPT_DERIVATION_LEVEL {
+        return Err(format!(
+            "Expected derivation level {}, saw: {}",
+            ATTEMPT_DERIVATION_LEVEL,
+            derivation_seed.level()
+        ));
+    }
+    derive_multi_blind_scramble_set(derivation_seed, num_cubes).map_err(|e| e.description)
+}
+
 pub struct DerivationSeedRng {
     derivation_seed: DerivationSeed,
     index: u128,
@@ -231,3 +271,64 @@ impl RngCore for DerivationSeedRng {
         fill_bytes_via_next(self, dest);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::scramble::{
+        derive_multi_blind_scramble_set_seeded, derive_scramble_for_event_seeded, DerivationSalt,
+        DerivationSeed, Event,
+    };
+
+    fn derivation_salt_hierarchy(path: &str) -> Vec<DerivationSalt> {
+        path.split('/')
+            .map(|salt| DerivationSalt::from_str(salt).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn multi_blind_scramble_set_follows_salt_hierarchy() -> Result<(), String> {
+        // From the example in the scramble derivation ADR.
+        let root_derivation_seed = DerivationSeed::from_str(
+            "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+        )?;
+        let attempt_path = "EBNLEND@MABLNHJFHGFEKFIA@DNBKABHHNANA@FD@KKADJAKNFCIJNJGIFCBLEDF/scrambles/333mbf/r1/g1/a1";
+        let scrambles = derive_multi_blind_scramble_set_seeded(
+            &root_derivation_seed,
+            &derivation_salt_hierarchy(attempt_path),
+            2,
+        )?;
+
+        // Each cube is derived as a `333bf` subevent (level 7) with the
+        // subevent scramble salts `sub1`, `sub2`, … (level 8).
+        let expected = [
+            "U2 L2 D F2 U F2 U' L2 U L2 U L2 F2 R2 U2 F' D' F2 D2 F' D' F U Uw Fw'",
+            "U R2 U2 B2 D' F2 R2 D2 R2 D B2 D F2 L' F D B L B2 L D2 B' R Uw2 Rw'",
+        ];
+        assert_eq!(
+            scrambles
+                .iter()
+                .map(|scramble| scramble.to_string())
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert_eq!(
+            derive_scramble_for_event_seeded(
+                &root_derivation_seed,
+                &derivation_salt_hierarchy(&format!("{}/333bf/sub2", attempt_path)),
+                Event::Cube3x3x3Blindfolded,
+            )?
+            .to_string(),
+            expected[1]
+        );
+
+        assert!(derive_multi_blind_scramble_set_seeded(
+            &root_derivation_seed,
+            &derivation_salt_hierarchy(&format!("{}/333mbf/sub1", attempt_path)),
+            2,
+        )
+        .is_err());
+        Ok(())
+    }
+}
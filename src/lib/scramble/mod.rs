@@ -3,7 +3,7 @@ mod collapse;
 pub mod get_kpuzzle;
 pub mod orbit_pieces_byte_slice;
 mod parity;
-mod puzzles;
+pub(crate) mod puzzles;
 mod randomize;
 pub mod scramble_finder;
 mod scramble_search;
@@ -16,11 +16,19 @@ pub use event::{Event, EventError};
 
 mod random_scramble_for_event;
 pub use random_scramble_for_event::{
-    experimental_scramble_finder_filter_and_or_search, random_scramble_for_event,
-    solve_known_puzzle, ExperimentalFilterAndOrSearchOptions,
+    derive_multi_blind_scramble_set, experimental_scramble_finder_filter_and_or_search,
+    random_multi_blind_scramble_set, random_scramble_for_event, solve_known_puzzle,
+    ExperimentalFilterAndOrSearchOptions,
 };
 
 mod derive_scramble_for_event;
 pub use derive_scramble_for_event::{
-    derive_scramble_for_event_seeded, DerivationSalt, DerivationSeed, DERIVATION_SEED_BYTE_LENGTH,
+    derive_multi_blind_scramble_set_seeded, derive_scramble_for_event_seeded, DerivationSalt,
+    DerivationSeed, DerivationSeedRng, DERIVATION_SEED_BYTE_LENGTH,
+};
+
+mod competition_seed;
+pub use competition_seed::{
+    competition_seed_commitment, deobfuscate_root_derivation_seed, obfuscate_root_derivation_seed,
+    verify_competition_seed_commitment, NUM_ROOT_SEED_OBFUSCATION_SHARDS,
 };
@@ -5,7 +5,7 @@ use cubing::{
     kpuzzle::{KPattern, KPuzzle, OrientationWithMod},
 };
 use num_integer::Integer;
-use rand::{rng, seq::IndexedRandom, Rng};
+use rand::{seq::IndexedRandom, Rng};
 
 use crate::{
     _internal::{
@@ -200,13 +200,13 @@ impl<TBigCube: BigCube> ScrambleFinder for BigCubeScrambleFinder<TBigCube> {
 }
 
 impl<TBigCube: BigCube> RandomMoveScrambleFinder for BigCubeScrambleFinder<TBigCube> {
-    fn generate_unfiltered_random_move_scramble(
+    fn generate_unfiltered_random_move_scramble<R: Rng>(
         &mut self,
         scramble_options: &Self::ScrambleOptions,
+        mut rng: R,
     ) -> Alg {
         // TODO: globally cache generators and `canonical_fsm` for each puzzle.
         let mut current_fsm_state = CANONICAL_FSM_START_STATE;
-        let mut rng = rng();
         let mut nodes = Vec::<AlgNode>::default();
         for _ in 0..self.info.num_random_moves().0 {
             // TODO: we can forward-cache the valid move classes for each state instead of rejection sampling.
@@ -238,7 +238,11 @@ impl<TBigCube: BigCube> RandomMoveScrambleFinder for BigCubeScrambleFinder<TBigC
             }
             BigCubeScrambleFinderSuffixConstraints::ForNoInspection => {
                 if self.info.size.is_odd() {
-                    alg = add_random_suffixes_from(alg, &self.info.no_inspection_suffixes_from())
+                    alg = add_random_suffixes_from(
+                        alg,
+                        &self.info.no_inspection_suffixes_from(),
+                        &mut rng,
+                    )
                 }
             }
         }
@@ -303,9 +307,13 @@ mod tests {
             },
             definitions::{cube5x5x5_kpuzzle, cube6x6x6_kpuzzle, cube7x7x7_kpuzzle},
         },
-        scramble_finder::scramble_finder::ScrambleFinder,
+        scramble_finder::{
+            random_move_scramble_finder::RandomMoveScrambleFinder, scramble_finder::ScrambleFinder,
+        },
+        DerivationSeed,
     };
     use cubing::alg::{parse_alg, Alg};
+    use std::str::FromStr;
 
     use crate::_internal::search::move_count::MoveCount;
 
@@ -580,4 +588,30 @@ mod tests {
             .is_reject());
         Ok(())
     }
+
+    #[test]
+    fn seeded_scrambles_are_reproducible() -> Result<(), String> {
+        let scramble_options = BigCubeScrambleFinderScrambleOptions {
+            suffix_constraints: BigCubeScrambleFinderSuffixConstraints::ForNoInspection,
+        };
+        let derivation_seed = DerivationSeed::from_str(
+            "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67",
+        )?;
+        let scramble = Cube5x5x5ScrambleFinder::default()
+            .generate_filtered_random_move_scramble(&scramble_options, derivation_seed);
+        assert_eq!(
+            Cube5x5x5ScrambleFinder::default()
+                .generate_filtered_random_move_scramble(&scramble_options, derivation_seed),
+            scramble
+        );
+        let other_derivation_seed = DerivationSeed::from_str(
+            "67e0a5b1bc24a7c0f1b6d5c3f0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9",
+        )?;
+        assert_ne!(
+            Cube5x5x5ScrambleFinder::default()
+                .generate_filtered_random_move_scramble(&scramble_options, other_derivation_seed),
+            scramble
+        );
+        Ok(())
+    }
 }
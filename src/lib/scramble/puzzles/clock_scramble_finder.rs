@@ -2,7 +2,7 @@ use cubing::{
     alg::{parse_move, Alg, AlgNode, Move},
     kpuzzle::KPuzzle,
 };
-use rand::{rng, Rng};
+use rand::Rng;
 
 use crate::{
     _internal::search::move_count::MoveCount,
@@ -98,11 +98,11 @@ impl ScrambleFinder for ClockScrambleFinder {
 
 // TODO: This should probably be a `SolvingBasedScrambleFinder`?
 impl RandomMoveScrambleFinder for ClockScrambleFinder {
-    fn generate_unfiltered_random_move_scramble(
+    fn generate_unfiltered_random_move_scramble<R: Rng>(
         &mut self,
         _scramble_options: &NoScrambleOptions,
+        mut rng: R,
     ) -> Alg {
-        let mut rng = rng();
         let mut alg_nodes = Vec::<AlgNode>::new();
 
         // TODO: implement `parse_quantum_move!(…)`?
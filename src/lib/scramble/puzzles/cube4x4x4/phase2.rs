@@ -23,7 +23,6 @@ use crate::{
                 },
                 search_adaptations::{IndividualSearchAdaptations, StoredSearchAdaptations},
                 solution_moves::SolutionMoves,
-                target_pattern_signature::check_target_pattern_consistency_single_iter,
             },
             mask_pattern::apply_mask,
             search_logger::SearchLogger,
@@ -169,9 +168,6 @@ pub(crate) fn phase2_search(search_logger: Arc<SearchLogger>) -> Cube4x4x4Phase2
             .unwrap()
     });
 
-    check_target_pattern_consistency_single_iter::<KPuzzle>(&mut phase2_target_patterns.iter())
-        .unwrap();
-
     let phase2_iterative_deepening_search =
         IterativeDeepeningSearch::<Cube4x4x4Phase2Puzzle>::new_with_hash_prune_table(
             ImmutableSearchData::try_from_common_options_with_auto_search_generators(
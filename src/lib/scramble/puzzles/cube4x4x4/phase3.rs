@@ -22,6 +22,9 @@ use crate::{
                     ImmutableSearchData, ImmutableSearchDataConstructionOptions,
                     IterativeDeepeningSearch,
                 },
+                target_pattern_signature::{
+                    HasTargetPatternSignature, KPatternOrbitPieceCountSignature,
+                },
             },
             search_logger::{SearchLogger, VerbosityLevel},
         },
@@ -202,6 +205,14 @@ impl SemiGroupActionPuzzle for Cube4x4x4Phase3Puzzle {
     }
 }
 
+impl HasTargetPatternSignature for Cube4x4x4Phase3Puzzle {
+    type Signature = KPatternOrbitPieceCountSignature;
+
+    fn get_target_pattern_signature(pattern: &Self::Pattern) -> Self::Signature {
+        KPatternOrbitPieceCountSignature::from(pattern)
+    }
+}
+
 /*
 
 Wings and centers are indexed by Speffz ordering: https://www.speedsolving.com/wiki/index.php?title=Speffz
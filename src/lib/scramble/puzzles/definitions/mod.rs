@@ -91,3 +91,25 @@ kpattern_from_json_file!(pub(crate), kilominx_phase3_target, "./kilominx/kilomin
 
 kpuzzle_from_json_file!(pub(crate), baby_fto, "./other/baby_fto.kpuzzle.json");
 kpattern_from_json_file!(pub(crate), baby_fto_orientation_canonicalization, "./other/baby_fto.orientation-canonicalization-pattern.json", baby_fto_kpuzzle());
+
+/******************************** Master Tetraminx ********************************/
+
+kpuzzle_from_json_file!(pub(crate), master_tetraminx, "./master_tetraminx/master_tetraminx.kpuzzle.json");
+kpattern_from_json_file!(pub(crate), master_tetraminx_phase1_target, "./master_tetraminx/master_tetraminx-phase1.target-pattern.json", master_tetraminx_kpuzzle());
+kpattern_from_json_file!(pub(crate), master_tetraminx_phase2_target, "./master_tetraminx/master_tetraminx-phase2.target-pattern.json", master_tetraminx_kpuzzle());
+kpattern_from_json_file!(pub(crate), master_tetraminx_phase3_target, "./master_tetraminx/master_tetraminx-phase3.target-pattern.json", master_tetraminx_kpuzzle());
+kpattern_from_json_file!(pub(crate), master_tetraminx_phase4_target, "./master_tetraminx/master_tetraminx-phase4.target-pattern.json", master_tetraminx_kpuzzle());
+
+/******************************** Redi Cube ********************************/
+
+kpuzzle_from_json_file!(pub(crate), redi_cube, "./redi_cube/redi_cube.kpuzzle.json");
+kpattern_from_json_file!(pub(crate), redi_cube_phase1_target, "./redi_cube/redi_cube-phase1.target-pattern.json", redi_cube_kpuzzle());
+
+/******************************** FTO ********************************/
+
+kpuzzle_from_json_file!(pub(crate), fto, "./fto/fto.kpuzzle.json");
+kpattern_from_json_file!(pub(crate), fto_center_colors, "./fto/fto-center-colors.mask-pattern.json", fto_kpuzzle());
+kpattern_from_json_file!(pub(crate), fto_phase1_target, "./fto/fto-phase1.target-pattern.json", fto_kpuzzle());
+kpattern_from_json_file!(pub(crate), fto_phase2_target, "./fto/fto-phase2.target-pattern.json", fto_kpuzzle());
+kpattern_from_json_file!(pub(crate), fto_phase3_target, "./fto/fto-phase3.target-pattern.json", fto_kpuzzle());
+kpattern_from_json_file!(pub(crate), fto_phase4_target, "./fto/fto-phase4.target-pattern.json", fto_kpuzzle());
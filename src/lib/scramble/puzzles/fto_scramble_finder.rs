@@ -0,0 +1,265 @@
+use cubing::{
+    alg::Alg,
+    kpuzzle::{KPattern, KPuzzle},
+};
+use rand::Rng;
+
+use crate::{
+    _internal::{
+        errors::SearchError,
+        search::{
+            filter::filtering_decision::FilteringDecision,
+            hash_prune_table::HashPruneTableSizeBounds,
+            iterative_deepening::{
+                individual_search::IndividualSearchOptions,
+                iterative_deepening_search::{ImmutableSearchData, IterativeDeepeningSearch},
+                search_adaptations::StoredSearchAdaptations,
+            },
+            mask_pattern::apply_mask,
+            move_count::MoveCount,
+            prune_table_trait::Depth,
+        },
+    },
+    experimental_lib_api::{
+        KPuzzleSimpleMaskPhase, KPuzzleSimpleMaskPhaseConstructionOptions, MultiPhaseSearch,
+    },
+    scramble::{
+        collapse::collapse_adjacent_moves,
+        get_kpuzzle::GetKPuzzle,
+        randomize::OrbitRandomizationConstraints,
+        scramble_finder::{
+            scramble_finder::ScrambleFinder,
+            solving_based_scramble_finder::{NoScrambleOptions, SolvingBasedScrambleFinder},
+        },
+        scramble_search::move_list_from_vec,
+    },
+};
+
+use super::{
+    super::randomize::{randomize_orbit, OrbitOrientationConstraint, OrbitPermutationConstraint},
+    definitions::{
+        fto_center_colors_kpattern, fto_kpuzzle, fto_phase1_target_kpattern,
+        fto_phase2_target_kpattern, fto_phase3_target_kpattern, fto_phase4_target_kpattern,
+    },
+};
+
+const FTO_SCRAMBLE_FILTERING_MIN_MOVE_COUNT: MoveCount = MoveCount(6);
+
+fn fto_moves() -> Vec<&'static str> {
+    vec!["U", "F", "L", "R", "B", "D", "BL", "BR"]
+}
+
+pub(crate) struct FTOScrambleFinder {
+    kpuzzle: KPuzzle,
+    filtering_search: IterativeDeepeningSearch<KPuzzle>,
+    multi_phase_search: MultiPhaseSearch<KPuzzle>,
+}
+
+impl Default for FTOScrambleFinder {
+    fn default() -> Self {
+        let kpuzzle = fto_kpuzzle();
+
+        // Centers of the same color are indistinguishable, so we filter by center colors.
+        let filtering_search = <IterativeDeepeningSearch>::new_with_hash_prune_table(
+            ImmutableSearchData::try_from_common_options_with_auto_search_generators(
+                kpuzzle.clone(),
+                move_list_from_vec(fto_moves()),
+                vec![apply_mask(&kpuzzle.default_pattern(), fto_center_colors_kpattern()).unwrap()],
+                Default::default(),
+            )
+            .unwrap(),
+            StoredSearchAdaptations::default(),
+            HashPruneTableSizeBounds::default(),
+        );
+
+        // Each phase solves the pieces that the next phase's moves don't touch. Each
+        // move subgroup fixes exactly those pieces, so every phase can finish.
+        let construct_phase = |phase_name: &str,
+                               mask: &KPattern,
+                               move_list_vec: Vec<&str>|
+         -> Box<KPuzzleSimpleMaskPhase> {
+            Box::new(
+                KPuzzleSimpleMaskPhase::try_new(
+                    phase_name.to_owned(),
+                    mask.clone(),
+                    move_list_from_vec(move_list_vec),
+                    KPuzzleSimpleMaskPhaseConstructionOptions {
+                        ..Default::default()
+                    },
+                )
+                .unwrap(),
+            )
+        };
+        let multi_phase_search = MultiPhaseSearch::try_new(
+            kpuzzle.clone(),
+            vec![
+                construct_phase(
+                    "reduce to <U, F, R, L, B>",
+                    fto_phase1_target_kpattern(),
+                    fto_moves(),
+                ),
+                construct_phase(
+                    "reduce to <U, F, R, L>",
+                    fto_phase2_target_kpattern(),
+                    vec!["U", "F", "R", "L", "B"],
+                ),
+                construct_phase(
+                    "reduce to <U, F, R>",
+                    fto_phase3_target_kpattern(),
+                    vec!["U", "F", "R", "L"],
+                ),
+                construct_phase(
+                    "reduce to <U, R> and solve corners",
+                    fto_phase4_target_kpattern(),
+                    vec!["U", "F", "R"],
+                ),
+                construct_phase("solve <U, R>", &kpuzzle.default_pattern(), vec!["U", "R"]),
+            ],
+            Default::default(),
+        )
+        .unwrap();
+
+        Self {
+            kpuzzle: kpuzzle.clone(),
+            filtering_search,
+            multi_phase_search,
+        }
+    }
+}
+
+impl ScrambleFinder for FTOScrambleFinder {
+    type TPuzzle = KPuzzle;
+    type ScrambleOptions = NoScrambleOptions;
+
+    fn filter_pattern(
+        &mut self,
+        pattern: &KPattern,
+        _scramble_options: &Self::ScrambleOptions,
+    ) -> FilteringDecision {
+        let pattern = apply_mask(pattern, fto_center_colors_kpattern()).unwrap();
+        if self
+            .filtering_search
+            .search(
+                &pattern,
+                IndividualSearchOptions {
+                    max_depth_exclusive: Some(Depth(FTO_SCRAMBLE_FILTERING_MIN_MOVE_COUNT.0)),
+                    ..Default::default()
+                },
+                Default::default(),
+            )
+            .next()
+            .is_some()
+        {
+            FilteringDecision::Reject
+        } else {
+            FilteringDecision::Accept
+        }
+    }
+}
+
+impl SolvingBasedScrambleFinder for FTOScrambleFinder {
+    fn derive_fair_unfiltered_pattern<R: Rng>(
+        &mut self,
+        _scramble_options: &Self::ScrambleOptions,
+        mut rng: R,
+    ) -> KPattern {
+        let mut scramble_pattern = self.kpuzzle.default_pattern();
+        randomize_orbit(
+            &mut scramble_pattern,
+            0,
+            "C4RNER",
+            OrbitRandomizationConstraints {
+                permutation: Some(OrbitPermutationConstraint::EvenParity),
+                orientation: Some(OrbitOrientationConstraint::EvenOddHackSumToZero(vec![
+                    0, 2, 4,
+                ])),
+                ..Default::default()
+            },
+            &mut rng,
+        );
+        // Centers of the same color are indistinguishable, but using even
+        // permutations of distinguishable centers still picks each arrangement of
+        // colors with equal probability.
+        for subset in [
+            vec![0, 1, 2, 3, 4, 14, 15, 16, 19, 20, 22, 23],
+            vec![5, 6, 7, 8, 9, 10, 11, 12, 13, 17, 18, 21],
+        ] {
+            randomize_orbit(
+                &mut scramble_pattern,
+                1,
+                "CENTERS",
+                OrbitRandomizationConstraints {
+                    permutation: Some(OrbitPermutationConstraint::EvenParity),
+                    subset: Some(subset),
+                    ..Default::default()
+                },
+                &mut rng,
+            );
+        }
+        randomize_orbit(
+            &mut scramble_pattern,
+            2,
+            "EDGES",
+            OrbitRandomizationConstraints {
+                permutation: Some(OrbitPermutationConstraint::EvenParity),
+                ..Default::default()
+            },
+            &mut rng,
+        );
+        scramble_pattern
+    }
+
+    fn solve_pattern(
+        &mut self,
+        pattern: &KPattern,
+        _scramble_options: &Self::ScrambleOptions,
+    ) -> Result<Alg, SearchError> {
+        self.multi_phase_search
+            .chain_first_solution_for_each_phase(pattern)
+    }
+
+    fn collapse_inverted_alg(&mut self, alg: Alg) -> Alg {
+        collapse_adjacent_moves(alg, 3, -1)
+    }
+}
+
+impl GetKPuzzle for FTOScrambleFinder {
+    fn get_kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cubing::alg::parse_alg;
+
+    use crate::scramble::{
+        puzzles::{
+            definitions::fto_kpuzzle,
+            fto_scramble_finder::{fto_moves, FTOScrambleFinder},
+        },
+        scramble_finder::{
+            solving_based_scramble_finder::NoScrambleOptions,
+            test_helpers::{check_fair_patterns_are_reachable, check_solves_scramble},
+        },
+    };
+
+    #[test]
+    fn fair_patterns_are_reachable() -> Result<(), String> {
+        check_fair_patterns_are_reachable::<FTOScrambleFinder>(
+            fto_kpuzzle(),
+            fto_moves(),
+            &NoScrambleOptions {},
+            10,
+        )
+    }
+
+    #[test]
+    fn solve_short_scramble() -> Result<(), String> {
+        check_solves_scramble::<FTOScrambleFinder>(
+            fto_kpuzzle(),
+            parse_alg!("F BL' R U BR D'"),
+            &NoScrambleOptions {},
+        )
+    }
+}
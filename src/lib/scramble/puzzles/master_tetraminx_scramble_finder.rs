@@ -0,0 +1,246 @@
+use cubing::{
+    alg::Alg,
+    kpuzzle::{KPattern, KPuzzle},
+};
+use rand::Rng;
+
+use crate::{
+    _internal::{
+        errors::SearchError,
+        search::{
+            filter::filtering_decision::FilteringDecision,
+            hash_prune_table::HashPruneTableSizeBounds,
+            iterative_deepening::{
+                individual_search::IndividualSearchOptions,
+                iterative_deepening_search::{ImmutableSearchData, IterativeDeepeningSearch},
+                search_adaptations::StoredSearchAdaptations,
+            },
+            move_count::MoveCount,
+            prune_table_trait::Depth,
+        },
+    },
+    experimental_lib_api::{
+        KPuzzleSimpleMaskPhase, KPuzzleSimpleMaskPhaseConstructionOptions, MultiPhaseSearch,
+    },
+    scramble::{
+        collapse::collapse_adjacent_moves,
+        get_kpuzzle::GetKPuzzle,
+        randomize::OrbitRandomizationConstraints,
+        scramble_finder::{
+            scramble_finder::ScrambleFinder,
+            solving_based_scramble_finder::{NoScrambleOptions, SolvingBasedScrambleFinder},
+        },
+        scramble_search::move_list_from_vec,
+    },
+};
+
+use super::{
+    super::randomize::{randomize_orbit, OrbitOrientationConstraint, OrbitPermutationConstraint},
+    definitions::{
+        master_tetraminx_kpuzzle, master_tetraminx_phase1_target_kpattern,
+        master_tetraminx_phase2_target_kpattern, master_tetraminx_phase3_target_kpattern,
+        master_tetraminx_phase4_target_kpattern,
+    },
+};
+
+const MASTER_TETRAMINX_SCRAMBLE_FILTERING_MIN_MOVE_COUNT: MoveCount = MoveCount(6);
+
+fn master_tetraminx_moves() -> Vec<&'static str> {
+    vec!["U", "L", "R", "B", "Uw", "Lw", "Rw", "Bw"]
+}
+
+pub(crate) struct MasterTetraminxScrambleFinder {
+    kpuzzle: KPuzzle,
+    filtering_search: IterativeDeepeningSearch<KPuzzle>,
+    multi_phase_search: MultiPhaseSearch<KPuzzle>,
+}
+
+impl Default for MasterTetraminxScrambleFinder {
+    fn default() -> Self {
+        let kpuzzle = master_tetraminx_kpuzzle();
+
+        let filtering_search = <IterativeDeepeningSearch>::new_with_hash_prune_table(
+            ImmutableSearchData::try_from_common_options_with_auto_search_generators(
+                kpuzzle.clone(),
+                move_list_from_vec(master_tetraminx_moves()),
+                vec![kpuzzle.default_pattern()],
+                Default::default(),
+            )
+            .unwrap(),
+            StoredSearchAdaptations::default(),
+            HashPruneTableSizeBounds::default(),
+        );
+
+        let construct_phase = |phase_name: &str, mask: &KPattern| -> Box<KPuzzleSimpleMaskPhase> {
+            Box::new(
+                KPuzzleSimpleMaskPhase::try_new(
+                    phase_name.to_owned(),
+                    mask.clone(),
+                    move_list_from_vec(master_tetraminx_moves()),
+                    KPuzzleSimpleMaskPhaseConstructionOptions {
+                        ..Default::default()
+                    },
+                )
+                .unwrap(),
+            )
+        };
+        let multi_phase_search = MultiPhaseSearch::try_new(
+            kpuzzle.clone(),
+            vec![
+                construct_phase(
+                    "solve edges and centers",
+                    master_tetraminx_phase1_target_kpattern(),
+                ),
+                construct_phase("solve corners", master_tetraminx_phase2_target_kpattern()),
+                construct_phase(
+                    "solve the first 4 wings",
+                    master_tetraminx_phase3_target_kpattern(),
+                ),
+                construct_phase(
+                    "solve the next 4 wings",
+                    master_tetraminx_phase4_target_kpattern(),
+                ),
+                construct_phase("solve the last 4 wings", &kpuzzle.default_pattern()),
+            ],
+            Default::default(),
+        )
+        .unwrap();
+
+        Self {
+            kpuzzle: kpuzzle.clone(),
+            filtering_search,
+            multi_phase_search,
+        }
+    }
+}
+
+impl ScrambleFinder for MasterTetraminxScrambleFinder {
+    type TPuzzle = KPuzzle;
+    type ScrambleOptions = NoScrambleOptions;
+
+    fn filter_pattern(
+        &mut self,
+        pattern: &KPattern,
+        _scramble_options: &Self::ScrambleOptions,
+    ) -> FilteringDecision {
+        if self
+            .filtering_search
+            .search(
+                pattern,
+                IndividualSearchOptions {
+                    max_depth_exclusive: Some(Depth(
+                        MASTER_TETRAMINX_SCRAMBLE_FILTERING_MIN_MOVE_COUNT.0,
+                    )),
+                    ..Default::default()
+                },
+                Default::default(),
+            )
+            .next()
+            .is_some()
+        {
+            FilteringDecision::Reject
+        } else {
+            FilteringDecision::Accept
+        }
+    }
+}
+
+impl SolvingBasedScrambleFinder for MasterTetraminxScrambleFinder {
+    fn derive_fair_unfiltered_pattern<R: Rng>(
+        &mut self,
+        _scramble_options: &Self::ScrambleOptions,
+        mut rng: R,
+    ) -> KPattern {
+        let mut scramble_pattern = self.kpuzzle.default_pattern();
+        randomize_orbit(
+            &mut scramble_pattern,
+            0,
+            "CORNERS",
+            OrbitRandomizationConstraints {
+                permutation: Some(OrbitPermutationConstraint::IdentityPermutation),
+                ..Default::default()
+            },
+            &mut rng,
+        );
+        randomize_orbit(
+            &mut scramble_pattern,
+            1,
+            "EDGES",
+            OrbitRandomizationConstraints {
+                permutation: Some(OrbitPermutationConstraint::EvenParity),
+                orientation: Some(OrbitOrientationConstraint::SumToZero),
+                ..Default::default()
+            },
+            &mut rng,
+        );
+        for (orbit_idx, orbit_name) in [(2, "WINGS"), (3, "CENTERS")] {
+            randomize_orbit(
+                &mut scramble_pattern,
+                orbit_idx,
+                orbit_name,
+                OrbitRandomizationConstraints {
+                    permutation: Some(OrbitPermutationConstraint::EvenParity),
+                    ..Default::default()
+                },
+                &mut rng,
+            );
+        }
+        scramble_pattern
+    }
+
+    fn solve_pattern(
+        &mut self,
+        pattern: &KPattern,
+        _scramble_options: &Self::ScrambleOptions,
+    ) -> Result<Alg, SearchError> {
+        self.multi_phase_search
+            .chain_first_solution_for_each_phase(pattern)
+    }
+
+    fn collapse_inverted_alg(&mut self, alg: Alg) -> Alg {
+        collapse_adjacent_moves(alg, 3, -1)
+    }
+}
+
+impl GetKPuzzle for MasterTetraminxScrambleFinder {
+    fn get_kpuzzle(&self) -> &KPuzzle {
+        &self.kpuzzle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cubing::alg::parse_alg;
+
+    use crate::scramble::{
+        puzzles::{
+            definitions::master_tetraminx_kpuzzle,
+            master_tetraminx_scramble_finder::{
+                master_tetraminx_moves, MasterTetraminxScrambleFinder,
+            },
+        },
+        scramble_finder::{
+            solving_based_scramble_finder::NoScrambleOptions,
+            test_helpers::{check_fair_patterns_are_reachable, check_solves_scramble},
+        },
+    };
+
+    #[test]
+    fn fair_patterns_are_reachable() -> Result<(), String> {
+        check_fair_patterns_are_reachable::<MasterTetraminxScrambleFinder>(
+            master_tetraminx_kpuzzle(),
+            master_tetraminx_moves(),
+            &NoScrambleOptions {},
+            10,
+        )
+    }
+
+    #[test]
+    fn solve_short_scramble() -> Result<(), String> {
+        check_solves_scramble::<MasterTetraminxScrambleFinder>(
+            master_tetraminx_kpuzzle(),
+            parse_alg!("Uw R' Lw B Rw' U"),
+            &NoScrambleOptions {},
+        )
+    }
+}
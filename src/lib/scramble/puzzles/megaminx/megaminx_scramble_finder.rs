@@ -1,6 +1,6 @@
 use cubing::alg::{parse_move, Alg, AlgNode, Move, Newline};
 use cubing::kpuzzle::{KPattern, KPuzzle};
-use rand::{rng, Rng};
+use rand::Rng;
 
 use crate::_internal::search::move_count::MoveCount;
 use crate::scramble::puzzles::canonicalizing_solved_kpattern_depth_filter::{
@@ -70,11 +70,11 @@ impl ScrambleFinder for MegaminxScrambleFinder {
 }
 
 impl RandomMoveScrambleFinder for MegaminxScrambleFinder {
-    fn generate_unfiltered_random_move_scramble(
+    fn generate_unfiltered_random_move_scramble<R: Rng>(
         &mut self,
         _scramble_options: &Self::ScrambleOptions,
+        mut rng: R,
     ) -> Alg {
-        let mut rng = rng();
         let mut alg_nodes = Vec::<AlgNode>::new();
 
         let r_array: [&Move; 2] = [parse_move!("R++"), parse_move!("R--")];
@@ -9,7 +9,10 @@ pub mod square1;
 pub mod two_phase_3x3x3_scramble_finder;
 
 pub mod baby_fto;
+pub mod fto_scramble_finder;
 pub mod kilominx;
+pub mod master_tetraminx_scramble_finder;
+pub mod redi_cube_scramble_finder;
 
 pub mod canonicalizing_solved_kpattern_depth_filter;
 
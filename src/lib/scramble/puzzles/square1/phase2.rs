@@ -18,7 +18,10 @@ use crate::{
                 pattern_deriver::PatternDeriver,
             },
             filter::filtering_decision::FilteringDecision,
-            iterative_deepening::search_adaptations::StoredSearchAdaptations,
+            iterative_deepening::{
+                search_adaptations::StoredSearchAdaptations,
+                target_pattern_signature::HasTargetPatternSignature,
+            },
             mask_pattern::apply_mask,
             prune_table_trait::{Depth, PruneTable},
         },
@@ -603,6 +606,13 @@ impl SemiGroupActionPuzzle for Square1Phase2Puzzle {
     }
 }
 
+impl HasTargetPatternSignature for Square1Phase2Puzzle {
+    // Any combination of coordinates is a valid pattern, so there is nothing to compare.
+    type Signature = ();
+
+    fn get_target_pattern_signature(_pattern: &Self::Pattern) -> Self::Signature {}
+}
+
 #[derive(Clone)]
 pub struct Square1Phase2PruneTable {
     tpuzzle: Square1Phase2Puzzle,
@@ -630,7 +640,13 @@ impl PruneTable<Square1Phase2Puzzle> for Square1Phase2PruneTable {
         max(shape_depth, max(edges_depth, corners_depth))
     }
 
-    fn extend_for_search_depth(&mut self, _search_depth: Depth, _approximate_num_entries: usize) {
+    fn extend_for_search_depth(
+        &mut self,
+        _search_depth: Depth,
+        _approximate_num_entries: usize,
+        _num_threads: usize,
+        _should_stop: &(dyn Fn() -> bool + Sync),
+    ) {
         // no-op
     }
 }
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use cubing::alg::{Alg, Move};
-use rand::{rng, seq::IndexedRandom};
+use rand::{seq::IndexedRandom, Rng};
 
 // Hardcoded to 2 because we only need this for BLD right now.
 const NUM_RANDOM_SUFFIX_CHOICES: usize = 2;
@@ -21,11 +21,11 @@ where
         .collect()
 }
 
-pub(crate) fn add_random_suffixes_from(
+pub(crate) fn add_random_suffixes_from<R: Rng>(
     alg: Alg,
     suffixes_from: &[Vec<Option<Move>>; NUM_RANDOM_SUFFIX_CHOICES],
+    mut rng: R,
 ) -> Alg {
-    let mut rng = rng();
     let mut nodes = alg.nodes;
     for suffix_from in suffixes_from {
         if let Some(Some(r#move)) = suffix_from.choose(&mut rng) {
@@ -138,7 +138,7 @@ impl SolvingBasedScrambleFinder for TwoPhase3x3x3ScrambleFinder {
             TwoPhase3x3x3PrefixOrSuffixConstraints::ForBLD => {
                 // TODO: randomize centers directly?
                 // (Could use a God's algorithm table from the orientation filter)
-                let suffix = random_suffix_for_bld();
+                let suffix = random_suffix_for_bld(&mut rng);
                 scramble_pattern.apply_alg(&suffix).unwrap()
             }
         }
@@ -357,10 +357,10 @@ impl GetKPuzzle for TwoPhase3x3x3ScrambleFinder {
     }
 }
 
-fn random_suffix_for_bld() -> Alg {
+fn random_suffix_for_bld<R: Rng>(rng: R) -> Alg {
     let s1 = static_parsed_opt_list(&["", "Rw", "Rw2", "Rw'", "Fw", "Fw'"]);
     let s2 = static_parsed_opt_list(&["", "Uw", "Uw2", "Uw'"]);
-    add_random_suffixes_from(Alg::default(), &[s1, s2])
+    add_random_suffixes_from(Alg::default(), &[s1, s2], rng)
 }
 
 pub(crate) enum TwoPhase3x3x3PrefixOrSuffixConstraints {
@@ -1,10 +1,12 @@
+use std::str::FromStr;
+
 use cubing::{alg::Alg, kpuzzle::KPuzzle};
 
 use crate::{
     _internal::errors::{ArgumentError, SearchError, TwipsError},
     scramble::{
         puzzles::{baby_fto::BabyFTOScrambleFinder, megaminx::megaminx_solver::MegaminxSolver},
-        DerivationSeed, Puzzle,
+        DerivationSalt, DerivationSeed, Puzzle,
     },
 };
 
@@ -18,9 +20,12 @@ use super::{
         clock_scramble_finder::ClockScrambleFinder,
         cube2x2x2_scramble_finder::Cube2x2x2ScrambleFinder,
         cube4x4x4::cube4x4x4_scramble_finder::Cube4x4x4ScrambleFinder,
+        fto_scramble_finder::FTOScrambleFinder,
         kilominx::kilominx_scramble_finder::KilominxScrambleFinder,
+        master_tetraminx_scramble_finder::MasterTetraminxScrambleFinder,
         megaminx::megaminx_scramble_finder::MegaminxScrambleFinder,
         pyraminx_scramble_finder::PyraminxScrambleFinder,
+        redi_cube_scramble_finder::RediCubeScrambleFinder,
         skewb_scramble_finder::SkewbScrambleFinder,
         square1::square1_scramble_finder::Square1ScrambleFinder,
         two_phase_3x3x3_scramble_finder::{
@@ -47,16 +52,43 @@ pub fn random_scramble_for_event(event: Event) -> Result<Alg, PuzzleError> {
     derive_scramble_for_event(event, derivation_seed)
 }
 
+pub fn random_multi_blind_scramble_set(num_cubes: usize) -> Result<Vec<Alg>, PuzzleError> {
+    let derivation_seed = DerivationSeed::from_thread_rng();
+    eprintln!("Derivation seed from thread RNG: {}", derivation_seed);
+    derive_multi_blind_scramble_set(derivation_seed, num_cubes)
+}
+
+/// Derives the scrambles for a single multi-blind attempt from the attempt seed
+/// (level 6 of the salt hierarchy). Each cube is a `333bf` subevent with the
+/// subevent scramble salt `sub1`, `sub2`, etc., so that any cube can also be
+/// derived individually.
+pub fn derive_multi_blind_scramble_set(
+    attempt_derivation_seed: DerivationSeed,
+    num_cubes: usize,
+) -> Result<Vec<Alg>, PuzzleError> {
+    if num_cubes == 0 {
+        return Err(PuzzleError {
+            description: "A multi-blind attempt must have at least one cube.".to_owned(),
+        });
+    }
+    (1..=num_cubes)
+        .map(|i| {
+            let subevent = Event::Cube3x3x3Blindfolded;
+            let derivation_seed = attempt_derivation_seed
+                .derive_hierarchy(&[
+                    subevent.into(),
+                    DerivationSalt::from_str(&format!("sub{}", i)).unwrap(),
+                ])
+                .derive(&subevent.into());
+            derive_scramble_for_event(subevent, derivation_seed)
+        })
+        .collect()
+}
+
 pub fn derive_scramble_for_event(
     subevent: Event,
     derivation_seed: DerivationSeed,
 ) -> Result<Alg, PuzzleError> {
-    let err = Err(PuzzleError {
-        description: format!(
-            "Scrambles are not implement for this event yet: {}",
-            subevent
-        ),
-    });
     match subevent {
         Event::Cube3x3x3Speedsolving => Ok(generate_fair_scramble::<TwoPhase3x3x3ScrambleFinder>(
             &TwoPhase3x3x3ScrambleOptions {
@@ -74,19 +106,28 @@ pub fn derive_scramble_for_event(
         )),
         Event::Cube5x5x5Speedsolving => Ok(generate_filtered_random_move_scramble::<
             Cube5x5x5ScrambleFinder,
-        >(&BigCubeScrambleFinderScrambleOptions {
-            suffix_constraints: BigCubeScrambleFinderSuffixConstraints::None,
-        })),
+        >(
+            &BigCubeScrambleFinderScrambleOptions {
+                suffix_constraints: BigCubeScrambleFinderSuffixConstraints::None,
+            },
+            derivation_seed,
+        )),
         Event::Cube6x6x6Speedsolving => Ok(generate_filtered_random_move_scramble::<
             Cube6x6x6ScrambleFinder,
-        >(&BigCubeScrambleFinderScrambleOptions {
-            suffix_constraints: BigCubeScrambleFinderSuffixConstraints::None,
-        })),
+        >(
+            &BigCubeScrambleFinderScrambleOptions {
+                suffix_constraints: BigCubeScrambleFinderSuffixConstraints::None,
+            },
+            derivation_seed,
+        )),
         Event::Cube7x7x7Speedsolving => Ok(generate_filtered_random_move_scramble::<
             Cube7x7x7ScrambleFinder,
-        >(&BigCubeScrambleFinderScrambleOptions {
-            suffix_constraints: BigCubeScrambleFinderSuffixConstraints::None,
-        })),
+        >(
+            &BigCubeScrambleFinderScrambleOptions {
+                suffix_constraints: BigCubeScrambleFinderSuffixConstraints::None,
+            },
+            derivation_seed,
+        )),
         Event::Cube3x3x3Blindfolded => Ok(generate_fair_scramble::<TwoPhase3x3x3ScrambleFinder>(
             &TwoPhase3x3x3ScrambleOptions {
                 prefix_or_suffix_constraints: TwoPhase3x3x3PrefixOrSuffixConstraints::ForBLD,
@@ -106,11 +147,14 @@ pub fn derive_scramble_for_event(
             derivation_seed,
         )),
         Event::ClockSpeedsolving => Ok(
-            generate_filtered_random_move_scramble::<ClockScrambleFinder>(&NoScrambleOptions {}),
+            generate_filtered_random_move_scramble::<ClockScrambleFinder>(
+                &NoScrambleOptions {},
+                derivation_seed,
+            ),
         ),
         Event::MegaminxSpeedsolving => Ok(generate_filtered_random_move_scramble::<
             MegaminxScrambleFinder,
-        >(&NoScrambleOptions {})),
+        >(&NoScrambleOptions {}, derivation_seed)),
         Event::PyraminxSpeedsolving => Ok(generate_fair_scramble::<PyraminxScrambleFinder>(
             &NoScrambleOptions {},
             derivation_seed,
@@ -129,22 +173,33 @@ pub fn derive_scramble_for_event(
         )),
         Event::Cube5x5x5Blindfolded => Ok(generate_filtered_random_move_scramble::<
             Cube5x5x5ScrambleFinder,
-        >(&BigCubeScrambleFinderScrambleOptions {
-            suffix_constraints: BigCubeScrambleFinderSuffixConstraints::ForNoInspection,
-        })),
+        >(
+            &BigCubeScrambleFinderScrambleOptions {
+                suffix_constraints: BigCubeScrambleFinderSuffixConstraints::ForNoInspection,
+            },
+            derivation_seed,
+        )),
         Event::Cube3x3x3MultiBlind => Ok(generate_fair_scramble::<TwoPhase3x3x3ScrambleFinder>(
             &TwoPhase3x3x3ScrambleOptions {
                 prefix_or_suffix_constraints: TwoPhase3x3x3PrefixOrSuffixConstraints::ForBLD,
             },
             derivation_seed,
-        )), // TODO: represent multiple returned scrambles without affecting ergonomics for other events.
-        Event::FTOSpeedsolving => err,
-        Event::MasterTetraminxSpeedsolving => err,
+        )), // This is a single cube. Use `derive_multi_blind_scramble_set(…)` for a full attempt.
+        Event::FTOSpeedsolving => Ok(generate_fair_scramble::<FTOScrambleFinder>(
+            &NoScrambleOptions {},
+            derivation_seed,
+        )),
+        Event::MasterTetraminxSpeedsolving => Ok(generate_fair_scramble::<
+            MasterTetraminxScrambleFinder,
+        >(&NoScrambleOptions {}, derivation_seed)),
         Event::KilominxSpeedsolving => Ok(generate_fair_scramble::<KilominxScrambleFinder>(
             &Default::default(),
             derivation_seed,
         )),
-        Event::RediCubeSpeedsolving => err,
+        Event::RediCubeSpeedsolving => Ok(generate_fair_scramble::<RediCubeScrambleFinder>(
+            &NoScrambleOptions {},
+            derivation_seed,
+        )),
         Event::BabyFTOSpeedsolving => Ok(generate_fair_scramble::<BabyFTOScrambleFinder>(
             &Default::default(),
             derivation_seed,
@@ -176,7 +231,7 @@ fn solving_based_filter_and_search<
                         description: "Rejected due to filtering".to_owned(),
                     });
                 }
-                eprintln!("Filtering decision: accepted")
+                eprint!("Filtering decision: accepted")
             };
 
             Ok(if options.perform_search {
@@ -363,10 +418,18 @@ pub fn experimental_scramble_finder_filter_and_or_search(
         Event::BabyFTOSpeedsolving => solving_based_filter_and_search_with_no_scramble_options::<
             BabyFTOScrambleFinder,
         >(options, false),
-        Event::Cube3x3x3MultiBlind
-        | Event::FTOSpeedsolving
-        | Event::MasterTetraminxSpeedsolving
-        | Event::RediCubeSpeedsolving => Err(PuzzleError {
+        Event::MasterTetraminxSpeedsolving => {
+            solving_based_filter_and_search_with_no_scramble_options::<MasterTetraminxScrambleFinder>(
+                options, false,
+            )
+        }
+        Event::FTOSpeedsolving => solving_based_filter_and_search_with_no_scramble_options::<
+            FTOScrambleFinder,
+        >(options, false),
+        Event::RediCubeSpeedsolving => solving_based_filter_and_search_with_no_scramble_options::<
+            RediCubeScrambleFinder,
+        >(options, false),
+        Event::Cube3x3x3MultiBlind => Err(PuzzleError {
             description: format!(
                 "Scramble finder testing is not implemented for this event yet: {}",
                 event
@@ -3,6 +3,9 @@ pub mod scramble_finder;
 pub mod solving_based_scramble_finder;
 
 pub mod random_move_scramble_finder;
+
+#[cfg(test)]
+pub(crate) mod test_helpers;
 // pub use solving_based_scramble_finder::free_memory_for_all_scramble_finders;
 
 use random_move_scramble_finder::free_memory_for_all_random_move_scramble_finders;
@@ -1,19 +1,27 @@
-use std::sync::{LazyLock, Mutex, RwLock};
+use std::{
+    str::FromStr,
+    sync::{LazyLock, Mutex, RwLock},
+};
 
 use cubing::{alg::Alg, kpuzzle::KPuzzle};
 use erased_set::ErasedSyncSet;
+use rand::Rng;
 
 use crate::{
     _internal::puzzle_traits::puzzle_traits::HasDefaultPattern,
-    scramble::{apply_flat_alg::apply_flat_alg, get_kpuzzle::GetKPuzzle},
+    scramble::{
+        apply_flat_alg::apply_flat_alg, derive_scramble_for_event::DerivationSeedRng,
+        get_kpuzzle::GetKPuzzle, DerivationSalt, DerivationSeed,
+    },
 };
 
 use super::scramble_finder::ScrambleFinder;
 
 pub trait RandomMoveScrambleFinder: ScrambleFinder {
-    fn generate_unfiltered_random_move_scramble(
+    fn generate_unfiltered_random_move_scramble<R: Rng>(
         &mut self,
         scramble_options: &Self::ScrambleOptions,
+        rng: R,
     ) -> Alg;
 
     fn puzzle(&self) -> &Self::TPuzzle;
@@ -21,13 +29,22 @@ pub trait RandomMoveScrambleFinder: ScrambleFinder {
     fn generate_filtered_random_move_scramble(
         &mut self,
         scramble_options: &Self::ScrambleOptions,
+        derivation_seed: DerivationSeed,
     ) -> Alg {
+        // Each candidate gets its own salt (matching `SolvingBasedScrambleFinder::generate_fair_scramble`), so that the result is reproducible from the derivation seed.
+        let mut i = 1;
         loop {
-            let scramble_alg = self.generate_unfiltered_random_move_scramble(scramble_options);
+            let salt = format!("candidate{}", i);
+            let mut rng = DerivationSeedRng::new(
+                derivation_seed.derive(&DerivationSalt::from_str(&salt).unwrap()),
+            );
+            let scramble_alg =
+                self.generate_unfiltered_random_move_scramble(scramble_options, &mut rng);
             let puzzle = self.puzzle();
             let pattern =
                 apply_flat_alg(puzzle, &puzzle.puzzle_default_pattern(), &scramble_alg).unwrap();
             if self.filter_pattern(&pattern, scramble_options).is_reject() {
+                i += 1;
                 continue;
             }
             return scramble_alg;
@@ -64,9 +81,11 @@ pub fn generate_filtered_random_move_scramble<
     ScrambleFinder: RandomMoveScrambleFinder + 'static + Sync + Send,
 >(
     scramble_options: &ScrambleFinder::ScrambleOptions,
+    derivation_seed: DerivationSeed,
 ) -> Alg {
     RandomMoveScrambleFinderCacher::generate_filtered_random_move_scramble::<ScrambleFinder>(
         scramble_options,
+        derivation_seed,
     )
 }
 
@@ -107,9 +126,11 @@ impl RandomMoveScrambleFinderCacher {
         ScrambleFinder: RandomMoveScrambleFinder + 'static + Sync + Send,
     >(
         scramble_options: &ScrambleFinder::ScrambleOptions,
+        derivation_seed: DerivationSeed,
     ) -> Alg {
         RandomMoveScrambleFinderCacher::map(|scramble_finder: &mut ScrambleFinder| {
-            scramble_finder.generate_filtered_random_move_scramble(scramble_options)
+            scramble_finder
+                .generate_filtered_random_move_scramble(scramble_options, derivation_seed)
         })
     }
 
@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use cubing::{
+    alg::Alg,
+    kpuzzle::{KPattern, KPuzzle},
+};
+
+use crate::{
+    _internal::schreier_sims::reachability::{check_reachability, ReachabilityCheck},
+    scramble::{
+        derive_scramble_for_event::DerivationSeedRng, scramble_search::move_list_from_vec,
+        DerivationSeed,
+    },
+};
+
+use super::{
+    scramble_finder::ScrambleFinder, solving_based_scramble_finder::SolvingBasedScrambleFinder,
+};
+
+// An arbitrary fixed seed, so that test failures are reproducible.
+const TEST_DERIVATION_SEED: &str =
+    "67002dfc95e6d4288f418fbaa9150aa65b239fd5581f2d067d0293b9321a8b67";
+
+/// Checks that the fair patterns derived by the scramble finder can be
+/// reached using the puzzle's scramble moves.
+pub(crate) fn check_fair_patterns_are_reachable<
+    TScrambleFinder: SolvingBasedScrambleFinder + ScrambleFinder<TPuzzle = KPuzzle>,
+>(
+    kpuzzle: &KPuzzle,
+    moves: Vec<&str>,
+    scramble_options: &TScrambleFinder::ScrambleOptions,
+    num_patterns: usize,
+) -> Result<(), String> {
+    let mut scramble_finder = TScrambleFinder::default();
+    let mut rng = DerivationSeedRng::new(DerivationSeed::from_str(TEST_DERIVATION_SEED)?);
+    let moves = move_list_from_vec(moves);
+    for _ in 0..num_patterns {
+        let pattern = scramble_finder.derive_fair_unfiltered_pattern(scramble_options, &mut rng);
+        check_reachability(
+            kpuzzle,
+            &moves,
+            &pattern,
+            &[kpuzzle.default_pattern()],
+            ReachabilityCheck::Always,
+        )
+        .map_err(|e| e.description)?;
+    }
+    Ok(())
+}
+
+/// Checks that the scramble finder solves the pattern for `scramble`.
+pub(crate) fn check_solves_scramble<
+    TScrambleFinder: SolvingBasedScrambleFinder + ScrambleFinder<TPuzzle = KPuzzle>,
+>(
+    kpuzzle: &KPuzzle,
+    scramble: &Alg,
+    scramble_options: &TScrambleFinder::ScrambleOptions,
+) -> Result<(), String> {
+    let pattern: KPattern = kpuzzle
+        .default_pattern()
+        .apply_alg(scramble)
+        .map_err(|e| e.to_string())?;
+    let solution = TScrambleFinder::default()
+        .solve_pattern(&pattern, scramble_options)
+        .map_err(|e| e.description)?;
+    assert_eq!(
+        pattern.apply_alg(&solution).map_err(|e| e.to_string())?,
+        kpuzzle.default_pattern()
+    );
+    Ok(())
+}
@@ -2,10 +2,13 @@ import { Alg } from "cubing/alg";
 import type { KPattern, KPuzzleDefinition } from "cubing/kpuzzle";
 import {
   default as init,
+  wasmDeriveMultiBlindScrambleSet as rawWasmDeriveMultiBlindScrambleSet,
   wasmDeriveScrambleForEvent as rawWasmDeriveScrambleForEvent,
   wasmFreeMemoryForAllScrambleFinders as rawWasmFreeMemoryForAllScrambleFinders,
+  wasmRandomMultiBlindScrambleSet as rawWasmRandomMultiBlindScrambleSet,
   wasmRandomScrambleForEvent as rawWasmRandomScrambleForEvent,
   wasmTwips as rawWasmTwips,
+  WasmTwipsSearch as RawWasmTwipsSearch,
 } from "../../.temp/rust-wasm/twips_wasm";
 
 let cachedInitWrapper: Promise<void> | undefined;
@@ -49,20 +52,166 @@ export async function wasmDeriveScrambleForEvent(
   );
 }
 
-export async function wasmTwips(
+export async function wasmRandomMultiBlindScrambleSet(
+  numCubes: number,
+): Promise<Alg[]> {
+  await initWrapper();
+  return (
+    JSON.parse(rawWasmRandomMultiBlindScrambleSet(numCubes)) as string[]
+  ).map((scramble) => new Alg(scramble));
+}
+
+export async function wasmDeriveMultiBlindScrambleSet(
+  hexDerivationSeed: string,
+  derivationSaltHierarchy: string[],
+  numCubes: number,
+): Promise<Alg[]> {
+  for (const derivationSalt of derivationSaltHierarchy) {
+    if (derivationSalt.includes("/")) {
+      throw new Error("Derivation salts cannot contain slashes.");
+    }
+  }
+  await initWrapper();
+  return (
+    JSON.parse(
+      rawWasmDeriveMultiBlindScrambleSet(
+        hexDerivationSeed,
+        derivationSaltHierarchy.join("/"),
+        numCubes,
+      ),
+    ) as string[]
+  ).map((scramble) => new Alg(scramble));
+}
+
+/** From `WasmTwipsSearch.checkpoint()`. */
+export interface WasmTwipsSearchCheckpoint {
+  depth: number;
+  continuationCondition: "none" | { at: string[] } | { after: string[] };
+}
+
+export interface WasmTwipsOptions {
+  minDepth?: number;
+  maxDepth?: number;
+  minNumSolutions?: number;
+  /** Stops the search after this many seconds. */
+  timeoutSeconds?: number;
+  /** Stops the search after visiting this many nodes. */
+  maxNodes?: number;
+  generatorMoves?: string[];
+  /** Each alg is used as a single move during search, and expanded in solutions. */
+  generatorAlgs?: string[];
+  /** Solutions are found for reaching any of these patterns. */
+  targetPatterns?: KPattern[];
+  /** Resumes a search from a checkpoint. This overrides `minDepth`. */
+  resume?: WasmTwipsSearchCheckpoint;
+}
+
+function serializeWasmTwipsArgs(
   kpuzzleDefinition: KPuzzleDefinition,
   searchPattern: KPattern,
-  options?: { minDepth?: number },
-): Promise<Alg> {
+  options?: WasmTwipsOptions,
+): [string, string, string] {
+  const { targetPatterns, ...otherOptions } = options ?? {};
+  return [
+    JSON.stringify(kpuzzleDefinition),
+    // biome-ignore lint/complexity/useLiteralKeys: JSON field access
+    JSON.stringify(searchPattern.toJSON()["patternData"]),
+    JSON.stringify({
+      ...otherOptions,
+      targetPatterns: targetPatterns?.map(
+        // biome-ignore lint/complexity/useLiteralKeys: JSON field access
+        (targetPattern) => targetPattern.toJSON()["patternData"],
+      ),
+    }),
+  ];
+}
+
+/** Returns `options.minNumSolutions` solutions (defaulting to 1). */
+export async function wasmTwipsSolutions(
+  kpuzzleDefinition: KPuzzleDefinition,
+  searchPattern: KPattern,
+  options?: WasmTwipsOptions,
+): Promise<Alg[]> {
   await initWrapper();
-  return new Alg(
+  const solutions: string[] = JSON.parse(
     rawWasmTwips(
-      JSON.stringify(kpuzzleDefinition),
-      // biome-ignore lint/complexity/useLiteralKeys: JSON field access
-      JSON.stringify(searchPattern.toJSON()["patternData"]),
-      JSON.stringify(options),
+      ...serializeWasmTwipsArgs(kpuzzleDefinition, searchPattern, options),
     ),
   );
+  return solutions.map((solution) => new Alg(solution));
+}
+
+/** Returns the first solution. */
+export async function wasmTwips(
+  kpuzzleDefinition: KPuzzleDefinition,
+  searchPattern: KPattern,
+  options?: Omit<WasmTwipsOptions, "minNumSolutions">,
+): Promise<Alg> {
+  return (await wasmTwipsSolutions(kpuzzleDefinition, searchPattern, options))[0];
+}
+
+/**
+ * An incremental search. Solutions are only searched for when requested, and
+ * the prune table is kept between solutions.
+ *
+ * If `minNumSolutions` is not specified, the search continues until all
+ * solutions have been found (which is often never, unless `maxDepth` is also
+ * specified).
+ *
+ * Call `.free()` when done, to release the memory for the prune table.
+ */
+export class WasmTwipsSearch implements Iterable<Alg> {
+  private constructor(private rawSearch: RawWasmTwipsSearch) {}
+
+  static async create(
+    kpuzzleDefinition: KPuzzleDefinition,
+    searchPattern: KPattern,
+    options?: WasmTwipsOptions,
+  ): Promise<WasmTwipsSearch> {
+    await initWrapper();
+    return new WasmTwipsSearch(
+      new RawWasmTwipsSearch(
+        ...serializeWasmTwipsArgs(kpuzzleDefinition, searchPattern, options),
+      ),
+    );
+  }
+
+  /** Returns `undefined` once there are no more solutions. */
+  next(): Alg | undefined {
+    const solution = this.rawSearch.next();
+    return solution === undefined ? undefined : new Alg(solution);
+  }
+
+  /**
+   * Returns `undefined` while the search may still return more solutions.
+   * Otherwise, this is one of: `"exhausted"`, `"maxDepthReached"`,
+   * `"minNumSolutionsReached"`, `"cancelled"`, `"timeLimitReached"`,
+   * `"nodeLimitReached"`.
+   */
+  terminationReason(): string | undefined {
+    return this.rawSearch.terminationReason();
+  }
+
+  /**
+   * Pass this as the `resume` option of a new search (with the same puzzle,
+   * pattern, and other options) to continue after the solutions returned so
+   * far.
+   */
+  checkpoint(): WasmTwipsSearchCheckpoint {
+    return JSON.parse(this.rawSearch.checkpoint());
+  }
+
+  *[Symbol.iterator](): Iterator<Alg> {
+    let solution: Alg | undefined;
+    // biome-ignore lint/suspicious/noAssignInExpressions: This is an iteration pattern.
+    while ((solution = this.next())) {
+      yield solution;
+    }
+  }
+
+  free(): void {
+    this.rawSearch.free();
+  }
 }
 
 export function wasmFreeMemoryForAllScrambleFinders(): number {
@@ -1,18 +1,20 @@
 use std::str::FromStr;
 
-use cubing::alg::Move;
+use cubing::alg::{Alg, Move};
 use cubing::kpuzzle::{KPattern, KPatternData, KPuzzle};
 use serde::{Deserialize, Serialize};
-use twips::_internal::canonical_fsm::search_generators::Generators;
+use twips::_internal::canonical_fsm::search_generators::{Generators, KPuzzleWithGeneratorMoves};
 use twips::_internal::search::iterative_deepening::individual_search::IndividualSearchOptions;
 use twips::_internal::search::iterative_deepening::iterative_deepening_search::{
-    ImmutableSearchData, IterativeDeepeningSearch,
+    ImmutableSearchData, IterativeDeepeningSearch, OwnedIterativeDeepeningSearchCursor,
 };
+use twips::_internal::search::iterative_deepening::search_checkpoint::SearchCheckpoint;
 use twips::scramble::scramble_finder::free_memory_for_all_scramble_finders;
 use wasm_bindgen::prelude::*;
 
 use twips::scramble::{
-    derive_scramble_for_event_seeded, random_scramble_for_event, DerivationSalt, DerivationSeed,
+    derive_multi_blind_scramble_set_seeded, derive_scramble_for_event_seeded,
+    random_multi_blind_scramble_set, random_scramble_for_event, DerivationSalt, DerivationSeed,
     Event,
 };
 
@@ -24,12 +26,99 @@ pub fn internal_init() {
 #[serde(rename_all = "camelCase")]
 pub struct WasmTwipsOptions {
     target_pattern: Option<KPatternData>,
+    /// Solutions are found for reaching any of these patterns. Cannot be combined with `targetPattern`.
+    target_patterns: Option<Vec<KPatternData>>,
     generator_moves: Option<Vec<Move>>,
+    /// Each alg is used as a single move during search, and expanded in solutions.
+    generator_algs: Option<Vec<Alg>>,
+    /// Resume the search from a `checkpoint()` of a previous search. This overrides `minDepth`.
+    resume: Option<SearchCheckpoint>,
 
     #[serde(flatten)]
     inidividual_search_options: IndividualSearchOptions,
 }
 
+fn owned_search(
+    kpuzzle_json: &str,
+    search_pattern_json: &str,
+    options: WasmTwipsOptions,
+) -> Result<WasmTwipsSearch, String> {
+    let kpuzzle = KPuzzle::try_from_json(kpuzzle_json.as_bytes());
+    let kpuzzle = kpuzzle.map_err(|e| e.to_string())?;
+    let generators = match (options.generator_moves, options.generator_algs) {
+        // Every search can return a checkpoint, which can be passed as `resume` to a later search.
+        (None, None) => Generators::default_in_stable_order(&kpuzzle),
+        (moves, algs) => Generators::Custom {
+            moves: moves.unwrap_or_default(),
+            algs: algs.unwrap_or_default(),
+        },
+    };
+    let kpuzzle_with_generator_moves = generators
+        .kpuzzle_with_generator_moves(&kpuzzle)
+        .map_err(|e| e.description)?;
+    // Patterns for the original puzzle are also valid for the puzzle with alg generators.
+    let kpuzzle = kpuzzle_with_generator_moves.kpuzzle.clone();
+
+    let search_pattern = KPattern::try_from_json(&kpuzzle, search_pattern_json.as_bytes());
+    let search_pattern = search_pattern.map_err(|e| e.to_string())?;
+
+    // `std::thread` panics on `wasm32-unknown-unknown`.
+    if options.inidividual_search_options.get_num_threads() > 1 {
+        return Err("`numThreads` is not supported in WASM".to_owned());
+    }
+    let target_patterns_data = match (options.target_pattern, options.target_patterns) {
+        (None, None) => None,
+        (Some(target_pattern_data), None) => Some(vec![target_pattern_data]),
+        (None, Some(target_patterns_data)) => Some(target_patterns_data),
+        (Some(_), Some(_)) => {
+            return Err("`targetPattern` and `targetPatterns` cannot both be specified".to_owned())
+        }
+    };
+    let target_patterns = match target_patterns_data {
+        Some(target_patterns_data) => {
+            if target_patterns_data.is_empty() {
+                return Err("`targetPatterns` must not be empty".to_owned());
+            }
+            target_patterns_data
+                .iter()
+                .map(|target_pattern_data| KPattern::try_from_data(&kpuzzle, target_pattern_data))
+                .collect::<Result<Vec<KPattern>, _>>()
+                .map_err(|e| e.to_string())?
+        }
+        None => vec![kpuzzle.default_pattern()],
+    };
+    let iterative_deepening_search = <IterativeDeepeningSearch<KPuzzle>>::new_with_hash_prune_table(
+        ImmutableSearchData::try_from_common_options_with_auto_search_generators(
+            kpuzzle,
+            kpuzzle_with_generator_moves.generator_moves.clone(),
+            target_patterns,
+            Default::default(),
+        )
+        .map_err(|e| e.description)?,
+        Default::default(), // StoredSearchAdaptations::default(),
+        Default::default(), // HashPruneTableSizeBounds::default(),
+    );
+
+    let mut individual_search_options = options.inidividual_search_options;
+    if let Some(checkpoint) = options.resume {
+        checkpoint.resume(&mut individual_search_options);
+    }
+    Ok(WasmTwipsSearch {
+        cursor: iterative_deepening_search.owned_search(
+            &search_pattern,
+            individual_search_options,
+            Default::default(),
+        ),
+        kpuzzle_with_generator_moves,
+    })
+}
+
+fn parse_options(options_json: &str) -> Result<WasmTwipsOptions, String> {
+    serde_json::from_slice(options_json.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Returns a JSON array of solutions. Unlike `WasmTwipsSearch`, this returns a
+/// single solution if `minNumSolutions` is not specified.
 #[wasm_bindgen]
 #[allow(non_snake_case)]
 pub fn wasmTwips(
@@ -39,62 +128,76 @@ pub fn wasmTwips(
 ) -> Result<String, String> {
     internal_init();
 
-    let kpuzzle = KPuzzle::try_from_json(kpuzzle_json.as_bytes());
-    let kpuzzle = kpuzzle.map_err(|e| e.to_string())?;
-
-    let search_pattern = KPattern::try_from_json(&kpuzzle, search_pattern_json.as_bytes());
-    let search_pattern = search_pattern.map_err(|e| e.to_string())?;
-
-    let options: WasmTwipsOptions = match serde_json::from_slice(options_json.as_bytes()) {
-        Ok(options) => options,
-        Err(e) => return Err(e.to_string()),
-    };
-    if options
+    let mut options = parse_options(&options_json)?;
+    let num_solutions = *options
         .inidividual_search_options
         .min_num_solutions
-        .is_some()
-    {
-        return Err("`minNumSolutions` is not implemented yet".to_owned());
+        .get_or_insert(1);
+    let mut search = owned_search(&kpuzzle_json, &search_pattern_json, options)?;
+    let solutions: Vec<String> = std::iter::from_fn(|| search.next())
+        .take(num_solutions)
+        .collect();
+    if solutions.is_empty() {
+        return Err(match search.cursor.termination_reason() {
+            Some(termination_reason) if termination_reason.is_interrupted() => {
+                format!("No solution found ({})!", termination_reason)
+            }
+            _ => "No solution found!".to_owned(),
+        });
     }
+    Ok(serde_json::to_string(&solutions).expect("Could not serialize solutions."))
+}
 
-    let target_pattern = match options.target_pattern {
-        Some(target_pattern_data) => {
-            let target_pattern = KPattern::try_from_data(&kpuzzle, &target_pattern_data);
-            target_pattern.map_err(|e| e.to_string())?
-        }
-        None => kpuzzle.default_pattern(),
-    };
-    let generators = match options.generator_moves {
-        Some(generator_moves) => Generators::Custom {
-            moves: generator_moves,
-            algs: vec![],
-        },
-        None => Generators::Default,
-    };
+/// An incremental search. Solutions are only searched for when requested, and
+/// the prune table is kept between calls.
+///
+/// If `minNumSolutions` is not specified, the search continues until all
+/// solutions have been found (which is often never, unless `maxDepth` is also
+/// specified).
+#[wasm_bindgen]
+pub struct WasmTwipsSearch {
+    cursor: OwnedIterativeDeepeningSearchCursor,
+    kpuzzle_with_generator_moves: KPuzzleWithGeneratorMoves,
+}
 
-    let mut iterative_deepening_search =
-        <IterativeDeepeningSearch<KPuzzle>>::new_with_hash_prune_table(
-            ImmutableSearchData::try_from_common_options_with_auto_search_generators(
-                kpuzzle.clone(),
-                generators.enumerate_moves_for_kpuzzle(&kpuzzle),
-                vec![target_pattern], // TODO: support multiple target patterns.
-                Default::default(),
-            )
-            .map_err(|e| e.description)?,
-            Default::default(), // StoredSearchAdaptations::default(),
-            Default::default(), // HashPruneTableSizeBounds::default(),
-        );
-
-    match iterative_deepening_search
-        .search(
-            &search_pattern,
-            options.inidividual_search_options,
-            Default::default(),
-        )
-        .next()
-    {
-        Some(alg) => Ok(alg.to_string().to_owned()),
-        None => Err("No solution found!".to_owned()),
+#[wasm_bindgen]
+impl WasmTwipsSearch {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        kpuzzle_json: String,
+        search_pattern_json: String,
+        options_json: String,
+    ) -> Result<WasmTwipsSearch, String> {
+        internal_init();
+
+        let options = parse_options(&options_json)?;
+        owned_search(&kpuzzle_json, &search_pattern_json, options)
+    }
+
+    /// Returns `undefined` once there are no more solutions.
+    #[allow(clippy::should_implement_trait)] // `Iterator` is not exposed to JS.
+    pub fn next(&mut self) -> Option<String> {
+        self.cursor.next().map(|alg| {
+            self.kpuzzle_with_generator_moves
+                .expand_alg_generators(&alg)
+                .to_string()
+        })
+    }
+
+    /// Returns `undefined` while the search may still return more solutions.
+    /// Otherwise, this is a string like `"exhausted"` or `"timeLimitReached"`.
+    #[wasm_bindgen(js_name = terminationReason)]
+    pub fn termination_reason(&self) -> Option<String> {
+        let termination_reason = self.cursor.termination_reason()?;
+        let value = serde_json::to_value(termination_reason)
+            .expect("Could not serialize termination reason.");
+        value.as_str().map(str::to_owned)
+    }
+
+    /// Returns a JSON checkpoint that can be passed as the `resume` option to
+    /// continue after the solutions returned so far.
+    pub fn checkpoint(&self) -> String {
+        serde_json::to_string(self.cursor.checkpoint()).expect("Could not serialize checkpoint.")
     }
 }
 
@@ -121,14 +224,7 @@ pub fn wasmDeriveScrambleForEvent(
     internal_init();
 
     let derivation_seed = DerivationSeed::from_str(&hex_derivation_seed_str)?;
-    let hierarchy = if derivation_salt_hierarchy_str.is_empty() {
-        vec![]
-    } else {
-        derivation_salt_hierarchy_str
-            .split("/")
-            .map(DerivationSalt::from_str)
-            .collect::<Result<Vec<DerivationSalt>, String>>()?
-    };
+    let hierarchy = parse_derivation_salt_hierarchy(&derivation_salt_hierarchy_str)?;
     let subevent = Event::try_from(subevent_str.as_str()).map_err(|e| e.description)?;
     match derive_scramble_for_event_seeded(&derivation_seed, &hierarchy, subevent) {
         Ok(scramble) => Ok(scramble.to_string()),
@@ -136,6 +232,52 @@ pub fn wasmDeriveScrambleForEvent(
     }
 }
 
+fn parse_derivation_salt_hierarchy(
+    // Blank string or a slash-separated hierarchy
+    derivation_salt_hierarchy_str: &str,
+) -> Result<Vec<DerivationSalt>, String> {
+    if derivation_salt_hierarchy_str.is_empty() {
+        return Ok(vec![]);
+    }
+    derivation_salt_hierarchy_str
+        .split("/")
+        .map(DerivationSalt::from_str)
+        .collect()
+}
+
+fn scramble_set_to_json(scramble_set: Vec<Alg>) -> String {
+    let scramble_strings: Vec<String> = scramble_set.iter().map(|alg| alg.to_string()).collect();
+    serde_json::to_string(&scramble_strings).expect("Could not serialize scrambles.")
+}
+
+/// Returns a JSON array of scrambles.
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn wasmRandomMultiBlindScrambleSet(num_cubes: u32) -> Result<String, String> {
+    internal_init();
+
+    random_multi_blind_scramble_set(num_cubes as usize)
+        .map(scramble_set_to_json)
+        .map_err(|e| e.description)
+}
+
+/// Returns a JSON array of scrambles.
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn wasmDeriveMultiBlindScrambleSet(
+    hex_derivation_seed_str: String,
+    // A slash-separated hierarchy that ends at the attempt level.
+    derivation_salt_hierarchy_str: String,
+    num_cubes: u32,
+) -> Result<String, String> {
+    internal_init();
+
+    let derivation_seed = DerivationSeed::from_str(&hex_derivation_seed_str)?;
+    let hierarchy = parse_derivation_salt_hierarchy(&derivation_salt_hierarchy_str)?;
+    derive_multi_blind_scramble_set_seeded(&derivation_seed, &hierarchy, num_cubes as usize)
+        .map(scramble_set_to_json)
+}
+
 #[wasm_bindgen]
 #[allow(non_snake_case)]
 pub extern "C" fn wasmFreeMemoryForAllScrambleFinders() -> u32 {